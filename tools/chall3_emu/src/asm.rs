@@ -0,0 +1,1004 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use crate::error::Error;
+use crate::gb::{
+    GameboyInstruction, GameboyInstructionCondition, GameboyInstructionFamily, GameboyInstructionOperand,
+    GameboyInstructionPointerOp, GameboyNamedRegister16, GameboyNamedRegister8, InstructionFetch,
+};
+use crate::symbols::SymbolTable;
+
+/// Bytes assembled from a snippet of SM83 assembly.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Assembly {
+    /// Address the first byte was assembled for.
+    pub origin: u16,
+    pub bytes: Vec<u8>,
+    /// Labels defined by the snippet, with their address.
+    pub labels: Vec<(String, u16)>,
+    /// The source with the address and bytes of every line.
+    pub listing: String,
+}
+
+/// Decodes opcodes without operands, the operands read as zero.
+struct ZeroFetch;
+
+impl InstructionFetch for ZeroFetch {
+    fn fetch(&mut self) -> u8 {
+        0x00
+    }
+}
+
+/// An instruction the CPU decodes, with the opcode bytes it starts with.
+type Template = (Vec<u8>, GameboyInstruction);
+
+/// Every instruction the CPU decodes.
+fn instruction_templates() -> Vec<Template> {
+    let mut templates = Vec::new();
+
+    for opcode in 0..=0xFF {
+        if opcode == 0xCB {
+            continue;
+        }
+
+        let instruction = GameboyInstruction::decode(&mut ZeroFetch, opcode);
+
+        if instruction.instruction_family() != GameboyInstructionFamily::ILLEGAL {
+            templates.push((vec![opcode], instruction));
+        }
+    }
+
+    for opcode in 0..=0xFF {
+        templates.push((vec![0xCB, opcode], GameboyInstruction::decode_prefix(opcode)));
+    }
+
+    templates
+}
+
+/// An operand as written in the source. Expressions are kept as text until every label is known.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Operand {
+    Register8(GameboyNamedRegister8),
+    Register16(GameboyNamedRegister16),
+    Condition(GameboyInstructionCondition),
+    /// `[bc]`, `[de]` or `[hl]`.
+    Pointer(GameboyNamedRegister16),
+    /// `[hli]` or `[hl+]`.
+    PointerIncrement,
+    /// `[hld]` or `[hl-]`.
+    PointerDecrement,
+    /// `[c]` or `[$FF00+c]`.
+    HighPointer,
+    /// `[expression]`.
+    Memory(String),
+    /// `sp+expression` or `sp-expression`, with the sign kept in the expression.
+    StackPointerOffset(String),
+    Expression(String),
+}
+
+fn register8(name: &str) -> Option<GameboyNamedRegister8> {
+    match name {
+        "a" => Some(GameboyNamedRegister8::A),
+        "b" => Some(GameboyNamedRegister8::B),
+        "c" => Some(GameboyNamedRegister8::C),
+        "d" => Some(GameboyNamedRegister8::D),
+        "e" => Some(GameboyNamedRegister8::E),
+        "h" => Some(GameboyNamedRegister8::H),
+        "l" => Some(GameboyNamedRegister8::L),
+        _ => None,
+    }
+}
+
+fn register16(name: &str) -> Option<GameboyNamedRegister16> {
+    match name {
+        "af" => Some(GameboyNamedRegister16::AF),
+        "bc" => Some(GameboyNamedRegister16::BC),
+        "de" => Some(GameboyNamedRegister16::DE),
+        "hl" => Some(GameboyNamedRegister16::HL),
+        "sp" => Some(GameboyNamedRegister16::SP),
+        _ => None,
+    }
+}
+
+fn condition(name: &str) -> Option<GameboyInstructionCondition> {
+    match name {
+        "nz" => Some(GameboyInstructionCondition::NZ),
+        "z" => Some(GameboyInstructionCondition::Z),
+        "nc" => Some(GameboyInstructionCondition::NC),
+        "c" => Some(GameboyInstructionCondition::C),
+        _ => None,
+    }
+}
+
+fn parse_operand(text: &str) -> Operand {
+    let lower = text.to_lowercase();
+    let compact = lower.split_whitespace().collect::<String>();
+
+    if let Some(register) = register8(&compact) {
+        return Operand::Register8(register);
+    }
+
+    if let Some(register) = register16(&compact) {
+        return Operand::Register16(register);
+    }
+
+    if let Some(condition) = condition(&compact) {
+        return Operand::Condition(condition);
+    }
+
+    if let Some(inner) = compact.strip_prefix('[').and_then(|inner| inner.strip_suffix(']')) {
+        return match inner {
+            "bc" => Operand::Pointer(GameboyNamedRegister16::BC),
+            "de" => Operand::Pointer(GameboyNamedRegister16::DE),
+            "hl" => Operand::Pointer(GameboyNamedRegister16::HL),
+            "hli" | "hl+" => Operand::PointerIncrement,
+            "hld" | "hl-" => Operand::PointerDecrement,
+            "c" | "$ff00+c" => Operand::HighPointer,
+            _ => {
+                let inner = text.trim();
+                Operand::Memory(inner[1..inner.len() - 1].to_owned())
+            },
+        };
+    }
+
+    if compact.starts_with("sp+") || compact.starts_with("sp-") {
+        let offset = text.trim()[2..].trim_start();
+        return Operand::StackPointerOffset(offset.to_owned());
+    }
+
+    Operand::Expression(text.trim().to_owned())
+}
+
+/// Splits operands on the commas that aren't in brackets, parentheses or strings.
+fn split_operands(text: &str) -> Vec<String> {
+    let mut operands = Vec::new();
+    let mut current = String::new();
+    let mut depth = 0;
+    let mut in_string = false;
+    let mut escaped = false;
+
+    for character in text.chars() {
+        if in_string {
+            current.push(character);
+
+            if escaped {
+                escaped = false;
+            } else if character == '\\' {
+                escaped = true;
+            } else if character == '"' {
+                in_string = false;
+            }
+
+            continue;
+        }
+
+        match character {
+            '"' => in_string = true,
+            '(' | '[' => depth += 1,
+            ')' | ']' => depth -= 1,
+            ',' if depth == 0 => {
+                operands.push(current.trim().to_owned());
+                current.clear();
+                continue;
+            },
+            _ => {},
+        }
+
+        current.push(character);
+    }
+
+    if !current.trim().is_empty() {
+        operands.push(current.trim().to_owned());
+    }
+
+    operands
+}
+
+/// Strips a `;` comment that isn't in a string.
+fn strip_comment(line: &str) -> &str {
+    let mut in_string = false;
+    let mut escaped = false;
+
+    for (index, character) in line.char_indices() {
+        if in_string {
+            if escaped {
+                escaped = false;
+            } else if character == '\\' {
+                escaped = true;
+            } else if character == '"' {
+                in_string = false;
+            }
+        } else if character == '"' {
+            in_string = true;
+        } else if character == ';' {
+            return &line[..index];
+        }
+    }
+
+    line
+}
+
+/// Splits a line on the `::` separating statements, which has a space before it unlike the `::` of exported labels.
+fn split_statements(line: &str) -> Vec<&str> {
+    let mut statements = Vec::new();
+    let mut start = 0;
+    let mut in_string = false;
+    let mut escaped = false;
+    let mut previous = ' ';
+
+    for (index, character) in line.char_indices() {
+        if in_string {
+            if escaped {
+                escaped = false;
+            } else if character == '\\' {
+                escaped = true;
+            } else if character == '"' {
+                in_string = false;
+            }
+        } else if character == '"' {
+            in_string = true;
+        } else if index >= start && line[index..].starts_with("::") && previous.is_whitespace() {
+            statements.push(&line[start..index]);
+            start = index + 2;
+        }
+
+        previous = character;
+    }
+
+    statements.push(&line[start..]);
+    statements
+}
+
+fn parse_string(text: &str) -> Option<Result<Vec<u8>, String>> {
+    let inner = text.strip_prefix('"')?.strip_suffix('"')?;
+    let mut bytes = Vec::new();
+    let mut characters = inner.chars();
+
+    while let Some(character) = characters.next() {
+        let character = if character == '\\' {
+            match characters.next() {
+                Some('n') => '\n',
+                Some('r') => '\r',
+                Some('t') => '\t',
+                Some('0') => '\0',
+                Some('\\') => '\\',
+                Some('"') => '"',
+                other => return Some(Err(format!("invalid escape sequence \\{}", other.unwrap_or(' ')))),
+            }
+        } else {
+            character
+        };
+
+        let mut buffer = [0; 4];
+        bytes.extend_from_slice(character.encode_utf8(&mut buffer).as_bytes());
+    }
+
+    Some(Ok(bytes))
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(i64),
+    Name(String),
+    /// `@`, the address of the current line.
+    Current,
+    Operator(&'static str),
+}
+
+fn tokenize(text: &str) -> Result<Vec<Token>, String> {
+    const OPERATORS: [&str; 14] = ["<<", ">>", "+", "-", "*", "/", "%", "&", "|", "^", "~", "(", ")", ","];
+
+    let mut tokens = Vec::new();
+    let mut rest = text.trim_start();
+
+    while let Some(character) = rest.chars().next() {
+        if let Some(digits) = rest.strip_prefix('$').or_else(|| rest.strip_prefix("0x")) {
+            let length = digits.find(|c: char| !c.is_ascii_hexdigit()).unwrap_or(digits.len());
+            let value = i64::from_str_radix(&digits[..length], 16).map_err(|_| format!("invalid number {}", rest))?;
+            tokens.push(Token::Number(value));
+            rest = &digits[length..];
+        } else if let Some(digits) = rest.strip_prefix('%').filter(|digits| digits.starts_with(['0', '1'])) {
+            let length = digits.find(|c: char| c != '0' && c != '1').unwrap_or(digits.len());
+            let value = i64::from_str_radix(&digits[..length], 2).map_err(|_| format!("invalid number {}", rest))?;
+            tokens.push(Token::Number(value));
+            rest = &digits[length..];
+        } else if character.is_ascii_digit() {
+            let length = rest.find(|c: char| !c.is_ascii_digit()).unwrap_or(rest.len());
+            let value = rest[..length].parse::<i64>().map_err(|_| format!("invalid number {}", rest))?;
+            tokens.push(Token::Number(value));
+            rest = &rest[length..];
+        } else if character == '"' {
+            // A one-character string is its character code, as in `cp "0"`
+            let end = rest[1..].find('"').ok_or("unterminated string")? + 2;
+            let bytes = parse_string(&rest[..end]).ok_or("invalid string")??;
+
+            if bytes.len() != 1 {
+                return Err(format!("{} isn't a single character", &rest[..end]));
+            }
+
+            tokens.push(Token::Number(bytes[0] as i64));
+            rest = &rest[end..];
+        } else if character.is_ascii_alphabetic() || character == '_' || character == '.' {
+            let length = rest
+                .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '.' || c == '#'))
+                .unwrap_or(rest.len());
+            tokens.push(Token::Name(rest[..length].to_owned()));
+            rest = &rest[length..];
+        } else if character == '@' {
+            tokens.push(Token::Current);
+            rest = &rest[1..];
+        } else if let Some(operator) = OPERATORS.into_iter().find(|operator| rest.starts_with(operator)) {
+            tokens.push(Token::Operator(operator));
+            rest = &rest[operator.len()..];
+        } else {
+            return Err(format!("unexpected character {:?}", character));
+        }
+
+        rest = rest.trim_start();
+    }
+
+    Ok(tokens)
+}
+
+/// Evaluates expressions with labels, symbols, `@`, `HIGH()`, `LOW()` and the usual integer operators.
+struct Evaluator<'a> {
+    tokens: Vec<Token>,
+    position: usize,
+    context: &'a Context<'a>,
+}
+
+/// What names in expressions resolve to.
+struct Context<'a> {
+    labels: &'a HashMap<String, u16>,
+    symbols: &'a SymbolTable,
+    /// Global label local labels are relative to.
+    scope: Option<&'a str>,
+    current: u16,
+}
+
+impl Context<'_> {
+    fn full_name(&self, name: &str) -> String {
+        match (name.starts_with('.'), self.scope) {
+            (true, Some(scope)) => format!("{}{}", scope, name),
+            _ => name.to_owned(),
+        }
+    }
+
+    fn resolve(&self, name: &str) -> Result<i64, String> {
+        let name = self.full_name(name);
+
+        if let Some(&address) = self.labels.get(&name) {
+            return Ok(address as i64);
+        }
+
+        match self.symbols.get(&name) {
+            Some(symbol) => Ok(symbol.address as i64),
+            None => Err(format!("unknown symbol {}", name)),
+        }
+    }
+
+    fn evaluate(&self, text: &str) -> Result<i64, String> {
+        let mut evaluator = Evaluator {
+            tokens: tokenize(text)?,
+            position: 0,
+            context: self,
+        };
+
+        let value = evaluator.binary(0)?;
+
+        match evaluator.tokens.get(evaluator.position) {
+            None => Ok(value),
+            Some(token) => Err(format!("unexpected {:?} in expression {:?}", token, text)),
+        }
+    }
+}
+
+impl Evaluator<'_> {
+    /// Binary operators from the loosest to the tightest.
+    const PRECEDENCE: [&'static [&'static str]; 6] =
+        [&["|"], &["^"], &["&"], &["<<", ">>"], &["+", "-"], &["*", "/", "%"]];
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn peek_operator(&self) -> Option<&'static str> {
+        match self.tokens.get(self.position) {
+            Some(Token::Operator(operator)) => Some(operator),
+            _ => None,
+        }
+    }
+
+    fn expect(&mut self, operator: &str) -> Result<(), String> {
+        match self.next() {
+            Some(Token::Operator(found)) if found == operator => Ok(()),
+            _ => Err(format!("expected {:?}", operator)),
+        }
+    }
+
+    fn binary(&mut self, level: usize) -> Result<i64, String> {
+        if level == Evaluator::PRECEDENCE.len() {
+            return self.unary();
+        }
+
+        let mut value = self.binary(level + 1)?;
+
+        while let Some(operator) =
+            self.peek_operator().filter(|operator| Evaluator::PRECEDENCE[level].contains(operator))
+        {
+            self.position += 1;
+            let right = self.binary(level + 1)?;
+
+            value = match operator {
+                "|" => value | right,
+                "^" => value ^ right,
+                "&" => value & right,
+                "<<" => value.checked_shl(right as u32).unwrap_or(0),
+                ">>" => value.checked_shr(right as u32).unwrap_or(0),
+                "+" => value.wrapping_add(right),
+                "-" => value.wrapping_sub(right),
+                "*" => value.wrapping_mul(right),
+                "/" => value.checked_div(right).ok_or("division by zero")?,
+                _ => value.checked_rem(right).ok_or("division by zero")?,
+            };
+        }
+
+        Ok(value)
+    }
+
+    fn unary(&mut self) -> Result<i64, String> {
+        match self.next() {
+            Some(Token::Operator("-")) => Ok(self.unary()?.wrapping_neg()),
+            Some(Token::Operator("+")) => self.unary(),
+            Some(Token::Operator("~")) => Ok(!self.unary()?),
+            Some(Token::Operator("(")) => {
+                let value = self.binary(0)?;
+                self.expect(")")?;
+                Ok(value)
+            },
+            Some(Token::Number(value)) => Ok(value),
+            Some(Token::Current) => Ok(self.context.current as i64),
+            Some(Token::Name(name)) if self.peek_operator() == Some("(") => {
+                self.expect("(")?;
+                let value = self.binary(0)?;
+                self.expect(")")?;
+
+                match name.to_lowercase().as_str() {
+                    "high" => Ok((value >> 8) & 0xFF),
+                    "low" => Ok(value & 0xFF),
+                    _ => Err(format!("unknown function {}", name)),
+                }
+            },
+            Some(Token::Name(name)) => self.context.resolve(&name),
+            Some(token) => Err(format!("unexpected {:?}", token)),
+            None => Err("unexpected end of expression".to_owned()),
+        }
+    }
+}
+
+/// What a line of source turns into.
+#[derive(Debug)]
+enum Statement {
+    Instruction {
+        /// Opcode bytes, followed by the operand of `template`.
+        opcode: Vec<u8>,
+        family: GameboyInstructionFamily,
+        /// The operand of the template the immediate goes into, if it has one.
+        immediate: Option<(GameboyInstructionOperand, String)>,
+        size: usize,
+    },
+    Bytes(Vec<DataItem>),
+    Words(Vec<String>),
+    Space {
+        count: usize,
+        fill: Option<String>,
+    },
+}
+
+#[derive(Debug)]
+enum DataItem {
+    String(Vec<u8>),
+    Expression(String),
+}
+
+impl Statement {
+    fn size(&self) -> usize {
+        match self {
+            Statement::Instruction { size, .. } => *size,
+            Statement::Bytes(items) => items
+                .iter()
+                .map(|item| match item {
+                    DataItem::String(bytes) => bytes.len(),
+                    DataItem::Expression(_) => 1,
+                })
+                .sum(),
+            Statement::Words(words) => words.len() * 2,
+            Statement::Space { count, .. } => *count,
+        }
+    }
+}
+
+/// Returns whether a source operand can be encoded as an operand of the template. Values are only compared for
+/// operands that select the opcode, the bit of BIT/RES/SET and the vector of RST, which can't use labels defined
+/// further down.
+fn operand_matches(
+    family: GameboyInstructionFamily,
+    template: GameboyInstructionOperand,
+    operand: &Operand,
+    evaluate: &dyn Fn(&str) -> Option<i64>,
+) -> bool {
+    match (template, operand) {
+        (GameboyInstructionOperand::Register8(expected), Operand::Register8(register)) => expected == *register,
+        (GameboyInstructionOperand::Register16(expected), Operand::Register16(register)) => expected == *register,
+        (GameboyInstructionOperand::Pointer(expected, None), Operand::Pointer(register)) => expected == *register,
+        (GameboyInstructionOperand::Pointer(_, Some(GameboyInstructionPointerOp::Increment)), Operand::PointerIncrement) => {
+            true
+        },
+        (GameboyInstructionOperand::Pointer(_, Some(GameboyInstructionPointerOp::Decrement)), Operand::PointerDecrement) => {
+            true
+        },
+        (GameboyInstructionOperand::HighPointer(_), Operand::HighPointer) => true,
+        (GameboyInstructionOperand::Immediate8(_), Operand::Memory(_)) => family == GameboyInstructionFamily::LDH,
+        (GameboyInstructionOperand::Immediate8(expected), Operand::Expression(expression)) => match family {
+            GameboyInstructionFamily::LDH => false,
+            GameboyInstructionFamily::BIT | GameboyInstructionFamily::RES | GameboyInstructionFamily::SET => {
+                evaluate(expression) == Some(expected as i64)
+            },
+            _ => true,
+        },
+        (GameboyInstructionOperand::ImmediateSigned8(_), Operand::Expression(_)) => true,
+        (GameboyInstructionOperand::Immediate16(_), Operand::Expression(_)) => true,
+        (GameboyInstructionOperand::Address(expected), Operand::Expression(expression)) => match family {
+            GameboyInstructionFamily::RST => evaluate(expression) == Some(expected as i64),
+            _ => true,
+        },
+        (GameboyInstructionOperand::AddressPointer(_), Operand::Memory(_)) => true,
+        (GameboyInstructionOperand::StackPointerOffset(_), Operand::StackPointerOffset(_)) => true,
+        _ => false,
+    }
+}
+
+/// Finds the template an instruction is encoded with.
+fn find_template<'t>(
+    templates: &'t [Template],
+    mnemonic: &str,
+    mut operands: Vec<Operand>,
+    evaluate: &dyn Fn(&str) -> Option<i64>,
+) -> Result<(&'t Template, Vec<Operand>), String> {
+    let mut mnemonic = mnemonic.to_lowercase();
+
+    // Aliases for `ld [hli], a` and friends
+    match mnemonic.as_str() {
+        "ldi" | "ldd" => {
+            let pointer = if mnemonic == "ldi" { Operand::PointerIncrement } else { Operand::PointerDecrement };
+
+            for operand in operands.iter_mut() {
+                if *operand == Operand::Pointer(GameboyNamedRegister16::HL) {
+                    *operand = pointer.clone();
+                }
+            }
+
+            mnemonic = "ld".to_owned();
+        },
+        "ld" if operands.contains(&Operand::HighPointer) => mnemonic = "ldh".to_owned(),
+        _ => {},
+    }
+
+    // `add b` and `and a, b` are the same as `add a, b` and `and b`
+    let register_a = Operand::Register8(GameboyNamedRegister8::A);
+    match mnemonic.as_str() {
+        "add" | "adc" | "sub" | "sbc" if operands.len() == 1 => operands.insert(0, register_a),
+        "and" | "xor" | "or" | "cp" if operands.len() == 2 && operands[0] == register_a => {
+            operands.remove(0);
+        },
+        _ => {},
+    }
+
+    // `jp [hl]` is another way to write `jp hl`
+    if mnemonic == "jp" && operands == [Operand::Pointer(GameboyNamedRegister16::HL)] {
+        operands = vec![Operand::Register16(GameboyNamedRegister16::HL)];
+    }
+
+    let family_name = |template: &GameboyInstruction| format!("{:?}", template.instruction_family()).to_lowercase();
+
+    if !templates.iter().any(|(_, template)| family_name(template) == mnemonic) {
+        return Err(format!("unknown instruction {}", mnemonic));
+    }
+
+    let conditional = matches!(mnemonic.as_str(), "jp" | "jr" | "call" | "ret");
+
+    let found = templates.iter().find(|(_, template)| {
+        if family_name(template) != mnemonic {
+            return false;
+        }
+
+        let mut rest = operands.as_slice();
+
+        if conditional {
+            match (template.condition(), rest.first()) {
+                (Some(expected), Some(Operand::Condition(condition))) if expected == *condition => rest = &rest[1..],
+                (Some(GameboyInstructionCondition::C), Some(Operand::Register8(GameboyNamedRegister8::C))) => {
+                    rest = &rest[1..]
+                },
+                (None, _) => {},
+                _ => return false,
+            }
+        }
+
+        let expected = [template.operand1(), template.operand2()].into_iter().flatten().collect::<Vec<_>>();
+
+        expected.len() == rest.len()
+            && expected
+                .iter()
+                .zip(rest)
+                .all(|(&expected, operand)| operand_matches(template.instruction_family(), expected, operand, evaluate))
+    });
+
+    match found {
+        Some(template) => Ok((template, operands)),
+        None => Err(format!("no {} instruction takes these operands", mnemonic)),
+    }
+}
+
+/// A parsed line, waiting for the second pass.
+struct Line {
+    number: usize,
+    address: u16,
+    scope: Option<String>,
+    /// Source of the statement, without the labels, for the listing.
+    text: String,
+    labels: Vec<String>,
+    statement: Option<Statement>,
+}
+
+/// Assembles SM83 code written in RGBDS syntax for `origin`: instructions, `db`, `dw` and `ds`, global and `.local`
+/// labels, and expressions using them, the labels of `symbols` and `@`. Statements can be separated by `::` to put
+/// several on a line.
+pub fn assemble(source: &str, origin: u16, symbols: &SymbolTable) -> Result<Assembly, Error> {
+    let templates = instruction_templates();
+    let mut labels: HashMap<String, u16> = HashMap::new();
+    let mut label_order = Vec::new();
+    let mut lines = Vec::new();
+    let mut scope: Option<String> = None;
+    let mut address = origin as usize;
+
+    // First pass: define labels and size every statement
+    for (index, raw_line) in source.lines().enumerate() {
+        let number = index + 1;
+        let invalid = |reason: String| Error::InvalidAssembly(number, reason);
+
+        for part in split_statements(strip_comment(raw_line)) {
+            let mut text = part.trim();
+            let mut line_labels = Vec::new();
+
+            // Labels end with a colon, local ones may be written without when alone
+            loop {
+                let word_end = text.find(char::is_whitespace).unwrap_or(text.len());
+                let word = &text[..word_end];
+
+                let name = if let Some(name) = word.strip_suffix(':') {
+                    name.trim_end_matches(':')
+                } else if word.starts_with('.') && word_end == text.len() {
+                    word
+                } else {
+                    break;
+                };
+
+                if name.is_empty() {
+                    return Err(invalid("empty label".to_owned()));
+                }
+
+                let full_name = if let Some(local) = name.strip_prefix('.') {
+                    let parent = scope.as_ref().ok_or_else(|| invalid(format!("local label .{} has no parent", local)))?;
+                    format!("{}.{}", parent, local)
+                } else {
+                    if !name.contains('.') {
+                        scope = Some(name.to_owned());
+                    }
+                    name.to_owned()
+                };
+
+                if labels.insert(full_name.clone(), address as u16).is_some() {
+                    return Err(invalid(format!("label {} is already defined", full_name)));
+                }
+
+                label_order.push(full_name.clone());
+                line_labels.push(full_name);
+                text = text[word_end..].trim_start();
+            }
+
+            let statement = if text.is_empty() {
+                None
+            } else {
+                let context = Context {
+                    labels: &labels,
+                    symbols,
+                    scope: scope.as_deref(),
+                    current: address as u16,
+                };
+
+                Some(parse_statement(text, &templates, &context).map_err(invalid)?)
+            };
+
+            let size = statement.as_ref().map_or(0, Statement::size);
+
+            if address + size > 0x10000 {
+                return Err(invalid("the code runs past FFFFh".to_owned()));
+            }
+
+            lines.push(Line {
+                number,
+                address: address as u16,
+                scope: scope.clone(),
+                text: text.to_owned(),
+                labels: line_labels,
+                statement,
+            });
+
+            address += size;
+        }
+    }
+
+    // Second pass: every label is known, encode
+    let mut bytes = Vec::new();
+    let mut listing = String::new();
+
+    for line in &lines {
+        let context = Context {
+            labels: &labels,
+            symbols,
+            scope: line.scope.as_deref(),
+            current: line.address,
+        };
+
+        let encoded = match &line.statement {
+            Some(statement) => {
+                encode(statement, &context).map_err(|reason| Error::InvalidAssembly(line.number, reason))?
+            },
+            None => Vec::new(),
+        };
+
+        write_listing(&mut listing, line, &encoded);
+        bytes.extend_from_slice(&encoded);
+    }
+
+    Ok(Assembly {
+        origin,
+        bytes,
+        labels: label_order
+            .into_iter()
+            .map(|name| {
+                let address = labels[&name];
+                (name, address)
+            })
+            .collect(),
+        listing,
+    })
+}
+
+fn parse_statement(
+    text: &str,
+    templates: &[Template],
+    context: &Context,
+) -> Result<Statement, String> {
+    let mnemonic_end = text.find(char::is_whitespace).unwrap_or(text.len());
+    let mnemonic = text[..mnemonic_end].to_lowercase();
+    let operands = split_operands(&text[mnemonic_end..]);
+
+    match mnemonic.as_str() {
+        "db" => {
+            let items = operands
+                .into_iter()
+                .map(|operand| match parse_string(&operand) {
+                    Some(bytes) => bytes.map(DataItem::String),
+                    None => Ok(DataItem::Expression(operand)),
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+
+            Ok(Statement::Bytes(items))
+        },
+        "dw" => Ok(Statement::Words(operands)),
+        "ds" => {
+            let count = operands.first().ok_or("ds needs a size")?;
+            // The size has to be known right away, so it can only use labels defined before
+            let count = context.evaluate(count)?;
+
+            if !(0..=0x10000).contains(&count) {
+                return Err(format!("invalid ds size {}", count));
+            }
+
+            Ok(Statement::Space {
+                count: count as usize,
+                fill: operands.get(1).cloned(),
+            })
+        },
+        _ => {
+            let operands = operands.iter().map(|operand| parse_operand(operand)).collect::<Vec<_>>();
+            let evaluate = |expression: &str| context.evaluate(expression).ok();
+            let ((opcode, template), operands) = find_template(templates, &mnemonic, operands, &evaluate)?;
+
+            // At most one operand of an instruction is encoded after the opcode, the condition isn't one
+            let family = template.instruction_family();
+            let skip = usize::from(template.condition().is_some());
+            let immediate = [template.operand1(), template.operand2()]
+                .into_iter()
+                .flatten()
+                .zip(&operands[skip..])
+                .find_map(|(expected, operand)| match operand {
+                    Operand::Memory(expression) | Operand::StackPointerOffset(expression) | Operand::Expression(expression)
+                        if !matches!(
+                            family,
+                            GameboyInstructionFamily::BIT
+                                | GameboyInstructionFamily::RES
+                                | GameboyInstructionFamily::SET
+                                | GameboyInstructionFamily::RST
+                        ) =>
+                    {
+                        Some((expected, expression.clone()))
+                    },
+                    _ => None,
+                });
+
+            Ok(Statement::Instruction {
+                opcode: opcode.clone(),
+                family,
+                immediate,
+                size: template.size() as usize,
+            })
+        },
+    }
+}
+
+fn encode(statement: &Statement, context: &Context) -> Result<Vec<u8>, String> {
+    let byte = |value: i64| -> Result<u8, String> {
+        if (-0x80..=0xFF).contains(&value) {
+            Ok(value as u8)
+        } else {
+            Err(format!("{} doesn't fit in a byte", value))
+        }
+    };
+    let word = |value: i64| -> Result<u16, String> {
+        if (-0x8000..=0xFFFF).contains(&value) {
+            Ok(value as u16)
+        } else {
+            Err(format!("{} doesn't fit in a word", value))
+        }
+    };
+    let signed = |value: i64| -> Result<u8, String> {
+        if (-0x80..=0x7F).contains(&value) {
+            Ok(value as i8 as u8)
+        } else {
+            Err(format!("{} doesn't fit in a signed byte", value))
+        }
+    };
+
+    let mut bytes = Vec::new();
+
+    match statement {
+        Statement::Instruction { opcode, family, immediate, size } => {
+            bytes.extend_from_slice(opcode);
+
+            match immediate {
+                Some((GameboyInstructionOperand::Immediate8(_), expression)) if *family == GameboyInstructionFamily::LDH => {
+                    let address = context.evaluate(expression)?;
+
+                    match address {
+                        0xFF00..=0xFFFF => bytes.push(address as u8),
+                        0x00..=0xFF => bytes.push(address as u8),
+                        _ => return Err(format!("ldh can't access {:04X}h", address)),
+                    }
+                },
+                Some((GameboyInstructionOperand::Immediate8(_), expression)) => bytes.push(byte(context.evaluate(expression)?)?),
+                Some((GameboyInstructionOperand::ImmediateSigned8(_), expression)) if *family == GameboyInstructionFamily::JR => {
+                    let target = context.evaluate(expression)?;
+                    let offset = target - (context.current as i64 + 2);
+
+                    bytes.push(signed(offset).map_err(|_| format!("jr target is {} bytes away", offset))?);
+                },
+                Some((GameboyInstructionOperand::ImmediateSigned8(_) | GameboyInstructionOperand::StackPointerOffset(_), expression)) => {
+                    bytes.push(signed(context.evaluate(expression)?)?)
+                },
+                Some((_, expression)) => bytes.extend_from_slice(&word(context.evaluate(expression)?)?.to_le_bytes()),
+                None => {},
+            }
+
+            // STOP is followed by a padding byte
+            bytes.resize(*size, 0x00);
+        },
+        Statement::Bytes(items) => {
+            for item in items {
+                match item {
+                    DataItem::String(string) => bytes.extend_from_slice(string),
+                    DataItem::Expression(expression) => bytes.push(byte(context.evaluate(expression)?)?),
+                }
+            }
+        },
+        Statement::Words(words) => {
+            for expression in words {
+                bytes.extend_from_slice(&word(context.evaluate(expression)?)?.to_le_bytes());
+            }
+        },
+        Statement::Space { count, fill } => {
+            let fill = match fill {
+                Some(fill) => byte(context.evaluate(fill)?)?,
+                None => 0x00,
+            };
+
+            bytes.resize(*count, fill);
+        },
+    }
+
+    Ok(bytes)
+}
+
+/// Bytes shown on a listing line, the rest go on the next lines.
+const LISTING_BYTES_PER_LINE: usize = 4;
+
+fn write_listing(listing: &mut String, line: &Line, bytes: &[u8]) {
+    for label in &line.labels {
+        let _ = writeln!(listing, "{:04X}  {:width$}  {}:", line.address, "", label, width = LISTING_BYTES_PER_LINE * 3 - 1);
+    }
+
+    if line.statement.is_none() {
+        return;
+    }
+
+    let mut chunks = bytes.chunks(LISTING_BYTES_PER_LINE);
+    let first = chunks.next().unwrap_or(&[]);
+    let hex = |chunk: &[u8]| chunk.iter().map(|byte| format!("{:02X}", byte)).collect::<Vec<_>>().join(" ");
+
+    let _ = writeln!(
+        listing,
+        "{:04X}  {:<width$}      {}",
+        line.address,
+        hex(first),
+        line.text,
+        width = LISTING_BYTES_PER_LINE * 3 - 1
+    );
+
+    for (index, chunk) in chunks.enumerate() {
+        let address = line.address as usize + (index + 1) * LISTING_BYTES_PER_LINE;
+        let _ = writeln!(listing, "{:04X}  {}", address, hex(chunk));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::disasm::Disassembler;
+
+    #[test]
+    fn numbers_follow_rgbds() {
+        let assembly = assemble("ld a, 16\nld a, $10\nld a, 0x10\nld a, %10000", 0xC000, &SymbolTable::default()).unwrap();
+
+        assert_eq!(assembly.bytes, [0x3E, 0x10, 0x3E, 0x10, 0x3E, 0x10, 0x3E, 0x10]);
+    }
+
+    #[test]
+    fn strings_can_contain_separators() {
+        let assembly = assemble("db \"a\\\" :: b\" :: db $00", 0xC000, &SymbolTable::default()).unwrap();
+
+        assert_eq!(assembly.bytes, b"a\" :: b\0");
+    }
+
+    #[test]
+    fn disassembled_instructions_assemble_back() {
+        let symbols = SymbolTable::default();
+        let disassembler = Disassembler::new(&symbols);
+        // Every opcode with operands 1234h, then every CB-prefixed one. Illegal opcodes come back as `db`
+        let encodings = (0x00..=0xFF)
+            .map(|opcode| vec![opcode, 0x34, 0x12])
+            .chain((0x00..=0xFF).map(|opcode| vec![0xCB, opcode]));
+
+        for bytes in encodings {
+            let read = |address: u16| bytes.get((address - 0xC000) as usize).copied().unwrap_or(0x00);
+            let line = disassembler.instruction(&read, 0xC000, bytes.len());
+            let assembly =
+                assemble(&line.text, 0xC000, &symbols).unwrap_or_else(|error| panic!("{}: {}", line.text, error));
+
+            assert_eq!(assembly.bytes, line.bytes, "{}", line.text);
+        }
+    }
+}
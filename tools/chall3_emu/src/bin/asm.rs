@@ -0,0 +1,57 @@
+use std::fs;
+use std::path::PathBuf;
+
+use clap::Parser;
+
+use gbhttpd::asm::assemble;
+use gbhttpd::error::Error as GameboyError;
+use gbhttpd::symbols::SymbolTable;
+
+/// Assembles a snippet of SM83 assembly in RGBDS syntax, printing its listing.
+#[derive(Parser, Debug)]
+#[command(version, about, long_about = None)]
+struct Args {
+    source_path: PathBuf,
+
+    /// Symbol file written by rgblink, whose labels the snippet can use.
+    #[arg(short, long)]
+    symbol_file_path: Option<PathBuf>,
+
+    /// Address the snippet runs at, a number or a label.
+    #[arg(long, default_value = "$C000")]
+    origin: String,
+
+    /// Where to write the assembled bytes.
+    #[arg(short, long)]
+    output_path: Option<PathBuf>,
+}
+
+#[derive(Debug)]
+#[allow(dead_code)] // Only read through the Debug impl when returned from main
+enum Error {
+    FileRead(std::io::Error),
+    FileWrite(std::io::Error),
+    Gameboy(GameboyError),
+}
+
+fn main() -> Result<(), Error> {
+    let args = Args::parse();
+
+    let source = fs::read_to_string(&args.source_path).map_err(Error::FileRead)?;
+    let symbols = match &args.symbol_file_path {
+        Some(symbol_file_path) => SymbolTable::load(symbol_file_path).map_err(Error::Gameboy)?,
+        None => SymbolTable::default(),
+    };
+
+    let origin = symbols.resolve(&args.origin).map_err(Error::Gameboy)?;
+    let assembly = assemble(&source, origin, &symbols).map_err(Error::Gameboy)?;
+
+    print!("{}", assembly.listing);
+    println!("{}", assembly.bytes.iter().map(|byte| format!("{:02X}", byte)).collect::<String>());
+
+    if let Some(output_path) = &args.output_path {
+        fs::write(output_path, &assembly.bytes).map_err(Error::FileWrite)?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,626 @@
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use clap::Parser;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Paragraph};
+use ratatui::{DefaultTerminal, Frame};
+
+use gbhttpd::debug::{Breakpoint, StopReason, WatchAccess, Watchpoint};
+use gbhttpd::disasm::Disassembler;
+use gbhttpd::error::Error as GameboyError;
+use gbhttpd::gb::{Gameboy, GameboyRegisterFlags};
+use gbhttpd::rewind::HistoryConfig;
+use gbhttpd::symbols::SymbolTable;
+
+/// Bytes per line of the memory view.
+const MEMORY_ROW: u16 = 8;
+
+/// T-cycles run between two redraws while running, a frame.
+const RUN_CYCLES: u64 = 70224;
+
+/// Number of bytes before PC the disassembly looks for an instruction boundary from.
+const DISASSEMBLY_LOOKBEHIND: u16 = 24;
+
+const HELP: &str = "s step  n next  c continue/pause  u step back  U reverse continue  b breakpoint at PC  \
+                    ↑↓ PgUp PgDn memory  : command  q quit";
+
+const COMMANDS: &str = "break ADDR, delete ID, watch START [END], rwatch START [END], unwatch ID, mem ADDR, \
+                        rewind N, reset, quit";
+
+/// Interactive terminal debugger: registers, disassembly around PC, memory, stack and breakpoints.
+#[derive(Parser, Debug)]
+#[command(version, about, long_about = None)]
+struct Args {
+    #[arg(short, long)]
+    rom_file_path: PathBuf,
+
+    /// DMG or CGB boot ROM to run before the game.
+    #[arg(short, long)]
+    boot_rom_path: Option<PathBuf>,
+
+    /// Symbol file written by rgblink, the ROM path with a `.sym` extension by default if there is one.
+    #[arg(short, long)]
+    symbol_file_path: Option<PathBuf>,
+
+    /// Breakpoints to start with, numbers or labels.
+    #[arg(long = "break")]
+    breakpoints: Vec<String>,
+
+    /// Number of steps recorded to step back, 0 to disable going back.
+    #[arg(long, default_value_t = 100_000)]
+    history: usize,
+}
+
+#[derive(Debug)]
+#[allow(dead_code)] // Only read through the Debug impl when returned from main
+enum Error {
+    FileRead(io::Error),
+    Terminal(io::Error),
+    Gameboy(GameboyError),
+}
+
+enum Mode {
+    Normal,
+    /// Typing a command after `:`.
+    Command(String),
+}
+
+struct App {
+    gb: Gameboy,
+    mode: Mode,
+    /// First address of the memory view.
+    memory_address: u16,
+    /// Lines the memory view fits, as of the last draw.
+    memory_rows: u16,
+    running: bool,
+    /// Temporary breakpoint at the instruction after a CALL or RST being stepped over.
+    step_over: Option<usize>,
+    status: String,
+    quit: bool,
+}
+
+fn hex_span(text: String, highlighted: bool) -> Span<'static> {
+    if highlighted {
+        Span::styled(text, Style::new().fg(Color::Black).bg(Color::Yellow))
+    } else {
+        Span::raw(text)
+    }
+}
+
+fn title(text: &str) -> Block<'_> {
+    Block::bordered().title(Span::styled(text, Style::new().add_modifier(Modifier::BOLD)))
+}
+
+impl App {
+    fn new(gb: Gameboy) -> Self {
+        let memory_address = gb.registers.sp & !(MEMORY_ROW - 1);
+
+        App {
+            gb,
+            mode: Mode::Normal,
+            memory_address,
+            memory_rows: 16,
+            running: false,
+            step_over: None,
+            status: HELP.to_owned(),
+            quit: false,
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [main, status] = Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).areas(frame.area());
+        let [left, right] = Layout::horizontal([Constraint::Percentage(50), Constraint::Percentage(50)]).areas(main);
+        let [registers, disassembly, breakpoints] =
+            Layout::vertical([Constraint::Length(8), Constraint::Min(0), Constraint::Length(8)]).areas(left);
+        let [memory, stack] = Layout::vertical([Constraint::Min(0), Constraint::Length(10)]).areas(right);
+
+        frame.render_widget(self.registers(), registers);
+        frame.render_widget(self.disassembly(disassembly), disassembly);
+        frame.render_widget(self.breakpoints(), breakpoints);
+        frame.render_widget(self.memory(memory), memory);
+        frame.render_widget(self.stack(stack), stack);
+
+        let status_line = match &self.mode {
+            Mode::Command(command) => Line::from(format!(":{}", command)),
+            Mode::Normal if self.running => Line::from("Running, c to pause"),
+            Mode::Normal => Line::from(self.status.as_str()),
+        };
+
+        frame.render_widget(Paragraph::new(status_line), status);
+    }
+
+    fn registers(&self) -> Paragraph<'static> {
+        let gb = &self.gb;
+        let registers = &gb.registers;
+
+        let flags: String = [
+            (GameboyRegisterFlags::Z, 'Z'),
+            (GameboyRegisterFlags::N, 'N'),
+            (GameboyRegisterFlags::H, 'H'),
+            (GameboyRegisterFlags::C, 'C'),
+        ]
+        .into_iter()
+        .map(|(flag, letter)| if registers.get_flag(flag) { letter } else { '-' })
+        .collect();
+
+        let (_, rom_bank) = gb.bus.cartridge.rom_banks();
+        let history = match gb.history() {
+            Some(history) => format!("{} steps back", history.len()),
+            None => "off".to_owned(),
+        };
+
+        let state = if gb.locked {
+            "locked"
+        } else if gb.stopped {
+            "stopped"
+        } else if gb.halted {
+            "halted"
+        } else {
+            ""
+        };
+
+        let lines = vec![
+            Line::from(format!(
+                "AF {:04X}   BC {:04X}   DE {:04X}   HL {:04X}",
+                registers.af, registers.bc, registers.de, registers.hl
+            )),
+            Line::from(format!("SP {:04X}   PC {:04X}   {}", registers.sp, registers.pc, gb.symbolize(registers.pc))),
+            Line::from(format!("Flags {}   IME {}   {}", flags, gb.ime as u8, state)),
+            Line::from(format!(
+                "ROM bank {}   WRAM bank {}   VRAM bank {}",
+                rom_bank,
+                gb.bus.wram_bank.max(1),
+                gb.bus.vram_bank
+            )),
+            Line::from(format!("Cycles {}   frame {}", gb.cycles(), gb.frame())),
+            Line::from(format!("History {}", history)),
+        ];
+
+        Paragraph::new(lines).block(title("Registers"))
+    }
+
+    fn disassembly(&self, area: Rect) -> Paragraph<'static> {
+        let gb = &self.gb;
+        let pc = gb.registers.pc;
+        let read = |address: u16| gb.bus.peek(address);
+        let disassembler = Disassembler::for_gameboy(gb);
+        let height = area.height.saturating_sub(2) as usize;
+
+        let decode = |address: u16| disassembler.instruction(&read, address, (0x10000 - address as usize).min(3));
+
+        // Instructions can't be decoded backwards: start from the earliest address that decodes into PC
+        let start = (1..=DISASSEMBLY_LOOKBEHIND.min(pc))
+            .rev()
+            .map(|back| pc - back)
+            .find(|&start| {
+                let mut address = start;
+
+                while address < pc {
+                    address = address.saturating_add(decode(address).bytes.len() as u16);
+                }
+
+                address == pc
+            })
+            .unwrap_or(pc);
+
+        let mut lines = Vec::new();
+        let mut pc_line = 0;
+        let mut address = start as usize;
+
+        while lines.len() < height + height / 2 && address <= 0xFFFF {
+            let instruction = decode(address as u16);
+
+            for label in gb.symbols().labels_at(instruction.address, gb.bank_at(instruction.address)) {
+                lines.push(Line::styled(format!("{}:", label.name), Style::new().fg(Color::Cyan)));
+            }
+
+            let breakpoint = gb.breakpoints().any(|(_, breakpoint)| breakpoint.address == instruction.address);
+            let bytes: Vec<String> = instruction.bytes.iter().map(|byte| format!("{:02X}", byte)).collect();
+            let text = format!(
+                "{}{} {:04X}  {:<9} {}",
+                if instruction.address == pc { '>' } else { ' ' },
+                if breakpoint { '*' } else { ' ' },
+                instruction.address,
+                bytes.join(" "),
+                instruction.text
+            );
+
+            if instruction.address == pc {
+                pc_line = lines.len();
+                lines.push(Line::styled(text, Style::new().fg(Color::Black).bg(Color::Yellow)));
+            } else if breakpoint {
+                lines.push(Line::styled(text, Style::new().fg(Color::Red)));
+            } else {
+                lines.push(Line::from(text));
+            }
+
+            address += instruction.bytes.len();
+        }
+
+        // Keep PC in the upper third
+        let scroll = pc_line.saturating_sub(height / 3).min(lines.len().saturating_sub(height));
+
+        Paragraph::new(lines).block(title("Disassembly")).scroll((scroll as u16, 0))
+    }
+
+    fn breakpoints(&self) -> Paragraph<'static> {
+        let gb = &self.gb;
+        let mut lines = Vec::new();
+
+        for (id, breakpoint) in gb.breakpoints() {
+            if Some(id) != self.step_over {
+                let address = breakpoint.address;
+                lines.push(Line::from(format!("#{:<3} {:04X}  {}", id, address, gb.symbolize(address))));
+            }
+        }
+
+        for (id, watchpoint) in gb.bus.watchpoints.iter() {
+            let access = match (watchpoint.access.read, watchpoint.access.write) {
+                (true, true) => "read/write",
+                (true, false) => "read",
+                _ => "write",
+            };
+
+            lines.push(Line::from(format!(
+                "W{:<3} {:04X}-{:04X}  {} {}",
+                id,
+                watchpoint.range.start(),
+                watchpoint.range.end(),
+                access,
+                gb.symbolize(*watchpoint.range.start())
+            )));
+        }
+
+        if lines.is_empty() {
+            lines.push(Line::styled("b or :break ADDR to add one", Style::new().fg(Color::DarkGray)));
+        }
+
+        Paragraph::new(lines).block(title("Breakpoints"))
+    }
+
+    fn memory(&mut self, area: Rect) -> Paragraph<'static> {
+        // Fill the view even near the end of the address space
+        self.memory_rows = area.height.saturating_sub(2).max(1);
+        self.scroll_memory(0);
+
+        let gb = &self.gb;
+        let registers = &gb.registers;
+
+        let lines: Vec<Line> = (0..self.memory_rows)
+            .map_while(|row| self.memory_address.checked_add(row * MEMORY_ROW))
+            .map(|address| {
+                let mut label = gb.symbolize(address);
+                label.truncate(22);
+
+                let mut spans = vec![
+                    Span::styled(format!("{:<22} ", label), Style::new().fg(Color::Cyan)),
+                    Span::raw(format!("{:04X} ", address)),
+                ];
+                let mut ascii = String::new();
+
+                for offset in 0..MEMORY_ROW {
+                    let byte_address = address.wrapping_add(offset);
+                    let byte = gb.bus.peek(byte_address);
+                    let highlighted = byte_address == registers.sp || byte_address == registers.hl;
+
+                    spans.push(Span::raw(" "));
+                    spans.push(hex_span(format!("{:02X}", byte), highlighted));
+                    ascii.push(if byte.is_ascii_graphic() || byte == b' ' { byte as char } else { '.' });
+                }
+
+                spans.push(Span::raw(format!("  {}", ascii)));
+
+                Line::from(spans)
+            })
+            .collect();
+
+        Paragraph::new(lines).block(title("Memory (SP and HL highlighted)"))
+    }
+
+    fn stack(&self, area: Rect) -> Paragraph<'static> {
+        let gb = &self.gb;
+        let sp = gb.registers.sp;
+        let rows = area.height.saturating_sub(2);
+
+        let lines: Vec<Line> = (0..rows)
+            .map_while(|row| sp.checked_add(row * 2))
+            .map(|address| {
+                let value = u16::from_le_bytes([gb.bus.peek(address), gb.bus.peek(address.wrapping_add(1))]);
+                Line::from(format!("{:04X}  {:04X}  {}", address, value, gb.symbolize(value)))
+            })
+            .collect();
+
+        Paragraph::new(lines).block(title("Stack"))
+    }
+
+    /// Handles a key press while nothing is being typed.
+    fn key(&mut self, key: KeyEvent) {
+        if self.running {
+            // Anything but quitting waits for the emulator to be paused
+            match key.code {
+                KeyCode::Char('c') | KeyCode::Esc => self.pause("Paused"),
+                KeyCode::Char('q') => self.quit = true,
+                _ => {},
+            }
+
+            return;
+        }
+
+        let page = (self.memory_rows * MEMORY_ROW) as i32;
+
+        match key.code {
+            KeyCode::Char('q') => self.quit = true,
+            KeyCode::Char('s') => self.step(),
+            KeyCode::Char('n') => self.step_over(),
+            KeyCode::Char('c') => self.run(),
+            KeyCode::Char('u') => self.step_back(),
+            KeyCode::Char('U') => self.reverse_continue(),
+            KeyCode::Char('b') => self.toggle_breakpoint(self.gb.registers.pc),
+            KeyCode::Char(':') => self.mode = Mode::Command(String::new()),
+            KeyCode::Up => self.scroll_memory(-(MEMORY_ROW as i32)),
+            KeyCode::Down => self.scroll_memory(MEMORY_ROW as i32),
+            KeyCode::PageUp => self.scroll_memory(-page),
+            KeyCode::PageDown => self.scroll_memory(page),
+            _ => self.status = HELP.to_owned(),
+        }
+    }
+
+    /// Moves the memory view by `delta` bytes, keeping it within the address space.
+    fn scroll_memory(&mut self, delta: i32) {
+        let last = 0x10000 - (self.memory_rows * MEMORY_ROW) as i32;
+        self.memory_address = (self.memory_address as i32 + delta).clamp(0, last.max(0)) as u16;
+    }
+
+    fn step(&mut self) {
+        self.status = match self.gb.step() {
+            Ok(outcome) => format!("Stepped: {:?}", outcome),
+            Err(error) => format!("Error: {}", error),
+        };
+    }
+
+    /// Steps, running through the subroutine if the instruction is a CALL or an RST.
+    fn step_over(&mut self) {
+        let pc = self.gb.registers.pc;
+        let opcode = self.gb.bus.peek(pc);
+
+        let next = match opcode {
+            // CALL, CALL cc
+            0xCD | 0xC4 | 0xCC | 0xD4 | 0xDC => pc.wrapping_add(3),
+            // RST
+            0xC7 | 0xCF | 0xD7 | 0xDF | 0xE7 | 0xEF | 0xF7 | 0xFF => pc.wrapping_add(1),
+            _ => return self.step(),
+        };
+
+        self.step_over = Some(self.gb.add_breakpoint(Breakpoint::new(next)));
+        self.run();
+    }
+
+    fn run(&mut self) {
+        self.running = true;
+        self.status.clear();
+    }
+
+    fn pause(&mut self, status: impl Into<String>) {
+        self.running = false;
+        self.status = status.into();
+
+        if let Some(id) = self.step_over.take() {
+            self.gb.remove_breakpoint(id);
+        }
+    }
+
+    /// Runs for a frame while running.
+    fn tick(&mut self) {
+        let status = match self.gb.run_until(RUN_CYCLES) {
+            Ok(StopReason::CycleLimit) => return,
+            Ok(StopReason::Breakpoint(id)) if Some(id) == self.step_over => "Stepped over".to_owned(),
+            Ok(reason) => self.describe(reason),
+            Err(error) => format!("Error: {}", error),
+        };
+
+        self.pause(status);
+    }
+
+    fn describe(&self, reason: StopReason) -> String {
+        match reason {
+            StopReason::Breakpoint(id) => format!("Breakpoint #{} at {}", id, self.gb.symbolize(self.gb.registers.pc)),
+            StopReason::Watchpoint(hit) => format!(
+                "Watchpoint W{}: {:?} of {:02X} at {} by {}",
+                hit.id,
+                hit.kind,
+                hit.value,
+                self.gb.symbolize(hit.address),
+                self.gb.symbolize(hit.pc)
+            ),
+            StopReason::SoftwareBreakpoint => format!("ld b, b at {}", self.gb.symbolize(self.gb.registers.pc)),
+            StopReason::CycleLimit => "Paused".to_owned(),
+        }
+    }
+
+    fn step_back(&mut self) {
+        self.status = match self.gb.step_back() {
+            Ok(true) => "Stepped back".to_owned(),
+            Ok(false) => "Nothing to step back to, the history is empty or disabled".to_owned(),
+            Err(error) => format!("Error: {}", error),
+        };
+    }
+
+    fn reverse_continue(&mut self) {
+        self.status = match self.gb.reverse_continue() {
+            Ok(Some(reason)) => self.describe(reason),
+            Ok(None) => "Reached the start of the history".to_owned(),
+            Err(error) => format!("Error: {}", error),
+        };
+    }
+
+    fn toggle_breakpoint(&mut self, address: u16) {
+        let existing = self.gb.breakpoints().find(|(_, breakpoint)| breakpoint.address == address).map(|(id, _)| id);
+
+        match existing {
+            Some(id) => {
+                self.gb.remove_breakpoint(id);
+                self.status = format!("Removed breakpoint #{}", id);
+            },
+            None => {
+                let id = self.gb.add_breakpoint(Breakpoint::new(address));
+                self.status = format!("Breakpoint #{} at {}", id, self.gb.symbolize(address));
+            },
+        }
+    }
+
+    /// Handles a key press while typing a command.
+    fn command_key(&mut self, key: KeyEvent, mut command: String) {
+        match key.code {
+            KeyCode::Enter => {
+                self.mode = Mode::Normal;
+                self.status = self.execute(&command).unwrap_or_else(|error| format!("Error: {}", error));
+            },
+            KeyCode::Esc => self.mode = Mode::Normal,
+            KeyCode::Backspace => {
+                command.pop();
+                self.mode = Mode::Command(command);
+            },
+            KeyCode::Char(character) => {
+                command.push(character);
+                self.mode = Mode::Command(command);
+            },
+            _ => self.mode = Mode::Command(command),
+        }
+    }
+
+    /// Runs a command typed after `:`, returns the status to show.
+    fn execute(&mut self, command: &str) -> Result<String, GameboyError> {
+        let mut words = command.split_whitespace();
+        let name = words.next().unwrap_or("");
+        let arguments: Vec<&str> = words.collect();
+
+        let address = |index: usize| match arguments.get(index) {
+            Some(argument) => self.gb.resolve(argument).map(Some),
+            None => Ok(None),
+        };
+
+        match (name, arguments.len()) {
+            ("break" | "b", 1) => {
+                let address = address(0)?.unwrap_or_default();
+                let id = self.gb.add_breakpoint(Breakpoint::new(address));
+
+                Ok(format!("Breakpoint #{} at {}", id, self.gb.symbolize(address)))
+            },
+            ("delete" | "d", 1) => match arguments[0].parse().ok().and_then(|id| self.gb.remove_breakpoint(id)) {
+                Some(_) => Ok("Removed the breakpoint".to_owned()),
+                None => Ok(format!("No breakpoint #{}", arguments[0])),
+            },
+            ("watch" | "rwatch", 1 | 2) => {
+                let start = address(0)?.unwrap_or_default();
+                let end = address(1)?.unwrap_or(start);
+                let access = if name == "watch" { WatchAccess::WRITE } else { WatchAccess::READ };
+                let id = self.gb.add_watchpoint(Watchpoint::new(start..=end, access));
+
+                Ok(format!("Watchpoint W{} on {:04X}-{:04X}", id, start, end))
+            },
+            ("unwatch", 1) => {
+                let id = arguments[0].trim_start_matches('W').parse().ok();
+
+                match id.and_then(|id| self.gb.remove_watchpoint(id)) {
+                    Some(_) => Ok("Removed the watchpoint".to_owned()),
+                    None => Ok(format!("No watchpoint {}", arguments[0])),
+                }
+            },
+            ("mem" | "m", 1) => {
+                self.memory_address = address(0)?.unwrap_or_default();
+                Ok(format!("Memory at {}", self.gb.symbolize(self.memory_address)))
+            },
+            ("rewind", 1) => match arguments[0].parse() {
+                Ok(steps) => match self.gb.rewind(steps) {
+                    Ok(rewound) => Ok(format!("Went back {} steps", rewound)),
+                    Err(error) => Ok(format!("Error: {}", error)),
+                },
+                Err(_) => Ok(format!("Invalid number of steps {:?}", arguments[0])),
+            },
+            ("reset", 0) => {
+                self.gb.reset();
+                Ok("Reset".to_owned())
+            },
+            ("quit" | "q", 0) => {
+                self.quit = true;
+                Ok(String::new())
+            },
+            _ => Ok(format!("Commands: {}", COMMANDS)),
+        }
+    }
+}
+
+fn run(terminal: &mut DefaultTerminal, app: &mut App) -> io::Result<()> {
+    while !app.quit {
+        terminal.draw(|frame| app.draw(frame))?;
+
+        if app.running {
+            app.tick();
+
+            if !event::poll(Duration::ZERO)? {
+                continue;
+            }
+        }
+
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+
+        if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) {
+            app.quit = true;
+            continue;
+        }
+
+        match std::mem::replace(&mut app.mode, Mode::Normal) {
+            Mode::Normal => app.key(key),
+            Mode::Command(command) => app.command_key(key, command),
+        }
+    }
+
+    Ok(())
+}
+
+fn main() -> Result<(), Error> {
+    let args = Args::parse();
+
+    let mut gb = Gameboy::new();
+    gb.load_rom(fs::read(&args.rom_file_path).map_err(Error::FileRead)?).map_err(Error::Gameboy)?;
+
+    if let Some(boot_rom_path) = &args.boot_rom_path {
+        gb.load_boot_rom(fs::read(boot_rom_path).map_err(Error::FileRead)?).map_err(Error::Gameboy)?;
+    }
+
+    let symbol_file_path = args.symbol_file_path.clone().unwrap_or_else(|| args.rom_file_path.with_extension("sym"));
+
+    if args.symbol_file_path.is_some() || symbol_file_path.exists() {
+        gb.load_symbols(SymbolTable::load(&symbol_file_path).map_err(Error::Gameboy)?);
+    }
+
+    for breakpoint in &args.breakpoints {
+        gb.add_breakpoint(Breakpoint::new(gb.resolve(breakpoint).map_err(Error::Gameboy)?));
+    }
+
+    if args.history > 0 {
+        gb.enable_history(HistoryConfig {
+            capacity: args.history,
+            ..Default::default()
+        });
+    }
+
+    let mut app = App::new(gb);
+    let mut terminal = ratatui::init();
+    let result = run(&mut terminal, &mut app);
+    ratatui::restore();
+
+    result.map_err(Error::Terminal)
+}
@@ -0,0 +1,74 @@
+use std::fs;
+use std::path::PathBuf;
+
+use clap::Parser;
+
+use gbhttpd::disasm::disassemble_rom;
+use gbhttpd::error::Error as GameboyError;
+use gbhttpd::symbols::SymbolTable;
+
+/// Disassembles a range of a ROM as RGBDS source, using the labels of its symbol file.
+#[derive(Parser, Debug)]
+#[command(version, about, long_about = None)]
+struct Args {
+    #[arg(short, long)]
+    rom_file_path: PathBuf,
+
+    /// Symbol file written by rgblink, the ROM path with a `.sym` extension by default if there is one.
+    #[arg(short, long)]
+    symbol_file_path: Option<PathBuf>,
+
+    /// First address to disassemble, a number or a label.
+    #[arg(long, default_value = "$0000")]
+    start: String,
+
+    /// Last address to disassemble, a number or a label.
+    #[arg(long, default_value = "$3FFF")]
+    end: String,
+
+    /// ROM bank mapped at 4000h - 7FFFh.
+    #[arg(short, long, default_value_t = 1)]
+    bank: usize,
+
+    /// Where to write the listing, stdout by default.
+    #[arg(short, long)]
+    output_path: Option<PathBuf>,
+}
+
+#[derive(Debug)]
+#[allow(dead_code)] // Only read through the Debug impl when returned from main
+enum Error {
+    FileRead(std::io::Error),
+    FileWrite(std::io::Error),
+    Gameboy(GameboyError),
+}
+
+fn main() -> Result<(), Error> {
+    let args = Args::parse();
+
+    let rom = fs::read(&args.rom_file_path).map_err(Error::FileRead)?;
+
+    let symbols = match &args.symbol_file_path {
+        Some(symbol_file_path) => SymbolTable::load(symbol_file_path).map_err(Error::Gameboy)?,
+        None => {
+            let symbol_file_path = args.rom_file_path.with_extension("sym");
+
+            if symbol_file_path.exists() {
+                SymbolTable::load(&symbol_file_path).map_err(Error::Gameboy)?
+            } else {
+                SymbolTable::default()
+            }
+        },
+    };
+
+    let start = symbols.resolve(&args.start).map_err(Error::Gameboy)?;
+    let end = symbols.resolve(&args.end).map_err(Error::Gameboy)?;
+    let listing = disassemble_rom(&rom, args.bank, start..=end, &symbols);
+
+    match &args.output_path {
+        Some(output_path) => fs::write(output_path, listing).map_err(Error::FileWrite)?,
+        None => print!("{}", listing),
+    }
+
+    Ok(())
+}
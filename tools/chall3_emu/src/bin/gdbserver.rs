@@ -0,0 +1,90 @@
+use std::fs;
+use std::net::TcpListener;
+use std::path::PathBuf;
+
+use clap::Parser;
+
+use gbhttpd::error::Error as GameboyError;
+use gbhttpd::gb::Gameboy;
+use gbhttpd::gdb;
+use gbhttpd::rewind::HistoryConfig;
+use gbhttpd::symbols::SymbolTable;
+
+/// Serves a ROM to GDB remote serial protocol clients, such as `target remote` in GDB.
+#[derive(Parser, Debug)]
+#[command(version, about, long_about = None)]
+struct Args {
+    #[arg(short, long)]
+    rom_file_path: PathBuf,
+
+    /// DMG or CGB boot ROM to run before the game.
+    #[arg(short, long)]
+    boot_rom_path: Option<PathBuf>,
+
+    /// Symbol file written by rgblink, to print where the clients stopped.
+    #[arg(short, long)]
+    symbol_file_path: Option<PathBuf>,
+
+    #[arg(long, default_value = "127.0.0.1")]
+    host: String,
+
+    #[arg(short, long, default_value_t = 2159)]
+    port: u16,
+
+    /// Number of steps to record for reverse stepping and continuing, none by default.
+    #[arg(long)]
+    history: Option<usize>,
+}
+
+#[derive(Debug)]
+#[allow(dead_code)] // Only read through the Debug impl when returned from main
+enum Error {
+    FileRead(std::io::Error),
+    Listen(std::io::Error),
+    Gameboy(GameboyError),
+}
+
+fn main() -> Result<(), Error> {
+    let args = Args::parse();
+
+    let mut gb = Gameboy::new();
+    gb.load_rom(fs::read(&args.rom_file_path).map_err(Error::FileRead)?).map_err(Error::Gameboy)?;
+
+    if let Some(boot_rom_path) = &args.boot_rom_path {
+        gb.load_boot_rom(fs::read(boot_rom_path).map_err(Error::FileRead)?).map_err(Error::Gameboy)?;
+    }
+
+    if let Some(symbol_file_path) = &args.symbol_file_path {
+        gb.load_symbols(SymbolTable::load(symbol_file_path).map_err(Error::Gameboy)?);
+    }
+
+    if let Some(capacity) = args.history {
+        gb.enable_history(HistoryConfig {
+            capacity,
+            ..Default::default()
+        });
+    }
+
+    let listener = TcpListener::bind((args.host.as_str(), args.port)).map_err(Error::Listen)?;
+    println!("Listening on {}:{}", args.host, args.port);
+
+    for stream in listener.incoming() {
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(error) => {
+                eprintln!("Failed to accept a client: {}", error);
+                continue;
+            },
+        };
+
+        println!("Client connected from {:?}", stream.peer_addr().ok());
+
+        // A client going away abruptly doesn't stop the server
+        match gdb::serve(&mut gb, stream) {
+            Ok(()) => println!("Client left at {}", gb.symbolize(gb.registers.pc)),
+            Err(error) => eprintln!("Client failed: {}", error),
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,139 @@
+use std::fs;
+use std::path::PathBuf;
+
+use clap::{Parser, ValueEnum};
+
+use gbhttpd::error::{EmuError, Error as GameboyError};
+use gbhttpd::gb::{Gameboy, GameboyConfig, GameboyModel};
+use gbhttpd::joypad::InputMovie;
+use gbhttpd::symbols::SymbolTable;
+use gbhttpd::trace::{TraceFormat, Tracer};
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum Model {
+    Dmg,
+    Mgb,
+    Cgb,
+}
+
+impl From<Model> for GameboyModel {
+    fn from(model: Model) -> Self {
+        match model {
+            Model::Dmg => GameboyModel::Dmg,
+            Model::Mgb => GameboyModel::Mgb,
+            Model::Cgb => GameboyModel::Cgb,
+        }
+    }
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum Trace {
+    Doctor,
+    Annotated,
+    Binary,
+}
+
+impl From<Trace> for TraceFormat {
+    fn from(trace: Trace) -> Self {
+        match trace {
+            Trace::Doctor => TraceFormat::Doctor,
+            Trace::Annotated => TraceFormat::Annotated,
+            Trace::Binary => TraceFormat::Binary,
+        }
+    }
+}
+
+/// Runs a ROM headless for a number of frames, then saves what's on screen to a PNG file.
+#[derive(Parser, Debug)]
+#[command(version, about, long_about = None)]
+struct Args {
+    #[arg(short, long)]
+    rom_file_path: PathBuf,
+
+    /// Hardware to emulate, picked from the cartridge header by default.
+    #[arg(short, long, value_enum)]
+    model: Option<Model>,
+
+    /// DMG or CGB boot ROM to run before the game.
+    #[arg(short, long)]
+    boot_rom_path: Option<PathBuf>,
+
+    /// Battery save file to boot the ROM with.
+    #[arg(short, long)]
+    save_file_path: Option<PathBuf>,
+
+    /// Input movie to play back, made of `frame buttons` lines.
+    #[arg(short, long)]
+    input_movie_path: Option<PathBuf>,
+
+    /// Number of frames to run before taking the screenshot.
+    #[arg(short, long, default_value_t = 60)]
+    frame: u64,
+
+    #[arg(short, long, default_value = "screenshot.png")]
+    output_path: PathBuf,
+
+    /// File to write a line or a record to for every executed instruction.
+    #[arg(short, long)]
+    trace_path: Option<PathBuf>,
+
+    #[arg(long, value_enum, default_value_t = Trace::Doctor)]
+    trace_format: Trace,
+
+    /// Symbol file written by rgblink, for the labels of annotated traces.
+    #[arg(long)]
+    symbol_file_path: Option<PathBuf>,
+}
+
+#[derive(Debug)]
+#[allow(dead_code)] // Only read through the Debug impl when returned from main
+enum Error {
+    FileRead(std::io::Error),
+    Gameboy(GameboyError),
+    Emulation(EmuError),
+}
+
+fn main() -> Result<(), Error> {
+    let args = Args::parse();
+
+    let mut gb = Gameboy::with_config(GameboyConfig {
+        model: args.model.map(GameboyModel::from),
+        ..Default::default()
+    });
+    gb.load_rom(fs::read(&args.rom_file_path).map_err(Error::FileRead)?).map_err(Error::Gameboy)?;
+
+    if let Some(boot_rom_path) = &args.boot_rom_path {
+        gb.load_boot_rom(fs::read(boot_rom_path).map_err(Error::FileRead)?).map_err(Error::Gameboy)?;
+    }
+
+    if let Some(save_file_path) = &args.save_file_path {
+        gb.load_save_file(save_file_path).map_err(Error::Gameboy)?;
+    }
+
+    if let Some(input_movie_path) = &args.input_movie_path {
+        let script = fs::read_to_string(input_movie_path).map_err(Error::FileRead)?;
+        gb.load_input_movie(InputMovie::parse(&script).map_err(Error::Gameboy)?);
+    }
+
+    if let Some(symbol_file_path) = &args.symbol_file_path {
+        gb.load_symbols(SymbolTable::load(symbol_file_path).map_err(Error::Gameboy)?);
+    }
+
+    if let Some(trace_path) = &args.trace_path {
+        gb.set_tracer(Some(Tracer::create(trace_path, args.trace_format.into()).map_err(Error::Gameboy)?));
+    }
+
+    let result = gb.run_until_frame(args.frame);
+
+    // Keep the trace up to the instruction that failed
+    if let Some(tracer) = gb.set_tracer(None) {
+        tracer.finish().map_err(Error::Gameboy)?;
+    }
+
+    result.map_err(Error::Emulation)?;
+    gb.save_screenshot(&args.output_path).map_err(Error::Gameboy)?;
+
+    println!("Saved frame {} to {:?}", gb.frame(), args.output_path);
+
+    Ok(())
+}
@@ -0,0 +1,43 @@
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Write};
+use std::path::PathBuf;
+
+use clap::Parser;
+
+use gbhttpd::error::Error as GameboyError;
+use gbhttpd::trace::TraceReader;
+
+/// Converts a binary trace to Gameboy Doctor lines.
+#[derive(Parser, Debug)]
+#[command(version, about, long_about = None)]
+struct Args {
+    trace_path: PathBuf,
+
+    /// Where to write the lines, stdout by default.
+    #[arg(short, long)]
+    output_path: Option<PathBuf>,
+}
+
+#[derive(Debug)]
+#[allow(dead_code)] // Only read through the Debug impl when returned from main
+enum Error {
+    FileRead(io::Error),
+    FileWrite(io::Error),
+    Gameboy(GameboyError),
+}
+
+fn main() -> Result<(), Error> {
+    let args = Args::parse();
+
+    let input = BufReader::new(File::open(&args.trace_path).map_err(Error::FileRead)?);
+    let mut output: Box<dyn Write> = match &args.output_path {
+        Some(output_path) => Box::new(BufWriter::new(File::create(output_path).map_err(Error::FileWrite)?)),
+        None => Box::new(BufWriter::new(io::stdout().lock())),
+    };
+
+    for record in TraceReader::new(input).map_err(Error::Gameboy)? {
+        writeln!(output, "{}", record.map_err(Error::Gameboy)?).map_err(Error::FileWrite)?;
+    }
+
+    output.flush().map_err(Error::FileWrite)
+}
@@ -0,0 +1,673 @@
+use std::cell::Cell;
+use std::mem;
+
+use crate::cartridge::Cartridge;
+use crate::debug::{AccessKind, WatchHit, Watchpoints};
+use crate::dirty::DirtyPages;
+use crate::dma::{Hdma, OamDma};
+use crate::error::Error;
+use crate::gb::GameboyModel;
+use crate::hooks::{AccessLog, MemoryAccess};
+use crate::joypad::Joypad;
+use crate::ppu::{Ppu, VRAM_BANK_SIZE};
+use crate::savestate::{SaveState, StateReader, StateWriter};
+use crate::serial::Serial;
+use crate::timer::Timer;
+
+/// The interrupt sources, in priority order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Interrupt {
+    VBlank = 0,
+    LcdStat = 1,
+    Timer = 2,
+    Serial = 3,
+    Joypad = 4,
+}
+
+impl Interrupt {
+    /// Cost of dispatching an interrupt, in T-cycles.
+    pub const DISPATCH_CYCLES: u8 = 20;
+
+    pub fn mask(self) -> u8 {
+        1 << self as u8
+    }
+
+    /// Address the CPU jumps to when servicing this interrupt.
+    pub fn vector(self) -> u16 {
+        0x40 + 8 * self as u16
+    }
+
+    /// Returns the highest priority interrupt in a set of IE/IF bits.
+    pub fn highest(bits: u8) -> Option<Interrupt> {
+        [Interrupt::VBlank, Interrupt::LcdStat, Interrupt::Timer, Interrupt::Serial, Interrupt::Joypad]
+            .into_iter()
+            .find(|interrupt| bits & interrupt.mask() != 0)
+    }
+}
+
+/// Handlers for one I/O register (FF00h - FF7Fh).
+#[derive(Clone, Copy)]
+struct IoRegister {
+    read: fn(&Bus, u16) -> u8,
+    write: fn(&mut Bus, u16, u8),
+}
+
+impl IoRegister {
+    /// A register that behaves like plain memory.
+    const MEMORY: IoRegister = IoRegister {
+        read: Bus::read_io_memory,
+        write: Bus::write_io_memory,
+    };
+}
+
+const IO_REGISTERS: [IoRegister; 0x80] = {
+    let mut table = [IoRegister::MEMORY; 0x80];
+
+    // P1
+    table[0x00] = IoRegister {
+        read: |bus, _| bus.joypad.read(),
+        write: |bus, _, value| bus.joypad.write(value),
+    };
+
+    // SB, SC
+    let mut i = 0x01;
+    while i <= 0x02 {
+        table[i] = IoRegister {
+            read: |bus, address| bus.serial.read(address),
+            write: |bus, address, value| bus.serial.write(address, value),
+        };
+        i += 1;
+    }
+
+    // DIV, TIMA, TMA, TAC
+    let mut i = 0x04;
+    while i <= 0x07 {
+        table[i] = IoRegister {
+            read: |bus, address| bus.timer.read(address),
+            write: |bus, address, value| bus.timer.write(address, value),
+        };
+        i += 1;
+    }
+
+    // IF: the upper 3 bits are unused and always read as 1
+    table[0x0F] = IoRegister {
+        read: |bus, _| bus.io[0x0F] | 0xE0,
+        write: |bus, _, value| bus.io[0x0F] = value & 0x1F,
+    };
+
+    // LCDC, STAT, SCY, SCX, LY, LYC, then BGP, OBP0, OBP1, WY, WX
+    let mut i = 0x40;
+    while i <= 0x4B {
+        if i != 0x46 {
+            table[i] = IoRegister {
+                read: |bus, address| bus.ppu.read(address),
+                write: |bus, address, value| bus.ppu.write(address, value),
+            };
+        }
+        i += 1;
+    }
+
+    // KEY0: written by the CGB boot ROM to fall back to DMG compatibility mode, then locked
+    table[0x4C] = IoRegister {
+        read: |_, _| 0xFF,
+        write: |bus, _, value| {
+            if bus.boot_rom_mapped {
+                bus.dmg_compatibility = value & 0x04 != 0;
+            }
+        },
+    };
+
+    // BANK: any non-zero write unmaps the boot ROM for good
+    table[0x50] = IoRegister {
+        read: |_, _| 0xFF,
+        write: |bus, _, value| {
+            if bus.boot_rom_mapped && value != 0 {
+                bus.boot_rom_mapped = false;
+
+                if bus.dmg_compatibility {
+                    bus.set_cgb_mode(false);
+                }
+            }
+        },
+    };
+
+    // DMA: reads back the last written value
+    table[0x46] = IoRegister {
+        read: Bus::read_io_memory,
+        write: |bus, address, value| {
+            bus.write_io_memory(address, value);
+            bus.oam_dma.start(value);
+        },
+    };
+
+    // KEY1: bit 7 is the current speed, bit 0 arms a speed switch on the next STOP
+    table[0x4D] = IoRegister {
+        read: |bus, _| match bus.cgb {
+            true => 0x7E | (bus.double_speed as u8) << 7 | bus.speed_switch_armed as u8,
+            false => 0xFF,
+        },
+        write: |bus, _, value| bus.speed_switch_armed = bus.cgb && value & 0x01 != 0,
+    };
+
+    // VBK
+    table[0x4F] = IoRegister {
+        read: |bus, _| match bus.cgb {
+            true => 0xFE | bus.vram_bank,
+            false => 0xFF,
+        },
+        write: |bus, _, value| {
+            if bus.cgb {
+                bus.vram_bank = value & 0x01;
+            }
+        },
+    };
+
+    // HDMA1 - HDMA5
+    let mut i = 0x51;
+    while i <= 0x55 {
+        table[i] = IoRegister {
+            read: |bus, address| match bus.cgb {
+                true => bus.hdma.read(address),
+                false => 0xFF,
+            },
+            write: |bus, address, value| {
+                if bus.cgb {
+                    for _ in 0..bus.hdma.write(address, value) {
+                        bus.copy_hdma_block();
+                    }
+                }
+            },
+        };
+        i += 1;
+    }
+
+    // BCPS, BCPD, OCPS, OCPD
+    let mut i = 0x68;
+    while i <= 0x6B {
+        table[i] = IoRegister {
+            read: |bus, address| bus.ppu.read(address),
+            write: |bus, address, value| bus.ppu.write(address, value),
+        };
+        i += 1;
+    }
+
+    // SVBK
+    table[0x70] = IoRegister {
+        read: |bus, _| match bus.cgb {
+            true => 0xF8 | bus.wram_bank,
+            false => 0xFF,
+        },
+        write: |bus, _, value| {
+            if bus.cgb {
+                bus.wram_bank = value & 0x07;
+            }
+        },
+    };
+
+    table
+};
+
+const WRAM_BANK_SIZE: usize = 0x1000;
+
+/// The memory bus of the Game Boy, which dispatches every access to the right region.
+#[derive(Debug, Clone)]
+pub struct Bus {
+    pub cartridge: Cartridge,
+    /// The boot ROM, empty if none was given. A CGB boot ROM is also mapped at 0200h - 08FFh.
+    pub boot_rom: Vec<u8>,
+    pub boot_rom_mapped: bool,
+    /// Set by the CGB boot ROM through KEY0 when the game doesn't support the CGB.
+    dmg_compatibility: bool,
+    /// Set when running a CGB game, which enables the VRAM and WRAM banks, double speed and the color palettes.
+    pub cgb: bool,
+    /// Both VRAM banks, bank 1 only exists on the CGB.
+    pub vram: Vec<u8>,
+    /// Pages of `vram` written since the last checkpoint. Writing to `vram` directly bypasses the tracking.
+    vram_dirty: DirtyPages,
+    /// VRAM bank mapped at 8000h (VBK).
+    pub vram_bank: u8,
+    /// All 8 WRAM banks, banks 2 - 7 only exist on the CGB.
+    pub wram: Vec<u8>,
+    /// Pages of `wram` written since the last checkpoint. Writing to `wram` directly bypasses the tracking.
+    wram_dirty: DirtyPages,
+    /// WRAM bank mapped at D000h (SVBK), 0 selects bank 1.
+    pub wram_bank: u8,
+    pub double_speed: bool,
+    /// Set through KEY1, the next STOP switches speed instead of stopping the system.
+    pub speed_switch_armed: bool,
+    pub oam: [u8; 0xA0],
+    pub io: [u8; 0x80],
+    pub hram: [u8; 0x7F],
+    /// Interrupt enable register (FFFFh).
+    pub ie: u8,
+    pub joypad: Joypad,
+    pub timer: Timer,
+    pub serial: Serial,
+    pub ppu: Ppu,
+    pub oam_dma: OamDma,
+    pub hdma: Hdma,
+    /// T-cycles the CPU has to stay halted for while VRAM DMA blocks are copied, see [`Bus::take_dma_stall`].
+    dma_stall: u32,
+    pub watchpoints: Watchpoints,
+    /// First read or write watchpoint hit since the last [`Bus::take_watch_hit`]. Reads go through `&self`, hence the
+    /// cell.
+    watch_hit: Cell<Option<WatchHit>>,
+    /// Accesses waiting to be passed to the hooks of [`crate::gb::Gameboy`].
+    pub access_log: AccessLog,
+}
+
+impl Default for Bus {
+    fn default() -> Self {
+        Bus::new(Cartridge::empty())
+    }
+}
+
+impl Bus {
+    pub fn new(cartridge: Cartridge) -> Self {
+        Bus {
+            cartridge,
+            boot_rom: Vec::new(),
+            boot_rom_mapped: false,
+            dmg_compatibility: false,
+            cgb: false,
+            vram: vec![0; 2 * VRAM_BANK_SIZE],
+            vram_dirty: DirtyPages::new(2 * VRAM_BANK_SIZE),
+            vram_bank: 0,
+            wram: vec![0; 8 * WRAM_BANK_SIZE],
+            wram_dirty: DirtyPages::new(8 * WRAM_BANK_SIZE),
+            wram_bank: 0,
+            double_speed: false,
+            speed_switch_armed: false,
+            oam: [0; 0xA0],
+            io: [0; 0x80],
+            hram: [0; 0x7F],
+            ie: 0,
+            joypad: Joypad::default(),
+            timer: Timer::default(),
+            serial: Serial::default(),
+            ppu: Ppu::default(),
+            oam_dma: OamDma::default(),
+            hdma: Hdma::default(),
+            dma_stall: 0,
+            watchpoints: Watchpoints::default(),
+            watch_hit: Cell::new(None),
+            access_log: AccessLog::default(),
+        }
+    }
+
+    /// Powers the bus back on, clearing the memory and the state of every component. The cartridge is kept with its
+    /// external RAM, as are the boot ROM, the serial output, the buttons being held, the watchpoints and the hooks.
+    pub fn reset(&mut self) {
+        let mut cartridge = mem::take(&mut self.cartridge);
+        cartridge.reset();
+
+        let mut bus = Bus::new(cartridge);
+        bus.boot_rom = mem::take(&mut self.boot_rom);
+        bus.serial.output = mem::take(&mut self.serial.output);
+        bus.serial.linked = self.serial.linked;
+        bus.joypad.set_pressed(self.joypad.pressed());
+        bus.watchpoints = mem::take(&mut self.watchpoints);
+        bus.access_log.reads = self.access_log.reads;
+        bus.access_log.writes = self.access_log.writes;
+
+        *self = bus;
+    }
+
+    /// Enables or disables the CGB features, the CGB registers read as FFh when disabled.
+    pub fn set_cgb_mode(&mut self, cgb: bool) {
+        self.cgb = cgb;
+        self.ppu.set_cgb_mode(cgb);
+    }
+
+    /// Maps the boot ROM back, for it to run again from 0000h.
+    pub fn map_boot_rom(&mut self) {
+        self.boot_rom_mapped = true;
+        self.dmg_compatibility = false;
+    }
+
+    /// Puts the I/O registers in the state the boot ROM of `model` leaves them in.
+    pub fn apply_post_boot_state(&mut self, model: GameboyModel) {
+        // Sound registers, which are plain memory for now
+        const SOUND: [(u16, u8); 21] = [
+            (0xFF10, 0x80), (0xFF11, 0xBF), (0xFF12, 0xF3), (0xFF13, 0xFF), (0xFF14, 0xBF),
+            (0xFF16, 0x3F), (0xFF17, 0x00), (0xFF18, 0xFF), (0xFF19, 0xBF),
+            (0xFF1A, 0x7F), (0xFF1B, 0xFF), (0xFF1C, 0x9F), (0xFF1D, 0xFF), (0xFF1E, 0xBF),
+            (0xFF20, 0xFF), (0xFF21, 0x00), (0xFF22, 0x00), (0xFF23, 0xBF),
+            (0xFF24, 0x77), (0xFF25, 0xF3), (0xFF26, 0xF1),
+        ];
+
+        for (address, value) in SOUND {
+            self.write(address, value);
+        }
+
+        self.write(0xFF00, 0x30);
+        self.write(0xFF01, 0x00);
+        self.write(0xFF02, 0x00);
+        self.write(0xFF05, 0x00);
+        self.write(0xFF06, 0x00);
+        self.write(0xFF07, 0x00);
+        self.write(0xFF0F, 0x01);
+        self.io[0x46] = 0xFF;
+        self.ie = 0x00;
+        self.boot_rom_mapped = false;
+        self.dmg_compatibility = false;
+
+        match model {
+            GameboyModel::Dmg | GameboyModel::Mgb => self.timer.set_counter(0xABCC),
+            GameboyModel::Cgb => self.timer.set_counter(0x1EA0),
+        }
+    }
+
+    /// Returns the offset in `vram` of an address in 8000h - 9FFFh.
+    fn vram_offset(&self, address: u16) -> usize {
+        self.vram_bank as usize * VRAM_BANK_SIZE + (address - 0x8000) as usize
+    }
+
+    /// Returns the offset in `wram` of an address in C000h - DFFFh.
+    fn wram_offset(&self, address: u16) -> usize {
+        let offset = (address & 0x0FFF) as usize;
+
+        if address < 0xD000 {
+            offset
+        } else {
+            self.wram_bank.max(1) as usize * WRAM_BANK_SIZE + offset
+        }
+    }
+
+    /// Returns a byte as the CPU sees it: during an OAM DMA, OAM and the bus the DMA reads from return FFh.
+    fn cpu_view(&self, address: u16) -> u8 {
+        if self.oam_dma.conflicts_with(address) {
+            0xFF
+        } else {
+            self.peek(address)
+        }
+    }
+
+    /// Reads an opcode or an operand for the CPU. Neither read watchpoints nor read hooks see it, execute watchpoints
+    /// and hooks cover code.
+    pub fn fetch(&self, address: u16) -> u8 {
+        self.cpu_view(address)
+    }
+
+    /// Reads a byte of data for the CPU.
+    pub fn read(&self, address: u16) -> u8 {
+        let value = self.cpu_view(address);
+
+        if self.watchpoints.enabled {
+            self.check_watchpoints(address, AccessKind::Read, value);
+        }
+
+        if self.access_log.reads {
+            self.access_log.record(MemoryAccess { address, kind: AccessKind::Read, old: value, new: value });
+        }
+
+        value
+    }
+
+    fn check_watchpoints(&self, address: u16, kind: AccessKind, value: u8) {
+        if self.watch_hit.get().is_some() {
+            return;
+        }
+
+        if let Some(id) = self.watchpoints.find(address, kind) {
+            self.watch_hit.set(Some(WatchHit { id, address, kind, value, pc: 0 }));
+        }
+    }
+
+    /// Returns the first read or write watchpoint hit since the last call, with `pc` left to 0 for the caller to fill.
+    pub fn take_watch_hit(&self) -> Option<WatchHit> {
+        self.watch_hit.take()
+    }
+
+    /// Reads a byte the way a DMA or a debugger does, ignoring bus conflicts and watchpoints.
+    pub fn peek(&self, address: u16) -> u8 {
+        match address {
+            0x0000..=0x00FF | 0x0200..=0x08FF if self.boot_rom_mapped && (address as usize) < self.boot_rom.len() => {
+                self.boot_rom[address as usize]
+            },
+            0x0000..=0x7FFF => self.cartridge.read_rom(address),
+            0x8000..=0x9FFF => self.vram[self.vram_offset(address)],
+            0xA000..=0xBFFF => self.cartridge.read_ram(address),
+            0xC000..=0xDFFF => self.wram[self.wram_offset(address)],
+            // Echo RAM, mirrors C000h - DDFFh
+            0xE000..=0xFDFF => self.wram[self.wram_offset(address - 0x2000)],
+            0xFE00..=0xFE9F => self.oam[(address - 0xFE00) as usize],
+            // Unusable area
+            0xFEA0..=0xFEFF => 0xFF,
+            0xFF00..=0xFF7F => (IO_REGISTERS[(address - 0xFF00) as usize].read)(self, address),
+            0xFF80..=0xFFFE => self.hram[(address - 0xFF80) as usize],
+            0xFFFF => self.ie,
+        }
+    }
+
+    pub fn write(&mut self, address: u16, value: u8) {
+        if self.watchpoints.enabled {
+            self.check_watchpoints(address, AccessKind::Write, value);
+        }
+
+        if self.access_log.writes {
+            let old = self.peek(address);
+            self.access_log.record(MemoryAccess { address, kind: AccessKind::Write, old, new: value });
+        }
+
+        if self.oam_dma.conflicts_with(address) {
+            return;
+        }
+
+        match address {
+            0x0000..=0x7FFF => self.cartridge.write_rom(address, value),
+            0x8000..=0x9FFF => self.write_vram(self.vram_offset(address), value),
+            0xA000..=0xBFFF => self.cartridge.write_ram(address, value),
+            0xC000..=0xDFFF => self.write_wram(self.wram_offset(address), value),
+            0xE000..=0xFDFF => self.write_wram(self.wram_offset(address - 0x2000), value),
+            0xFE00..=0xFE9F => self.oam[(address - 0xFE00) as usize] = value,
+            0xFEA0..=0xFEFF => {},
+            0xFF00..=0xFF7F => (IO_REGISTERS[(address - 0xFF00) as usize].write)(self, address, value),
+            0xFF80..=0xFFFE => self.hram[(address - 0xFF80) as usize] = value,
+            0xFFFF => self.ie = value,
+        }
+    }
+
+    /// Advances every component on the bus by `cycles` T-cycles of the CPU.
+    pub fn tick(&mut self, cycles: u32) {
+        // The OAM DMA copies a byte per M-cycle
+        for _ in 0..cycles / 4 {
+            if let Some((source, offset)) = self.oam_dma.step() {
+                self.oam[offset] = self.peek(source);
+            }
+        }
+
+        if self.timer.tick(cycles) {
+            self.request_interrupt(Interrupt::Timer);
+        }
+
+        if self.serial.tick(cycles) {
+            self.request_interrupt(Interrupt::Serial);
+        }
+
+        // The PPU keeps running at the normal speed in double speed mode
+        let dots = if self.double_speed { cycles / 2 } else { cycles };
+        let ppu_interrupts = self.ppu.tick(dots, &self.vram, &self.oam);
+
+        if ppu_interrupts.hblank && self.hdma.hblank_active() {
+            self.copy_hdma_block();
+        }
+
+        if ppu_interrupts.vblank {
+            self.request_interrupt(Interrupt::VBlank);
+        }
+
+        if ppu_interrupts.stat {
+            self.request_interrupt(Interrupt::LcdStat);
+        }
+    }
+
+    fn write_vram(&mut self, offset: usize, value: u8) {
+        self.vram[offset] = value;
+        self.vram_dirty.mark(offset);
+    }
+
+    fn write_wram(&mut self, offset: usize, value: u8) {
+        self.wram[offset] = value;
+        self.wram_dirty.mark(offset);
+    }
+
+    /// Brings the bus back to the state of `checkpoint`, a copy of it. Only the pages of RAM written since the last
+    /// checkpoint are copied, unless `full` is set. The serial output is left alone.
+    pub fn restore_from(&mut self, checkpoint: &Bus, full: bool) {
+        self.cartridge.restore_from(&checkpoint.cartridge, full);
+
+        if full {
+            self.vram.copy_from_slice(&checkpoint.vram);
+            self.wram.copy_from_slice(&checkpoint.wram);
+            self.vram_dirty.clear();
+            self.wram_dirty.clear();
+        } else {
+            self.vram_dirty.restore(&mut self.vram, &checkpoint.vram);
+            self.wram_dirty.restore(&mut self.wram, &checkpoint.wram);
+        }
+
+        self.boot_rom_mapped = checkpoint.boot_rom_mapped;
+        self.dmg_compatibility = checkpoint.dmg_compatibility;
+        self.cgb = checkpoint.cgb;
+        self.vram_bank = checkpoint.vram_bank;
+        self.wram_bank = checkpoint.wram_bank;
+        self.double_speed = checkpoint.double_speed;
+        self.speed_switch_armed = checkpoint.speed_switch_armed;
+        self.oam = checkpoint.oam;
+        self.io = checkpoint.io;
+        self.hram = checkpoint.hram;
+        self.ie = checkpoint.ie;
+        self.joypad = checkpoint.joypad.clone();
+        self.timer = checkpoint.timer.clone();
+
+        let output = mem::take(&mut self.serial.output);
+        let linked = self.serial.linked;
+        self.serial.clone_from(&checkpoint.serial);
+        self.serial.output = output;
+        self.serial.linked = linked;
+
+        self.ppu.clone_from(&checkpoint.ppu);
+        self.oam_dma = checkpoint.oam_dma.clone();
+        self.hdma = checkpoint.hdma.clone();
+        self.dma_stall = checkpoint.dma_stall;
+    }
+
+    /// Forgets the pages written so far, making the current state the reference for [`Bus::restore_from`].
+    pub fn clear_dirty_pages(&mut self) {
+        self.vram_dirty.clear();
+        self.wram_dirty.clear();
+        self.cartridge.clear_dirty_pages();
+    }
+
+    /// Number of VRAM and WRAM pages written since the last checkpoint.
+    pub fn dirty_page_count(&self) -> usize {
+        self.vram_dirty.count() + self.wram_dirty.count()
+    }
+
+    /// Copies the next VRAM DMA block to the current VRAM bank, and halts the CPU for the duration of the copy.
+    fn copy_hdma_block(&mut self) {
+        let (source, destination) = self.hdma.next_block();
+
+        for i in 0..Hdma::BLOCK_SIZE {
+            let value = self.peek(source.wrapping_add(i));
+            self.write_vram(self.vram_offset(destination + i), value);
+        }
+
+        // The copy takes as long in both speeds, which is twice as many CPU cycles in double speed
+        self.dma_stall += Hdma::BLOCK_CYCLES << self.double_speed as u32;
+    }
+
+    /// Returns the T-cycles the CPU has to stay halted for because of VRAM DMA copies, and clears them.
+    pub fn take_dma_stall(&mut self) -> u32 {
+        std::mem::take(&mut self.dma_stall)
+    }
+
+    /// Sets the pressed buttons, one bit per [`crate::joypad::Button`].
+    pub fn set_buttons(&mut self, pressed: u8) {
+        if self.joypad.set_pressed(pressed) {
+            self.request_interrupt(Interrupt::Joypad);
+        }
+    }
+
+    /// Sets the bit of `interrupt` in IF.
+    pub fn request_interrupt(&mut self, interrupt: Interrupt) {
+        self.io[0x0F] |= interrupt.mask();
+    }
+
+    pub fn acknowledge_interrupt(&mut self, interrupt: Interrupt) {
+        self.io[0x0F] &= !interrupt.mask();
+    }
+
+    /// Returns the interrupts that are both requested and enabled.
+    pub fn pending_interrupts(&self) -> u8 {
+        self.ie & self.io[0x0F] & 0x1F
+    }
+
+    fn read_io_memory(&self, address: u16) -> u8 {
+        self.io[(address - 0xFF00) as usize]
+    }
+
+    fn write_io_memory(&mut self, address: u16, value: u8) {
+        self.io[(address - 0xFF00) as usize] = value;
+    }
+}
+
+/// The boot ROM isn't saved, it's part of the configuration like the cartridge ROM.
+impl SaveState for Bus {
+    fn save_state(&self, writer: &mut StateWriter) {
+        self.cartridge.save_state(writer);
+        writer.bool(self.boot_rom_mapped);
+        writer.bool(self.dmg_compatibility);
+        writer.bool(self.cgb);
+        writer.bytes(&self.vram);
+        writer.u8(self.vram_bank);
+        writer.bytes(&self.wram);
+        writer.u8(self.wram_bank);
+        writer.bool(self.double_speed);
+        writer.bool(self.speed_switch_armed);
+        writer.bytes(&self.oam);
+        writer.bytes(&self.io);
+        writer.bytes(&self.hram);
+        writer.u8(self.ie);
+        self.joypad.save_state(writer);
+        self.timer.save_state(writer);
+        self.serial.save_state(writer);
+        self.ppu.save_state(writer);
+        self.oam_dma.save_state(writer);
+        self.hdma.save_state(writer);
+        writer.u32(self.dma_stall);
+    }
+
+    fn load_state(&mut self, reader: &mut StateReader) -> Result<(), Error> {
+        self.cartridge.load_state(reader)?;
+        self.boot_rom_mapped = reader.bool()?;
+        self.dmg_compatibility = reader.bool()?;
+        self.cgb = reader.bool()?;
+        reader.bytes_into(&mut self.vram)?;
+        self.vram_dirty.mark_all();
+        self.vram_bank = reader.u8()?;
+        reader.bytes_into(&mut self.wram)?;
+        self.wram_dirty.mark_all();
+        self.wram_bank = reader.u8()?;
+        self.double_speed = reader.bool()?;
+        self.speed_switch_armed = reader.bool()?;
+        reader.bytes_into(&mut self.oam)?;
+        reader.bytes_into(&mut self.io)?;
+        reader.bytes_into(&mut self.hram)?;
+        self.ie = reader.u8()?;
+        self.joypad.load_state(reader)?;
+        self.timer.load_state(reader)?;
+        self.serial.load_state(reader)?;
+        self.ppu.load_state(reader)?;
+        self.oam_dma.load_state(reader)?;
+        self.hdma.load_state(reader)?;
+        self.dma_stall = reader.u32()?;
+
+        if self.boot_rom_mapped && self.boot_rom.is_empty() {
+            return Err(Error::InvalidSnapshot("the save state was made while running a boot ROM"));
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,774 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::dirty::DirtyPages;
+use crate::error::Error;
+use crate::savestate::{SaveState, StateReader, StateWriter};
+
+const ROM_BANK_SIZE: usize = 0x4000;
+const RAM_BANK_SIZE: usize = 0x2000;
+
+/// Size of the RTC footer appended to save files by VBA-M and BGB (the older footer has a 32-bit timestamp).
+const RTC_FOOTER_SIZE: usize = 48;
+const RTC_FOOTER_SIZE_LEGACY: usize = 44;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MbcKind {
+    None,
+    Mbc1,
+    Mbc3,
+    Mbc5,
+}
+
+/// The parts of the cartridge header (0100h - 014Fh) the emulator cares about.
+#[derive(Debug, Clone)]
+pub struct CartridgeHeader {
+    pub title: String,
+    pub cgb_flag: u8,
+    pub cartridge_type: u8,
+    pub mbc: MbcKind,
+    pub ram_size: usize,
+    pub has_battery: bool,
+    pub has_rtc: bool,
+}
+
+impl CartridgeHeader {
+    pub fn parse(rom: &[u8]) -> Result<Self, Error> {
+        if rom.len() < 0x150 {
+            return Err(Error::RomTooSmall(rom.len()));
+        }
+
+        let cartridge_type = rom[0x147];
+        let (mbc, has_battery, has_rtc) = match cartridge_type {
+            0x00 | 0x08 => (MbcKind::None, false, false),
+            0x09 => (MbcKind::None, true, false),
+            0x01 | 0x02 => (MbcKind::Mbc1, false, false),
+            0x03 => (MbcKind::Mbc1, true, false),
+            0x0F | 0x10 => (MbcKind::Mbc3, true, true),
+            0x11 | 0x12 => (MbcKind::Mbc3, false, false),
+            0x13 => (MbcKind::Mbc3, true, false),
+            0x19 | 0x1A | 0x1C | 0x1D => (MbcKind::Mbc5, false, false),
+            0x1B | 0x1E => (MbcKind::Mbc5, true, false),
+            _ => return Err(Error::UnsupportedCartridgeType(cartridge_type)),
+        };
+
+        let ram_size = match rom[0x149] {
+            0x00 => 0,
+            0x01 => 0x800,
+            0x02 => 0x2000,
+            0x03 => 0x8000,
+            0x04 => 0x20000,
+            0x05 => 0x10000,
+            ram_size => return Err(Error::InvalidRamSize(ram_size)),
+        };
+
+        // The title shrank over time to make room for the manufacturer code and the CGB flag
+        let title = rom[0x134..0x144]
+            .iter()
+            .take_while(|&&c| c.is_ascii_graphic() || c == b' ')
+            .map(|&c| c as char)
+            .collect::<String>()
+            .trim_end()
+            .to_owned();
+
+        Ok(CartridgeHeader {
+            title,
+            cgb_flag: rom[0x143],
+            cartridge_type,
+            mbc,
+            ram_size,
+            has_battery,
+            has_rtc,
+        })
+    }
+
+    /// Whether the game uses the CGB features, either as an enhancement or exclusively.
+    pub fn supports_cgb(&self) -> bool {
+        self.cgb_flag & 0x80 != 0
+    }
+}
+
+/// The MBC3 real-time clock, which keeps counting from the host clock.
+#[derive(Debug, Clone)]
+pub struct Rtc {
+    seconds: u8,
+    minutes: u8,
+    hours: u8,
+    days: u16,
+    halted: bool,
+    day_carry: bool,
+    /// The S, M, H, DL and DH registers as they were when last latched.
+    latched: [u8; 5],
+    /// UNIX timestamp of the last time the clock was brought up to date.
+    last_update: u64,
+    /// Set while the clock must not move with the host clock, see [`Cartridge::freeze_rtc`].
+    frozen: bool,
+}
+
+impl Default for Rtc {
+    fn default() -> Self {
+        Rtc {
+            seconds: 0,
+            minutes: 0,
+            hours: 0,
+            days: 0,
+            halted: false,
+            day_carry: false,
+            latched: [0; 5],
+            last_update: unix_now(),
+            frozen: false,
+        }
+    }
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+impl Rtc {
+    /// Brings the clock up to date with the host clock.
+    pub fn update(&mut self) {
+        if self.frozen {
+            return;
+        }
+
+        let now = unix_now();
+        let elapsed = now.saturating_sub(self.last_update);
+        self.last_update = now;
+
+        if !self.halted {
+            self.advance(elapsed);
+        }
+    }
+
+    /// Advances the clock by `seconds`.
+    pub fn advance(&mut self, seconds: u64) {
+        let total = self.seconds as u64 + seconds;
+        self.seconds = (total % 60) as u8;
+
+        let total = self.minutes as u64 + total / 60;
+        self.minutes = (total % 60) as u8;
+
+        let total = self.hours as u64 + total / 60;
+        self.hours = (total % 24) as u8;
+
+        let total = self.days as u64 + total / 24;
+        self.days = (total % 512) as u16;
+
+        if total >= 512 {
+            self.day_carry = true;
+        }
+    }
+
+    fn registers(&self) -> [u8; 5] {
+        [
+            self.seconds,
+            self.minutes,
+            self.hours,
+            self.days as u8,
+            (self.days >> 8) as u8 & 0x01 | (self.halted as u8) << 6 | (self.day_carry as u8) << 7,
+        ]
+    }
+
+    /// Serializes the clock the way VBA-M and BGB append it to save files.
+    fn to_footer(&self) -> Vec<u8> {
+        let mut footer = Vec::with_capacity(RTC_FOOTER_SIZE);
+
+        for register in self.registers().iter().chain(self.latched.iter()) {
+            footer.extend_from_slice(&(*register as u32).to_le_bytes());
+        }
+
+        footer.extend_from_slice(&self.last_update.to_le_bytes());
+        footer
+    }
+
+    fn from_footer(footer: &[u8]) -> Self {
+        let register = |i: usize| footer[i * 4];
+        let mut timestamp = [0; 8];
+        timestamp[..footer.len() - 40].copy_from_slice(&footer[40..]);
+
+        let mut rtc = Rtc {
+            seconds: register(0) % 60,
+            minutes: register(1) % 60,
+            hours: register(2) % 24,
+            days: register(3) as u16 | (register(4) as u16 & 0x01) << 8,
+            halted: register(4) & 0x40 != 0,
+            day_carry: register(4) & 0x80 != 0,
+            latched: [register(5), register(6), register(7), register(8), register(9)],
+            last_update: u64::from_le_bytes(timestamp),
+            frozen: false,
+        };
+
+        // The clock kept ticking while the save was sitting on disk
+        rtc.update();
+        rtc
+    }
+
+    pub fn latch(&mut self) {
+        self.update();
+        self.latched = self.registers();
+    }
+
+    /// Reads a latched register, `register` being the value written to 4000h (08h - 0Ch).
+    pub fn read(&self, register: u8) -> u8 {
+        self.latched[(register - 0x08) as usize]
+    }
+
+    pub fn write(&mut self, register: u8, value: u8) {
+        self.update();
+
+        match register {
+            0x08 => self.seconds = value % 60,
+            0x09 => self.minutes = value % 60,
+            0x0A => self.hours = value % 24,
+            0x0B => self.days = (self.days & 0x100) | value as u16,
+            _ => {
+                self.days = (self.days & 0xFF) | ((value as u16 & 0x01) << 8);
+                self.halted = value & 0x40 != 0;
+                self.day_carry = value & 0x80 != 0;
+            },
+        }
+    }
+}
+
+impl SaveState for Rtc {
+    fn save_state(&self, writer: &mut StateWriter) {
+        writer.u8(self.seconds);
+        writer.u8(self.minutes);
+        writer.u8(self.hours);
+        writer.u16(self.days);
+        writer.bool(self.halted);
+        writer.bool(self.day_carry);
+        writer.bytes(&self.latched);
+        writer.u64(self.last_update);
+    }
+
+    fn load_state(&mut self, reader: &mut StateReader) -> Result<(), Error> {
+        self.seconds = reader.u8()?;
+        self.minutes = reader.u8()?;
+        self.hours = reader.u8()?;
+        self.days = reader.u16()?;
+        self.halted = reader.bool()?;
+        self.day_carry = reader.bool()?;
+        reader.bytes_into(&mut self.latched)?;
+        self.last_update = reader.u64()?;
+
+        Ok(())
+    }
+}
+
+/// The state of the memory bank controller.
+#[derive(Debug, Clone)]
+enum Mbc {
+    None,
+    Mbc1 {
+        ram_enabled: bool,
+        /// Lower 5 bits of the ROM bank number (2000h - 3FFFh).
+        bank1: u8,
+        /// Upper 2 bits of the ROM bank number, or RAM bank number (4000h - 5FFFh).
+        bank2: u8,
+        /// Banking mode select (6000h - 7FFFh).
+        advanced_mode: bool,
+    },
+    Mbc3 {
+        ram_enabled: bool,
+        rom_bank: u8,
+        /// RAM bank (00h - 03h) or RTC register (08h - 0Ch).
+        ram_bank: u8,
+        rtc: Option<Rtc>,
+        /// Last value written to the latch register, the RTC gets latched on a 00h -> 01h write.
+        latch_value: u8,
+    },
+    Mbc5 {
+        ram_enabled: bool,
+        rom_bank: u16,
+        ram_bank: u8,
+    },
+}
+
+/// The cartridge plugged into the Game Boy: its ROM, its external RAM, and the MBC that maps them.
+#[derive(Debug, Clone)]
+pub struct Cartridge {
+    header: Option<CartridgeHeader>,
+    rom: Vec<u8>,
+    ram: Vec<u8>,
+    /// Pages of `ram` written since the last checkpoint, see [`Cartridge::restore_from`].
+    ram_dirty: DirtyPages,
+    mbc: Mbc,
+}
+
+impl Default for Cartridge {
+    fn default() -> Self {
+        Cartridge::empty()
+    }
+}
+
+impl Cartridge {
+    pub fn new(rom: Vec<u8>) -> Result<Self, Error> {
+        let header = CartridgeHeader::parse(&rom)?;
+
+        let mbc = match header.mbc {
+            MbcKind::None => Mbc::None,
+            MbcKind::Mbc1 => Mbc::Mbc1 {
+                ram_enabled: false,
+                bank1: 1,
+                bank2: 0,
+                advanced_mode: false,
+            },
+            MbcKind::Mbc3 => Mbc::Mbc3 {
+                ram_enabled: false,
+                rom_bank: 1,
+                ram_bank: 0,
+                rtc: header.has_rtc.then(Rtc::default),
+                latch_value: 0xFF,
+            },
+            MbcKind::Mbc5 => Mbc::Mbc5 {
+                ram_enabled: false,
+                rom_bank: 1,
+                ram_bank: 0,
+            },
+        };
+
+        // Pad the ROM to a power of two number of banks, so bank numbers can simply be masked
+        let mut rom = rom;
+        let bank_count = rom.len().div_ceil(ROM_BANK_SIZE).next_power_of_two().max(2);
+        rom.resize(bank_count * ROM_BANK_SIZE, 0xFF);
+
+        Ok(Cartridge {
+            ram: vec![0; header.ram_size],
+            ram_dirty: DirtyPages::new(header.ram_size),
+            header: Some(header),
+            rom,
+            mbc,
+        })
+    }
+
+    /// Puts the MBC back in its power on state. The external RAM and the RTC keep their contents.
+    pub fn reset(&mut self) {
+        match &mut self.mbc {
+            Mbc::None => {},
+            Mbc::Mbc1 { ram_enabled, bank1, bank2, advanced_mode } => {
+                *ram_enabled = false;
+                *bank1 = 1;
+                *bank2 = 0;
+                *advanced_mode = false;
+            },
+            Mbc::Mbc3 { ram_enabled, rom_bank, ram_bank, latch_value, .. } => {
+                *ram_enabled = false;
+                *rom_bank = 1;
+                *ram_bank = 0;
+                *latch_value = 0xFF;
+            },
+            Mbc::Mbc5 { ram_enabled, rom_bank, ram_bank } => {
+                *ram_enabled = false;
+                *rom_bank = 1;
+                *ram_bank = 0;
+            },
+        }
+    }
+
+    /// Brings the external RAM and the MBC back to the state of `checkpoint`, a copy of this cartridge. Only the
+    /// pages of RAM written since the last checkpoint are copied, unless `full` is set.
+    pub fn restore_from(&mut self, checkpoint: &Cartridge, full: bool) {
+        if full {
+            self.ram.copy_from_slice(&checkpoint.ram);
+            self.ram_dirty.clear();
+        } else {
+            self.ram_dirty.restore(&mut self.ram, &checkpoint.ram);
+        }
+
+        self.mbc = checkpoint.mbc.clone();
+    }
+
+    /// Stops the MBC3 clock from catching up with the host clock while `frozen` is set, for replaying steps as they
+    /// were recorded. The time that passed meanwhile is caught up with once unfrozen.
+    pub fn freeze_rtc(&mut self, frozen: bool) {
+        if let Mbc::Mbc3 { rtc: Some(rtc), .. } = &mut self.mbc {
+            rtc.frozen = frozen;
+        }
+    }
+
+    /// Forgets the pages of RAM written so far, making the current state the reference for
+    /// [`Cartridge::restore_from`].
+    pub fn clear_dirty_pages(&mut self) {
+        self.ram_dirty.clear();
+    }
+
+    /// Identifies the ROM in save states: its size and the global checksum from its header.
+    fn fingerprint(&self) -> (u32, u16) {
+        let checksum = match self.rom.get(0x14E..0x150) {
+            Some(checksum) => u16::from_be_bytes([checksum[0], checksum[1]]),
+            None => 0,
+        };
+
+        (self.rom.len() as u32, checksum)
+    }
+
+    /// A cartridge slot with nothing plugged in, which reads as open bus.
+    pub fn empty() -> Self {
+        Cartridge {
+            header: None,
+            rom: Vec::new(),
+            ram: Vec::new(),
+            ram_dirty: DirtyPages::default(),
+            mbc: Mbc::None,
+        }
+    }
+
+    pub fn header(&self) -> Option<&CartridgeHeader> {
+        self.header.as_ref()
+    }
+
+    pub fn rom(&self) -> &[u8] {
+        &self.rom
+    }
+
+    pub fn ram(&self) -> &[u8] {
+        &self.ram
+    }
+
+    /// Returns the external RAM and RTC contents the way they'd be stored in a `.sav` file.
+    pub fn save_data(&self) -> Vec<u8> {
+        let mut data = self.ram.clone();
+
+        if let Mbc::Mbc3 { rtc: Some(rtc), .. } = &self.mbc {
+            data.extend_from_slice(&rtc.to_footer());
+        }
+
+        data
+    }
+
+    /// Loads the contents of a `.sav` file into the external RAM, and the RTC if the file has one.
+    pub fn load_save_data(&mut self, data: &[u8]) -> Result<(), Error> {
+        let has_rtc = matches!(self.mbc, Mbc::Mbc3 { rtc: Some(_), .. });
+
+        if self.ram.is_empty() && !has_rtc {
+            return Err(Error::NoExternalRam);
+        }
+
+        let ram_len = self.ram.len().min(data.len());
+        self.ram[..ram_len].copy_from_slice(&data[..ram_len]);
+        self.ram_dirty.mark_all();
+
+        if let Mbc::Mbc3 { rtc: Some(rtc), .. } = &mut self.mbc {
+            let footer = &data[ram_len..];
+
+            if footer.len() == RTC_FOOTER_SIZE || footer.len() == RTC_FOOTER_SIZE_LEGACY {
+                *rtc = Rtc::from_footer(footer);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn rom_bank_count(&self) -> usize {
+        self.rom.len() / ROM_BANK_SIZE
+    }
+
+    fn ram_bank_count(&self) -> usize {
+        self.ram.len().div_ceil(RAM_BANK_SIZE)
+    }
+
+    /// Returns the ROM banks currently mapped at 0000h - 3FFFh and 4000h - 7FFFh.
+    pub fn rom_banks(&self) -> (usize, usize) {
+        let (low, high) = match self.mbc {
+            Mbc::None => (0, 1),
+            Mbc::Mbc1 { bank1, bank2, advanced_mode, .. } => {
+                let low = if advanced_mode { (bank2 as usize) << 5 } else { 0 };
+                (low, (bank2 as usize) << 5 | bank1 as usize)
+            },
+            Mbc::Mbc3 { rom_bank, .. } => (0, rom_bank as usize),
+            Mbc::Mbc5 { rom_bank, .. } => (0, rom_bank as usize),
+        };
+
+        let mask = self.rom_bank_count().max(1) - 1;
+        (low & mask, high & mask)
+    }
+
+    /// Returns the offset in the external RAM of an address in A000h - BFFFh, if RAM is mapped there.
+    fn ram_offset(&self, address: u16) -> Option<usize> {
+        let bank = match self.mbc {
+            Mbc::None => 0,
+            Mbc::Mbc1 { ram_enabled: false, .. }
+            | Mbc::Mbc3 { ram_enabled: false, .. }
+            | Mbc::Mbc5 { ram_enabled: false, .. } => return None,
+            Mbc::Mbc1 { bank2, advanced_mode, .. } => if advanced_mode { bank2 as usize } else { 0 },
+            Mbc::Mbc3 { ram_bank, .. } if ram_bank >= 0x08 => return None,
+            Mbc::Mbc3 { ram_bank, .. } => ram_bank as usize,
+            Mbc::Mbc5 { ram_bank, .. } => ram_bank as usize,
+        };
+
+        if self.ram.is_empty() {
+            return None;
+        }
+
+        let bank = bank % self.ram_bank_count();
+        let offset = bank * RAM_BANK_SIZE + (address - 0xA000) as usize;
+
+        // Carts with 2 KiB of RAM mirror it across the whole area
+        Some(offset % self.ram.len())
+    }
+
+    /// Reads from the ROM area (0000h - 7FFFh).
+    pub fn read_rom(&self, address: u16) -> u8 {
+        if self.rom.is_empty() {
+            return 0xFF;
+        }
+
+        let (low, high) = self.rom_banks();
+        let bank = if address < 0x4000 { low } else { high };
+
+        self.rom[bank * ROM_BANK_SIZE + (address as usize & 0x3FFF)]
+    }
+
+    /// Writes to the ROM area (0000h - 7FFFh), which is how the MBC gets configured.
+    pub fn write_rom(&mut self, address: u16, value: u8) {
+        match &mut self.mbc {
+            Mbc::None => {},
+            Mbc::Mbc1 { ram_enabled, bank1, bank2, advanced_mode } => match address {
+                0x0000..=0x1FFF => *ram_enabled = value & 0x0F == 0x0A,
+                0x2000..=0x3FFF => *bank1 = (value & 0x1F).max(1),
+                0x4000..=0x5FFF => *bank2 = value & 0x03,
+                _ => *advanced_mode = value & 0x01 != 0,
+            },
+            Mbc::Mbc3 { ram_enabled, rom_bank, ram_bank, rtc, latch_value } => match address {
+                0x0000..=0x1FFF => *ram_enabled = value & 0x0F == 0x0A,
+                0x2000..=0x3FFF => *rom_bank = (value & 0x7F).max(1),
+                0x4000..=0x5FFF => *ram_bank = value,
+                _ => {
+                    if *latch_value == 0x00 && value == 0x01 {
+                        if let Some(rtc) = rtc {
+                            rtc.latch();
+                        }
+                    }
+
+                    *latch_value = value;
+                },
+            },
+            Mbc::Mbc5 { ram_enabled, rom_bank, ram_bank } => match address {
+                0x0000..=0x1FFF => *ram_enabled = value == 0x0A,
+                0x2000..=0x2FFF => *rom_bank = (*rom_bank & 0x100) | value as u16,
+                0x3000..=0x3FFF => *rom_bank = (*rom_bank & 0xFF) | ((value as u16 & 0x01) << 8),
+                0x4000..=0x5FFF => *ram_bank = value & 0x0F,
+                _ => {},
+            },
+        }
+    }
+
+    /// Reads from the external RAM area (A000h - BFFFh).
+    pub fn read_ram(&self, address: u16) -> u8 {
+        if let Mbc::Mbc3 { ram_enabled: true, ram_bank: register @ 0x08..=0x0C, rtc: Some(rtc), .. } = &self.mbc {
+            return rtc.read(*register);
+        }
+
+        match self.ram_offset(address) {
+            Some(offset) => self.ram[offset],
+            None => 0xFF,
+        }
+    }
+
+    /// Writes to the external RAM area (A000h - BFFFh).
+    pub fn write_ram(&mut self, address: u16, value: u8) {
+        if let Mbc::Mbc3 { ram_enabled: true, ram_bank: register @ 0x08..=0x0C, rtc: Some(rtc), .. } = &mut self.mbc {
+            rtc.write(*register, value);
+            return;
+        }
+
+        if let Some(offset) = self.ram_offset(address) {
+            self.ram[offset] = value;
+            self.ram_dirty.mark(offset);
+        }
+    }
+}
+
+/// The ROM isn't saved, only its fingerprint to refuse restoring the state with another game.
+impl SaveState for Cartridge {
+    fn save_state(&self, writer: &mut StateWriter) {
+        let (rom_size, checksum) = self.fingerprint();
+        writer.u32(rom_size);
+        writer.u16(checksum);
+        writer.bytes(&self.ram);
+
+        match &self.mbc {
+            Mbc::None => writer.u8(0),
+            Mbc::Mbc1 { ram_enabled, bank1, bank2, advanced_mode } => {
+                writer.u8(1);
+                writer.bool(*ram_enabled);
+                writer.u8(*bank1);
+                writer.u8(*bank2);
+                writer.bool(*advanced_mode);
+            },
+            Mbc::Mbc3 { ram_enabled, rom_bank, ram_bank, rtc, latch_value } => {
+                writer.u8(3);
+                writer.bool(*ram_enabled);
+                writer.u8(*rom_bank);
+                writer.u8(*ram_bank);
+                writer.u8(*latch_value);
+
+                if let Some(rtc) = rtc {
+                    rtc.save_state(writer);
+                }
+            },
+            Mbc::Mbc5 { ram_enabled, rom_bank, ram_bank } => {
+                writer.u8(5);
+                writer.bool(*ram_enabled);
+                writer.u16(*rom_bank);
+                writer.u8(*ram_bank);
+            },
+        }
+    }
+
+    fn load_state(&mut self, reader: &mut StateReader) -> Result<(), Error> {
+        let rom_size = reader.u32()?;
+        let checksum = reader.u16()?;
+
+        if (rom_size, checksum) != self.fingerprint() {
+            return Err(Error::InvalidSnapshot("the save state was made with another ROM"));
+        }
+
+        reader.bytes_into(&mut self.ram)?;
+        self.ram_dirty.mark_all();
+
+        let kind = reader.u8()?;
+
+        match &mut self.mbc {
+            Mbc::None if kind == 0 => {},
+            Mbc::Mbc1 { ram_enabled, bank1, bank2, advanced_mode } if kind == 1 => {
+                *ram_enabled = reader.bool()?;
+                *bank1 = reader.u8()?;
+                *bank2 = reader.u8()?;
+                *advanced_mode = reader.bool()?;
+            },
+            Mbc::Mbc3 { ram_enabled, rom_bank, ram_bank, rtc, latch_value } if kind == 3 => {
+                *ram_enabled = reader.bool()?;
+                *rom_bank = reader.u8()?;
+                *ram_bank = reader.u8()?;
+                *latch_value = reader.u8()?;
+
+                if let Some(rtc) = rtc {
+                    rtc.load_state(reader)?;
+                }
+            },
+            Mbc::Mbc5 { ram_enabled, rom_bank, ram_bank } if kind == 5 => {
+                *ram_enabled = reader.bool()?;
+                *rom_bank = reader.u16()?;
+                *ram_bank = reader.u8()?;
+            },
+            _ => return Err(Error::InvalidSnapshot("MBC mismatch")),
+        }
+
+        Ok(())
+    }
+}
+
+/// A byte of external RAM that differs between two dumps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RamChange {
+    pub bank: usize,
+    /// Address of the byte when its bank is mapped at A000h - BFFFh.
+    pub address: u16,
+    pub old: u8,
+    pub new: u8,
+}
+
+/// Compares two dumps of the external RAM, typically taken before and after running some code.
+pub fn diff_ram(old: &[u8], new: &[u8]) -> Vec<RamChange> {
+    old.iter()
+        .zip(new.iter())
+        .enumerate()
+        .filter(|(_, (old, new))| old != new)
+        .map(|(offset, (&old, &new))| RamChange {
+            bank: offset / RAM_BANK_SIZE,
+            address: 0xA000 + (offset % RAM_BANK_SIZE) as u16,
+            old,
+            new,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A ROM of `banks` banks starting with their number, low byte first.
+    fn banked_rom(cartridge_type: u8, ram_size: u8, banks: usize) -> Vec<u8> {
+        let mut rom = vec![0; banks * ROM_BANK_SIZE];
+
+        for (bank, data) in rom.chunks_mut(ROM_BANK_SIZE).enumerate() {
+            data[0..2].copy_from_slice(&(bank as u16).to_le_bytes());
+        }
+
+        rom[0x147] = cartridge_type;
+        rom[0x149] = ram_size;
+        rom
+    }
+
+    fn mapped_bank(cartridge: &Cartridge, address: u16) -> u16 {
+        u16::from_le_bytes([cartridge.read_rom(address), cartridge.read_rom(address + 1)])
+    }
+
+    #[test]
+    fn mbc1_bank_mapping() {
+        let mut cartridge = Cartridge::new(banked_rom(0x03, 0x03, 128)).unwrap();
+
+        assert_eq!(mapped_bank(&cartridge, 0x4000), 1);
+
+        // Bank 0 can't be mapped at 4000h, 20h maps 21h
+        cartridge.write_rom(0x2000, 0x00);
+        assert_eq!(mapped_bank(&cartridge, 0x4000), 1);
+        cartridge.write_rom(0x2000, 0x05);
+        cartridge.write_rom(0x4000, 0x02);
+        assert_eq!(mapped_bank(&cartridge, 0x4000), 0x45);
+        cartridge.write_rom(0x2000, 0x20);
+        assert_eq!(mapped_bank(&cartridge, 0x4000), 0x41);
+
+        // The upper bits only apply to 0000h - 3FFFh and the RAM in advanced mode
+        assert_eq!(mapped_bank(&cartridge, 0x0000), 0);
+        cartridge.write_rom(0x0000, 0x0A);
+        cartridge.write_ram(0xA000, 0x12);
+        cartridge.write_rom(0x6000, 0x01);
+        assert_eq!(mapped_bank(&cartridge, 0x0000), 0x40);
+        cartridge.write_ram(0xA000, 0x34);
+
+        assert_eq!(cartridge.ram()[0], 0x12);
+        assert_eq!(cartridge.ram()[2 * RAM_BANK_SIZE], 0x34);
+    }
+
+    #[test]
+    fn mbc3_bank_mapping() {
+        let mut cartridge = Cartridge::new(banked_rom(0x13, 0x03, 128)).unwrap();
+
+        cartridge.write_rom(0x2000, 0x00);
+        assert_eq!(mapped_bank(&cartridge, 0x4000), 1);
+        cartridge.write_rom(0x2000, 0x7F);
+        assert_eq!(mapped_bank(&cartridge, 0x4000), 0x7F);
+        assert_eq!(mapped_bank(&cartridge, 0x0000), 0);
+
+        // RAM is only readable once enabled
+        cartridge.write_rom(0x4000, 0x03);
+        cartridge.write_ram(0xA000, 0x12);
+        assert_eq!(cartridge.read_ram(0xA000), 0xFF);
+        cartridge.write_rom(0x0000, 0x0A);
+        cartridge.write_ram(0xA000, 0x12);
+
+        assert_eq!(cartridge.read_ram(0xA000), 0x12);
+        assert_eq!(cartridge.ram()[3 * RAM_BANK_SIZE], 0x12);
+    }
+
+    #[test]
+    fn mbc5_bank_mapping() {
+        let mut cartridge = Cartridge::new(banked_rom(0x1B, 0x04, 512)).unwrap();
+
+        // Unlike the other MBCs, bank 0 can be mapped at 4000h
+        cartridge.write_rom(0x2000, 0x00);
+        assert_eq!(mapped_bank(&cartridge, 0x4000), 0);
+        cartridge.write_rom(0x2000, 0x34);
+        cartridge.write_rom(0x3000, 0x01);
+        assert_eq!(mapped_bank(&cartridge, 0x4000), 0x134);
+
+        cartridge.write_rom(0x0000, 0x0A);
+        cartridge.write_rom(0x4000, 0x0F);
+        cartridge.write_ram(0xBFFF, 0x12);
+
+        assert_eq!(cartridge.ram()[16 * RAM_BANK_SIZE - 1], 0x12);
+    }
+}
@@ -0,0 +1,160 @@
+use std::ops::RangeInclusive;
+
+use crate::gb::{GameboyNamedRegister16, GameboyNamedRegister8};
+
+/// An extra condition for a breakpoint to be hit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BreakpointCondition {
+    Register8(GameboyNamedRegister8, u8),
+    Register16(GameboyNamedRegister16, u16),
+    /// The byte at an address has a value.
+    Memory(u16, u8),
+}
+
+/// Stops execution right before the instruction at an address gets executed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Breakpoint {
+    pub address: u16,
+    /// Bank that must be mapped at `address`: ROM bank for 0000h - 7FFFh, VRAM bank for 8000h - 9FFFh, WRAM bank
+    /// for D000h - DFFFh. Ignored elsewhere.
+    pub bank: Option<usize>,
+    pub condition: Option<BreakpointCondition>,
+}
+
+impl Breakpoint {
+    pub fn new(address: u16) -> Self {
+        Breakpoint {
+            address,
+            bank: None,
+            condition: None,
+        }
+    }
+
+    pub fn in_bank(mut self, bank: usize) -> Self {
+        self.bank = Some(bank);
+        self
+    }
+
+    pub fn when(mut self, condition: BreakpointCondition) -> Self {
+        self.condition = Some(condition);
+        self
+    }
+}
+
+/// The kinds of access a watchpoint triggers on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct WatchAccess {
+    pub read: bool,
+    pub write: bool,
+    pub execute: bool,
+}
+
+impl WatchAccess {
+    pub const READ: WatchAccess = WatchAccess { read: true, write: false, execute: false };
+    pub const WRITE: WatchAccess = WatchAccess { read: false, write: true, execute: false };
+    pub const READ_WRITE: WatchAccess = WatchAccess { read: true, write: true, execute: false };
+    pub const EXECUTE: WatchAccess = WatchAccess { read: false, write: false, execute: true };
+}
+
+/// Stops execution when an address in a range is accessed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Watchpoint {
+    pub range: RangeInclusive<u16>,
+    pub access: WatchAccess,
+}
+
+impl Watchpoint {
+    pub fn new(range: RangeInclusive<u16>, access: WatchAccess) -> Self {
+        Watchpoint { range, access }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccessKind {
+    Read,
+    Write,
+    Execute,
+}
+
+/// A memory access that triggered a watchpoint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WatchHit {
+    /// Index of the watchpoint, as returned by [`crate::gb::Gameboy::add_watchpoint`].
+    pub id: usize,
+    pub address: u16,
+    pub kind: AccessKind,
+    /// The byte read or written, or the opcode for an execution.
+    pub value: u8,
+    /// Address of the instruction that made the access.
+    pub pc: u16,
+}
+
+/// Why [`crate::gb::Gameboy::run_until`] returned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StopReason {
+    /// PC reached the breakpoint with this index, as returned by [`crate::gb::Gameboy::add_breakpoint`]. The
+    /// instruction there hasn't been executed yet.
+    Breakpoint(usize),
+    /// A read or write watchpoint was hit by the last executed instruction, or an execute watchpoint by the next one.
+    Watchpoint(WatchHit),
+    /// A software breakpoint (`LD B, B`) was executed.
+    SoftwareBreakpoint,
+    /// The cycle budget ran out.
+    CycleLimit,
+}
+
+/// Watchpoints as seen by the bus. Kept out of the way of memory accesses when there are none.
+#[derive(Debug, Clone, Default)]
+pub struct Watchpoints {
+    /// Slots indexed by watchpoint id, `None` once removed.
+    watchpoints: Vec<Option<Watchpoint>>,
+    /// Set when any read or write watchpoint exists, which is the only thing checked on every access.
+    pub enabled: bool,
+}
+
+impl Watchpoints {
+    pub fn add(&mut self, watchpoint: Watchpoint) -> usize {
+        self.watchpoints.push(Some(watchpoint));
+        self.update_enabled();
+
+        self.watchpoints.len() - 1
+    }
+
+    /// Returns the removed watchpoint, `None` if there was none with this id.
+    pub fn remove(&mut self, id: usize) -> Option<Watchpoint> {
+        let watchpoint = self.watchpoints.get_mut(id)?.take();
+        self.update_enabled();
+
+        watchpoint
+    }
+
+    fn update_enabled(&mut self) {
+        self.enabled = self
+            .watchpoints
+            .iter()
+            .flatten()
+            .any(|watchpoint| watchpoint.access.read || watchpoint.access.write);
+    }
+
+    /// Returns the id of the first watchpoint covering an access, if any.
+    pub fn find(&self, address: u16, kind: AccessKind) -> Option<usize> {
+        self.watchpoints.iter().position(|watchpoint| {
+            watchpoint.as_ref().is_some_and(|watchpoint| {
+                let access = match kind {
+                    AccessKind::Read => watchpoint.access.read,
+                    AccessKind::Write => watchpoint.access.write,
+                    AccessKind::Execute => watchpoint.access.execute,
+                };
+
+                access && watchpoint.range.contains(&address)
+            })
+        })
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (usize, &Watchpoint)> {
+        self.watchpoints
+            .iter()
+            .enumerate()
+            .filter_map(|(id, watchpoint)| watchpoint.as_ref().map(|watchpoint| (id, watchpoint)))
+    }
+}
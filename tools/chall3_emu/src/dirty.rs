@@ -0,0 +1,86 @@
+/// Tracks which pages of a memory buffer were written to, so that restoring it only copies those.
+#[derive(Debug, Clone, Default)]
+pub struct DirtyPages {
+    /// One bit per page.
+    bits: Vec<u64>,
+    /// Number of pages in the buffer, the bits past it are never set.
+    pages: usize,
+}
+
+impl DirtyPages {
+    pub const PAGE_SIZE: usize = 0x100;
+
+    /// Tracks a buffer of `size` bytes, with no page dirty.
+    pub fn new(size: usize) -> Self {
+        let pages = size.div_ceil(DirtyPages::PAGE_SIZE);
+
+        DirtyPages {
+            bits: vec![0; pages.div_ceil(64)],
+            pages,
+        }
+    }
+
+    pub fn mark(&mut self, offset: usize) {
+        let page = offset / DirtyPages::PAGE_SIZE;
+        self.bits[page / 64] |= 1 << (page % 64);
+    }
+
+    pub fn mark_all(&mut self) {
+        self.bits.fill(u64::MAX);
+
+        if let Some(last) = self.bits.last_mut() {
+            if !self.pages.is_multiple_of(64) {
+                *last = (1 << (self.pages % 64)) - 1;
+            }
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.bits.fill(0);
+    }
+
+    pub fn count(&self) -> usize {
+        self.bits.iter().map(|bits| bits.count_ones() as usize).sum()
+    }
+
+    /// Copies the dirty pages of `source` back into `destination`, then clears them.
+    pub fn restore(&mut self, destination: &mut [u8], source: &[u8]) {
+        for (word, bits) in self.bits.iter_mut().enumerate() {
+            let mut remaining = *bits;
+
+            while remaining != 0 {
+                let page = word * 64 + remaining.trailing_zeros() as usize;
+                let start = page * DirtyPages::PAGE_SIZE;
+                remaining &= remaining - 1;
+
+                if start >= destination.len() {
+                    continue;
+                }
+
+                let end = (start + DirtyPages::PAGE_SIZE).min(destination.len());
+                destination[start..end].copy_from_slice(&source[start..end]);
+            }
+
+            *bits = 0;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mark_all_stops_at_the_end_of_the_buffer() {
+        let mut dirty = DirtyPages::new(0x2000);
+        dirty.mark_all();
+
+        assert_eq!(dirty.count(), 0x2000 / DirtyPages::PAGE_SIZE);
+
+        let mut destination = vec![0; 0x2000];
+        dirty.restore(&mut destination, &[1; 0x2000]);
+
+        assert!(destination.iter().all(|&byte| byte == 1));
+        assert_eq!(dirty.count(), 0);
+    }
+}
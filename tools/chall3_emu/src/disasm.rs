@@ -0,0 +1,460 @@
+use std::fmt::Write;
+use std::ops::RangeInclusive;
+
+use crate::gb::{
+    Gameboy, GameboyInstruction, GameboyInstructionCondition, GameboyInstructionFamily, GameboyInstructionOperand,
+    GameboyInstructionPointerOp, GameboyNamedRegister16, GameboyNamedRegister8, InstructionFetch,
+};
+use crate::symbols::{Symbol, SymbolTable};
+
+/// An instruction, or bytes that can't be written as one, rendered as RGBDS source.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DisassembledLine {
+    pub address: u16,
+    pub bytes: Vec<u8>,
+    /// The source for `bytes`, without indentation, such as `ld bc, $D200`.
+    pub text: String,
+}
+
+/// Reads the operands of an instruction from any memory.
+struct MemoryFetch<'a> {
+    read: &'a dyn Fn(u16) -> u8,
+    address: u16,
+}
+
+impl InstructionFetch for MemoryFetch<'_> {
+    fn fetch(&mut self) -> u8 {
+        let byte = (self.read)(self.address);
+        self.address = self.address.wrapping_add(1);
+
+        byte
+    }
+}
+
+fn register8_name(register: GameboyNamedRegister8) -> &'static str {
+    match register {
+        GameboyNamedRegister8::A => "a",
+        GameboyNamedRegister8::B => "b",
+        GameboyNamedRegister8::C => "c",
+        GameboyNamedRegister8::D => "d",
+        GameboyNamedRegister8::E => "e",
+        GameboyNamedRegister8::H => "h",
+        GameboyNamedRegister8::L => "l",
+    }
+}
+
+fn register16_name(register: GameboyNamedRegister16) -> &'static str {
+    match register {
+        GameboyNamedRegister16::AF => "af",
+        GameboyNamedRegister16::BC => "bc",
+        GameboyNamedRegister16::DE => "de",
+        GameboyNamedRegister16::HL => "hl",
+        GameboyNamedRegister16::SP => "sp",
+        GameboyNamedRegister16::PC => "pc",
+    }
+}
+
+fn condition_name(condition: GameboyInstructionCondition) -> &'static str {
+    match condition {
+        GameboyInstructionCondition::NZ => "nz",
+        GameboyInstructionCondition::Z => "z",
+        GameboyInstructionCondition::NC => "nc",
+        GameboyInstructionCondition::C => "c",
+    }
+}
+
+/// Formats a signed offset as `$05` or `-$03`.
+fn signed_hex(value: i8) -> String {
+    if value < 0 {
+        format!("-${:02X}", value.unsigned_abs())
+    } else {
+        format!("${:02X}", value)
+    }
+}
+
+fn data_line(address: u16, bytes: &[u8]) -> DisassembledLine {
+    let values = bytes.iter().map(|byte| format!("${:02X}", byte)).collect::<Vec<_>>();
+
+    DisassembledLine {
+        address,
+        bytes: bytes.to_vec(),
+        text: format!("db {}", values.join(", ")),
+    }
+}
+
+/// Returns the section type of the memory area of an address, and whether it's RAM, where code can only be placed
+/// with a `LOAD` block.
+fn section_type(address: u16) -> Option<(&'static str, bool)> {
+    match address {
+        0x0000..=0x3FFF => Some(("ROM0", false)),
+        0x4000..=0x7FFF => Some(("ROMX", false)),
+        0x8000..=0x9FFF => Some(("VRAM", true)),
+        0xA000..=0xBFFF => Some(("SRAM", true)),
+        0xC000..=0xCFFF => Some(("WRAM0", true)),
+        0xD000..=0xDFFF => Some(("WRAMX", true)),
+        0xFF80..=0xFFFE => Some(("HRAM", true)),
+        _ => None,
+    }
+}
+
+/// Renders SM83 code as RGBDS source, with the labels of a symbol table for jump targets and addresses.
+#[derive(Debug, Clone)]
+pub struct Disassembler<'a> {
+    symbols: &'a SymbolTable,
+    /// Bank mapped in each 4 KiB of the address space, `None` where it's unknown or the area isn't banked.
+    banks: [Option<usize>; 16],
+    /// Last global label written, whose local labels can be written as `.local`.
+    scope: Option<String>,
+}
+
+impl<'a> Disassembler<'a> {
+    /// A disassembler for code in ROM bank 0, where only labels of bank 0 are used for 0000h - 3FFFh.
+    pub fn new(symbols: &'a SymbolTable) -> Self {
+        let mut banks = [None; 16];
+        banks[..4].fill(Some(0));
+
+        Disassembler {
+            symbols,
+            banks,
+            scope: None,
+        }
+    }
+
+    /// A disassembler using the labels of the banks currently mapped in the system.
+    pub fn for_gameboy(gb: &'a Gameboy) -> Self {
+        let mut banks = [None; 16];
+
+        for (area, bank) in banks.iter_mut().enumerate() {
+            *bank = gb.bank_at((area as u16) << 12);
+        }
+
+        Disassembler {
+            symbols: gb.symbols(),
+            banks,
+            scope: None,
+        }
+    }
+
+    /// Uses the labels of a ROM bank for 4000h - 7FFFh.
+    pub fn with_rom_bank(mut self, bank: usize) -> Self {
+        self.banks[4..8].fill(Some(bank));
+        self
+    }
+
+    fn bank_at(&self, address: u16) -> Option<usize> {
+        self.banks[(address >> 12) as usize]
+    }
+
+    /// Name of a label as it can be written at this point of the listing.
+    fn label_name(&self, symbol: &Symbol) -> String {
+        if let (Some((parent, local)), Some(scope)) = (symbol.name.split_once('.'), &self.scope) {
+            if parent == scope {
+                return format!(".{}", local);
+            }
+        }
+
+        symbol.name.clone()
+    }
+
+    /// Writes an address as the label there, `$XXXX` if there is none.
+    fn address_operand(&self, address: u16) -> String {
+        match self.symbols.lookup(address, self.bank_at(address)) {
+            Some((symbol, 0)) => self.label_name(symbol),
+            _ => format!("${:04X}", address),
+        }
+    }
+
+    /// Writes a jump target as the label there or `label+offset`, `$XXXX` if there is no label before it.
+    fn target_operand(&self, address: u16) -> String {
+        match self.symbols.lookup(address, self.bank_at(address)) {
+            Some((symbol, 0)) => self.label_name(symbol),
+            Some((symbol, offset)) => format!("{}+${:X}", self.label_name(symbol), offset),
+            None => format!("${:04X}", address),
+        }
+    }
+
+    fn operand(&self, instruction: &GameboyInstruction, operand: GameboyInstructionOperand) -> String {
+        match operand {
+            GameboyInstructionOperand::Register8(register) => register8_name(register).to_owned(),
+            GameboyInstructionOperand::Register16(register) => register16_name(register).to_owned(),
+            GameboyInstructionOperand::Pointer(register, None) => format!("[{}]", register16_name(register)),
+            GameboyInstructionOperand::Pointer(_, Some(GameboyInstructionPointerOp::Increment)) => "[hli]".to_owned(),
+            GameboyInstructionOperand::Pointer(_, Some(GameboyInstructionPointerOp::Decrement)) => "[hld]".to_owned(),
+            GameboyInstructionOperand::Immediate8(value) => match instruction.instruction_family() {
+                // The operand of LDH is the low byte of an address in FF00h - FFFFh
+                GameboyInstructionFamily::LDH => format!("[{}]", self.address_operand(0xFF00 | value as u16)),
+                GameboyInstructionFamily::BIT | GameboyInstructionFamily::RES | GameboyInstructionFamily::SET => {
+                    value.to_string()
+                },
+                _ => format!("${:02X}", value),
+            },
+            GameboyInstructionOperand::ImmediateSigned8(value) => signed_hex(value),
+            GameboyInstructionOperand::Immediate16(value) => self.address_operand(value),
+            GameboyInstructionOperand::Address(address) => match instruction.instruction_family() {
+                GameboyInstructionFamily::RST => format!("${:02X}", address),
+                _ => self.target_operand(address),
+            },
+            GameboyInstructionOperand::AddressPointer(address) => format!("[{}]", self.address_operand(address)),
+            GameboyInstructionOperand::HighPointer(register) => format!("[{}]", register8_name(register)),
+            GameboyInstructionOperand::StackPointerOffset(offset) if offset < 0 => {
+                format!("sp - ${:02X}", offset.unsigned_abs())
+            },
+            GameboyInstructionOperand::StackPointerOffset(offset) => format!("sp + ${:02X}", offset),
+        }
+    }
+
+    /// Disassembles the instruction at `address`, reading memory through `read`. At most `available` bytes are used,
+    /// an instruction that doesn't fit is written as data.
+    pub fn instruction(&self, read: &dyn Fn(u16) -> u8, address: u16, available: usize) -> DisassembledLine {
+        let mut fetch = MemoryFetch {
+            read,
+            address: address.wrapping_add(1),
+        };
+        let opcode = read(address);
+        let instruction = GameboyInstruction::decode(&mut fetch, opcode);
+
+        let size = instruction.size() as usize;
+        let bytes = (0..size.min(available.max(1)))
+            .map(|offset| read(address.wrapping_add(offset as u16)))
+            .collect::<Vec<u8>>();
+
+        if bytes.len() < size {
+            return data_line(address, &bytes[..1]);
+        }
+
+        let family = instruction.instruction_family();
+        let mnemonic = format!("{:?}", family).to_lowercase();
+        let mut operands = Vec::new();
+
+        if let Some(condition) = instruction.condition() {
+            operands.push(condition_name(condition).to_owned());
+        }
+
+        match family {
+            GameboyInstructionFamily::ILLEGAL => return data_line(address, &bytes),
+            // rgbasm always assembles STOP with a 00h padding byte
+            GameboyInstructionFamily::STOP if bytes[1] != 0x00 => return data_line(address, &bytes),
+            GameboyInstructionFamily::JR => {
+                if let Some(GameboyInstructionOperand::ImmediateSigned8(offset)) = instruction.operand1() {
+                    let target = address.wrapping_add(2).wrapping_add(offset as u16);
+                    operands.push(self.target_operand(target));
+                }
+            },
+            GameboyInstructionFamily::ADD
+            | GameboyInstructionFamily::ADC
+            | GameboyInstructionFamily::SUB
+            | GameboyInstructionFamily::SBC
+                if instruction.operand1() == Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)) =>
+            {
+                // Written with A implied, like AND, XOR, OR and CP
+                if let Some(operand) = instruction.operand2() {
+                    operands.push(self.operand(&instruction, operand));
+                }
+            },
+            _ => {
+                for operand in [instruction.operand1(), instruction.operand2()].into_iter().flatten() {
+                    operands.push(self.operand(&instruction, operand));
+                }
+            },
+        }
+
+        let text = if operands.is_empty() {
+            mnemonic
+        } else {
+            format!("{} {}", mnemonic, operands.join(", "))
+        };
+
+        DisassembledLine { address, bytes, text }
+    }
+
+    /// Disassembles the instruction at `address` in a range ending at `end`. Bytes are written as data when the
+    /// instruction would run past the end of the range or over a label.
+    fn line(&self, read: &dyn Fn(u16) -> u8, address: usize, end: usize) -> DisassembledLine {
+        let line = self.instruction(read, address as u16, end - address + 1);
+
+        let split = (1..line.bytes.len()).find(|&offset| {
+            let inner = (address + offset) as u16;
+            self.symbols.labels_at(inner, self.bank_at(inner)).next().is_some()
+        });
+
+        match split {
+            Some(split) => data_line(address as u16, &line.bytes[..split]),
+            None => line,
+        }
+    }
+
+    /// Disassembles `range` from the first byte, instruction after instruction. Bytes are written as data when an
+    /// instruction would run past the end of the range or over a label.
+    pub fn lines(&self, read: &dyn Fn(u16) -> u8, range: RangeInclusive<u16>) -> Vec<DisassembledLine> {
+        let mut lines = Vec::new();
+        let end = *range.end() as usize;
+        let mut address = *range.start() as usize;
+
+        while address <= end {
+            let line = self.line(read, address, end);
+            address += line.bytes.len();
+            lines.push(line);
+        }
+
+        lines
+    }
+
+    /// Writes `range` as RGBDS source: a section at the start of the range, the labels of the symbol table, and an
+    /// instruction per line.
+    pub fn listing(&mut self, read: &dyn Fn(u16) -> u8, range: RangeInclusive<u16>) -> String {
+        let mut listing = String::new();
+        let start = *range.start();
+        let bank = self.bank_at(start);
+        let bank_suffix = |bank: Option<usize>| match bank {
+            Some(bank) if start >= 0x4000 => format!(", BANK[${:X}]", bank),
+            _ => String::new(),
+        };
+
+        let load = match section_type(start) {
+            Some((section_type, false)) => {
+                let _ = writeln!(
+                    listing,
+                    "SECTION \"Disassembly ${:04X}\", {}[${:04X}]{}",
+                    start,
+                    section_type,
+                    start,
+                    bank_suffix(bank)
+                );
+                false
+            },
+            Some((section_type, true)) => {
+                // Code for RAM is stored in ROM and copied there, so it's assembled for the RAM address
+                let _ = writeln!(listing, "SECTION \"Disassembly ${:04X}\", ROM0", start);
+                let _ = writeln!(
+                    listing,
+                    "LOAD \"Disassembly ${:04X} (RAM)\", {}[${:04X}]{}",
+                    start,
+                    section_type,
+                    start,
+                    bank_suffix(bank)
+                );
+                true
+            },
+            None => {
+                let _ = writeln!(listing, "; ${:04X} can't be the address of a section", start);
+                false
+            },
+        };
+
+        // Local labels are written in full until their parent is
+        self.scope = None;
+
+        let end = *range.end() as usize;
+        let mut address = start as usize;
+
+        while address <= end {
+            let labels = self
+                .symbols
+                .labels_at(address as u16, self.bank_at(address as u16))
+                .cloned()
+                .collect::<Vec<Symbol>>();
+
+            for label in labels {
+                if label.name.contains('.') {
+                    let name = self.label_name(&label);
+
+                    if name.starts_with('.') {
+                        let _ = writeln!(listing, "{}", name);
+                    } else {
+                        let _ = writeln!(listing, "{}:", name);
+                    }
+                } else {
+                    let _ = writeln!(listing);
+                    let _ = writeln!(listing, "{}:", label.name);
+                    self.scope = Some(label.name);
+                }
+            }
+
+            // Decoded once the labels are written, for the operands to use the local labels of the new scope
+            let line = self.line(read, address, end);
+            address += line.bytes.len();
+
+            let _ = writeln!(listing, "    {}", line.text);
+        }
+
+        if load {
+            let _ = writeln!(listing, "ENDL");
+        }
+
+        listing
+    }
+}
+
+/// Disassembles a range of a ROM file, as it's seen with `bank` mapped at 4000h - 7FFFh.
+pub fn disassemble_rom(rom: &[u8], bank: usize, range: RangeInclusive<u16>, symbols: &SymbolTable) -> String {
+    let read = |address: u16| {
+        let offset = match address {
+            0x0000..=0x3FFF => address as usize,
+            _ => bank * 0x4000 + (address as usize & 0x3FFF),
+        };
+
+        rom.get(offset).copied().unwrap_or(0xFF)
+    };
+
+    Disassembler::new(symbols).with_rom_bank(bank).listing(&read, range)
+}
+
+/// Disassembles memory as the CPU currently sees it, with the banks that are mapped.
+pub fn disassemble_memory(gb: &Gameboy, range: RangeInclusive<u16>) -> String {
+    let read = |address: u16| gb.bus.peek(address);
+
+    Disassembler::for_gameboy(gb).listing(&read, range)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn listing_uses_labels() {
+        let mut rom = vec![0; 0x8000];
+        rom[0x150..0x166].copy_from_slice(&[
+            0x21, 0x00, 0xC0, 0x3E, 0x05, 0x22, 0x3D, 0x20, 0xFC, 0xE0, 0x40, 0xCB, 0x7F, 0xC6, 0x01, 0xF8, 0xFE, 0xCD,
+            0x55, 0x01, 0xD3, 0xC3,
+        ]);
+        let symbols = SymbolTable::parse("00:0150 Start\n00:0155 Start.loop\n00:C000 wBuffer\n").unwrap();
+
+        assert_eq!(
+            disassemble_rom(&rom, 1, 0x0150..=0x0165, &symbols),
+            concat!(
+                "SECTION \"Disassembly $0150\", ROM0[$0150]\n",
+                "\n",
+                "Start:\n",
+                "    ld hl, wBuffer\n",
+                "    ld a, $05\n",
+                ".loop\n",
+                "    ld [hli], a\n",
+                "    dec a\n",
+                "    jr nz, .loop\n",
+                "    ldh [$FF40], a\n",
+                "    bit 7, a\n",
+                "    add $01\n",
+                "    ld hl, sp - $02\n",
+                "    call .loop\n",
+                "    db $D3\n",
+                "    db $C3\n",
+            )
+        );
+    }
+
+    #[test]
+    fn code_for_ram_is_in_a_load_block() {
+        // jr @
+        let read = |address: u16| if address == 0xC000 { 0x18 } else { 0xFE };
+
+        assert_eq!(
+            Disassembler::new(&SymbolTable::default()).listing(&read, 0xC000..=0xC001),
+            concat!(
+                "SECTION \"Disassembly $C000\", ROM0\n",
+                "LOAD \"Disassembly $C000 (RAM)\", WRAM0[$C000]\n",
+                "    jr $C000\n",
+                "ENDL\n",
+            )
+        );
+    }
+}
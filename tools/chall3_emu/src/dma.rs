@@ -0,0 +1,196 @@
+use crate::error::Error;
+use crate::savestate::{SaveState, StateReader, StateWriter};
+
+/// The OAM DMA controller (FF46h), which copies 160 bytes to OAM, one per M-cycle.
+#[derive(Debug, Clone, Default)]
+pub struct OamDma {
+    /// Source address written to FF46h, waiting for the setup M-cycle to pass.
+    pending: Option<u16>,
+    /// Source address of the transfer in progress.
+    source: Option<u16>,
+    /// Index of the next byte to copy.
+    index: u16,
+}
+
+impl OamDma {
+    pub const LENGTH: u16 = 0xA0;
+
+    /// Starts a transfer from `value` * 100h, after a setup M-cycle during which the previous one keeps going.
+    pub fn start(&mut self, value: u8) {
+        self.pending = Some((value as u16) << 8);
+    }
+
+    /// Whether a transfer is in progress, during which the CPU can't access OAM nor the bus of the source.
+    pub fn active(&self) -> bool {
+        self.source.is_some()
+    }
+
+    /// Whether `address` is on the same bus as the source of the transfer in progress: the VRAM bus or the
+    /// external bus.
+    pub fn conflicts_with(&self, address: u16) -> bool {
+        let Some(source) = self.source else {
+            return false;
+        };
+
+        let on_vram_bus = |address: u16| (0x8000..=0x9FFF).contains(&address);
+
+        match address {
+            0xFE00..=0xFEFF => true,
+            0xFF00..=0xFFFF => false,
+            _ => on_vram_bus(address) == on_vram_bus(source),
+        }
+    }
+
+    /// Advances the transfer by one M-cycle, returns the source address and OAM offset of the byte to copy, if any.
+    pub fn step(&mut self) -> Option<(u16, usize)> {
+        let copy = self.source.map(|source| {
+            let copy = (source + self.index, self.index as usize);
+            self.index += 1;
+
+            if self.index == OamDma::LENGTH {
+                self.source = None;
+            }
+
+            copy
+        });
+
+        if let Some(source) = self.pending.take() {
+            self.source = Some(source);
+            self.index = 0;
+        }
+
+        copy
+    }
+}
+
+fn save_address(writer: &mut StateWriter, address: Option<u16>) {
+    writer.bool(address.is_some());
+    writer.u16(address.unwrap_or(0));
+}
+
+fn load_address(reader: &mut StateReader) -> Result<Option<u16>, Error> {
+    let is_some = reader.bool()?;
+    let address = reader.u16()?;
+
+    Ok(is_some.then_some(address))
+}
+
+impl SaveState for OamDma {
+    fn save_state(&self, writer: &mut StateWriter) {
+        save_address(writer, self.pending);
+        save_address(writer, self.source);
+        writer.u16(self.index);
+    }
+
+    fn load_state(&mut self, reader: &mut StateReader) -> Result<(), Error> {
+        self.pending = load_address(reader)?;
+        self.source = load_address(reader)?;
+        self.index = reader.u16()?;
+
+        Ok(())
+    }
+}
+
+/// The CGB VRAM DMA controller (FF51h - FF55h), copying blocks of 16 bytes to VRAM either all at once (general
+/// purpose DMA) or one block per HBlank (HBlank DMA).
+#[derive(Debug, Clone)]
+pub struct Hdma {
+    source: u16,
+    destination: u16,
+    /// Number of blocks left to copy minus one, as read from FF55h.
+    remaining: u8,
+    /// Set while an HBlank DMA is in progress.
+    hblank: bool,
+}
+
+impl Default for Hdma {
+    fn default() -> Self {
+        Hdma {
+            source: 0,
+            destination: 0x8000,
+            remaining: 0x7F,
+            hblank: false,
+        }
+    }
+}
+
+impl Hdma {
+    pub const BLOCK_SIZE: u16 = 0x10;
+    /// Time the CPU is halted for while a block is copied, in T-cycles at normal speed.
+    pub const BLOCK_CYCLES: u32 = 32;
+
+    pub fn hblank_active(&self) -> bool {
+        self.hblank
+    }
+
+    pub fn read(&self, address: u16) -> u8 {
+        match address {
+            0xFF55 => ((!self.hblank as u8) << 7) | self.remaining,
+            // The source and destination registers are write-only
+            _ => 0xFF,
+        }
+    }
+
+    /// Writes a register, returns the number of blocks to copy right away for a general purpose DMA.
+    pub fn write(&mut self, address: u16, value: u8) -> u8 {
+        match address {
+            0xFF51 => self.source = (self.source & 0x00FF) | (value as u16) << 8,
+            0xFF52 => self.source = (self.source & 0xFF00) | (value & 0xF0) as u16,
+            0xFF53 => self.destination = 0x8000 | (self.destination & 0x00FF) | ((value & 0x1F) as u16) << 8,
+            0xFF54 => self.destination = (self.destination & 0xFF00) | (value & 0xF0) as u16,
+            _ => {
+                if self.hblank && value & 0x80 == 0 {
+                    // Writing bit 7 cleared during an HBlank DMA cancels it
+                    self.hblank = false;
+                    return 0;
+                }
+
+                self.remaining = value & 0x7F;
+
+                if value & 0x80 != 0 {
+                    self.hblank = true;
+                } else {
+                    return self.remaining + 1;
+                }
+            },
+        }
+
+        0
+    }
+
+    /// Returns the source and destination addresses of the next block to copy, and moves on to the one after.
+    pub fn next_block(&mut self) -> (u16, u16) {
+        let block = (self.source, self.destination);
+
+        self.source = self.source.wrapping_add(Hdma::BLOCK_SIZE);
+        self.destination = 0x8000 | (self.destination.wrapping_add(Hdma::BLOCK_SIZE) & 0x1FF0);
+
+        match self.remaining.checked_sub(1) {
+            Some(remaining) => self.remaining = remaining,
+            None => {
+                self.remaining = 0x7F;
+                self.hblank = false;
+            },
+        }
+
+        block
+    }
+}
+
+impl SaveState for Hdma {
+    fn save_state(&self, writer: &mut StateWriter) {
+        writer.u16(self.source);
+        writer.u16(self.destination);
+        writer.u8(self.remaining);
+        writer.bool(self.hblank);
+    }
+
+    fn load_state(&mut self, reader: &mut StateReader) -> Result<(), Error> {
+        self.source = reader.u16()?;
+        self.destination = reader.u16()?;
+        self.remaining = reader.u8()?;
+        self.hblank = reader.bool()?;
+
+        Ok(())
+    }
+}
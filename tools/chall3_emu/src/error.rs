@@ -0,0 +1,111 @@
+use std::{fmt, io};
+
+use crate::gb::{GameboyInstructionFamily, GameboyInstructionOperand, GameboyRegisters, SuspiciousExecution};
+
+#[derive(Debug)]
+pub enum Error {
+    IoError(io::Error),
+    /// The ROM is too small to even contain a header.
+    RomTooSmall(usize),
+    UnsupportedCartridgeType(u8),
+    InvalidRamSize(u8),
+    /// A save file was given for a cartridge with no external RAM or RTC.
+    NoExternalRam,
+    /// The save file was flushed before being loaded.
+    NoSaveFile,
+    PngError(png::EncodingError),
+    /// The boot ROM is neither a 256-byte DMG one nor a 2304-byte CGB one.
+    InvalidBootRomSize(usize),
+    /// The save state is corrupted, or was made with another game.
+    InvalidSnapshot(&'static str),
+    /// The save state was made by a version of the emulator with another format.
+    UnsupportedSnapshotVersion(u32),
+    /// A line of an input movie couldn't be parsed, with its line number.
+    InvalidInputMovie(usize, String),
+    /// A line of a symbol file couldn't be parsed, with its line number.
+    InvalidSymbolFile(usize, String),
+    /// Neither an address nor a known label.
+    UnknownSymbol(String),
+    /// A line of assembly couldn't be assembled, with its line number.
+    InvalidAssembly(usize, String),
+    /// The binary trace is corrupted, or was written by a version of the emulator with another format.
+    InvalidTrace(&'static str),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EmuErrorKind {
+    /// An instruction of this family was decoded with operands it can't be executed with.
+    InvalidOperands(GameboyInstructionFamily),
+    /// An operand was used where an 8-bit value was expected.
+    InvalidOperand(GameboyInstructionOperand),
+    /// The CPU executed one of the unused opcodes and is now locked up.
+    IllegalOpcode,
+    /// The instruction was refused by [`crate::gb::SuspiciousExecutionPolicy::Stop`].
+    SuspiciousExecution(SuspiciousExecution),
+    /// A snapshot of the execution history couldn't be restored to go back in time.
+    InvalidHistorySnapshot,
+}
+
+/// An error raised while executing an instruction, with the CPU state right before it.
+#[derive(Debug, Clone)]
+pub struct EmuError {
+    pub kind: EmuErrorKind,
+    pub pc: u16,
+    pub opcode: u8,
+    pub registers: GameboyRegisters,
+}
+
+impl fmt::Display for EmuErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EmuErrorKind::InvalidOperands(family) => write!(f, "invalid operands for {:?} instruction", family),
+            EmuErrorKind::InvalidOperand(operand) => write!(f, "invalid 8-bit operand {:?}", operand),
+            EmuErrorKind::IllegalOpcode => write!(f, "illegal opcode, the CPU is locked up"),
+            EmuErrorKind::SuspiciousExecution(reason) => write!(f, "suspicious execution: {:?}", reason),
+            EmuErrorKind::InvalidHistorySnapshot => write!(f, "a snapshot of the history couldn't be restored"),
+        }
+    }
+}
+
+impl fmt::Display for EmuError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} at PC={:04X} (opcode {:02X}), AF={:04X} BC={:04X} DE={:04X} HL={:04X} SP={:04X}",
+            self.kind,
+            self.pc,
+            self.opcode,
+            self.registers.af,
+            self.registers.bc,
+            self.registers.de,
+            self.registers.hl,
+            self.registers.sp,
+        )
+    }
+}
+
+impl std::error::Error for EmuError {}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::IoError(error) => write!(f, "I/O error: {}", error),
+            Error::RomTooSmall(size) => write!(f, "ROM is too small ({} bytes) to contain a header", size),
+            Error::UnsupportedCartridgeType(cartridge_type) => write!(f, "unsupported cartridge type {:02X}h", cartridge_type),
+            Error::InvalidRamSize(ram_size) => write!(f, "invalid RAM size {:02X}h", ram_size),
+            Error::NoExternalRam => write!(f, "the cartridge has no external RAM to load a save into"),
+            Error::NoSaveFile => write!(f, "no save file was loaded"),
+            Error::PngError(error) => write!(f, "PNG encoding error: {}", error),
+            Error::InvalidBootRomSize(size) => write!(f, "invalid boot ROM size ({} bytes)", size),
+            Error::InvalidSnapshot(reason) => write!(f, "invalid save state: {}", reason),
+            Error::UnsupportedSnapshotVersion(version) => write!(f, "unsupported save state version {}", version),
+            Error::InvalidInputMovie(line, reason) => write!(f, "invalid input movie at line {}: {}", line, reason),
+            Error::InvalidSymbolFile(line, reason) => write!(f, "invalid symbol file at line {}: {}", line, reason),
+            Error::UnknownSymbol(name) => write!(f, "unknown symbol or invalid address {:?}", name),
+            Error::InvalidAssembly(line, reason) => write!(f, "assembly error at line {}: {}", line, reason),
+            Error::InvalidTrace(reason) => write!(f, "invalid trace: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
@@ -1,4 +1,21 @@
-#[derive(Debug)]
+use std::{fs, mem};
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::bus::{Bus, Interrupt};
+use crate::cartridge::Cartridge;
+use crate::debug::{AccessKind, Breakpoint, BreakpointCondition, StopReason, WatchHit, Watchpoint};
+use crate::error::{EmuError, EmuErrorKind, Error};
+use crate::hooks::{HookContext, Hooks, MemoryAccess};
+use crate::joypad::InputMovie;
+use crate::ppu;
+use crate::rewind::{History, HistoryConfig, HistoryEntry};
+use crate::savestate::{SaveState, Snapshot};
+use crate::symbols::SymbolTable;
+use crate::trace::Tracer;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum GameboyNamedRegister8 {
     A,
     B,
@@ -9,7 +26,7 @@ pub enum GameboyNamedRegister8 {
     L,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum GameboyNamedRegister16 {
     AF,
     BC,
@@ -27,7 +44,7 @@ pub enum GameboyRegisterFlags {
     C = 0b0001_0000,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct GameboyRegisters {
     pub af: u16,
     pub bc: u16,
@@ -55,6 +72,26 @@ impl GameboyRegisters {
         GameboyRegisters::default()
     }
 
+    /// Register values left by the boot ROM of `model`, `cgb_game` telling whether the CGB one started the game in
+    /// CGB mode or in DMG compatibility mode.
+    pub fn post_boot(model: GameboyModel, cgb_game: bool) -> Self {
+        let (af, bc, de, hl) = match model {
+            GameboyModel::Dmg => (0x01B0, 0x0013, 0x00D8, 0x014D),
+            GameboyModel::Mgb => (0xFFB0, 0x0013, 0x00D8, 0x014D),
+            GameboyModel::Cgb if cgb_game => (0x1180, 0x0000, 0xFF56, 0x000D),
+            GameboyModel::Cgb => (0x1180, 0x0000, 0x0008, 0x007C),
+        };
+
+        GameboyRegisters {
+            af,
+            bc,
+            de,
+            hl,
+            pc: 0x0100,
+            sp: 0xFFFE,
+        }
+    }
+
     pub fn get_flag(&self, flag: GameboyRegisterFlags) -> bool {
         self.af & flag as u16 != 0
     }
@@ -76,7 +113,6 @@ impl GameboyRegisters {
             GameboyNamedRegister8::E => self.de as u8,
             GameboyNamedRegister8::H => (self.hl >> 8) as u8,
             GameboyNamedRegister8::L => self.hl as u8,
-            _ => panic!("Invalid register: {:?}", register),
         }
     }
 
@@ -89,40 +125,40 @@ impl GameboyRegisters {
             GameboyNamedRegister8::E => self.de = (self.de & 0xFF00) | value as u16,
             GameboyNamedRegister8::H => self.hl = (self.hl & 0x00FF) | (value as u16) << 8,
             GameboyNamedRegister8::L => self.hl = (self.hl & 0xFF00) | value as u16,
-            _ => panic!("Invalid register: {:?}", register),
         }
     }
 
     pub fn get_reg16(&self, register: &GameboyNamedRegister16) -> u16 {
         match register {
+            GameboyNamedRegister16::AF => self.af,
             GameboyNamedRegister16::BC => self.bc,
             GameboyNamedRegister16::DE => self.de,
             GameboyNamedRegister16::HL => self.hl,
             GameboyNamedRegister16::SP => self.sp,
             GameboyNamedRegister16::PC => self.pc,
-            _ => panic!("Invalid register: {:?}", register),
         }
     }
 
     pub fn set_reg16(&mut self, register: &GameboyNamedRegister16, value: u16) {
         match register {
+            // The lower nibble of F is hardwired to zero
+            GameboyNamedRegister16::AF => self.af = value & 0xFFF0,
             GameboyNamedRegister16::BC => self.bc = value,
             GameboyNamedRegister16::DE => self.de = value,
             GameboyNamedRegister16::HL => self.hl = value,
             GameboyNamedRegister16::SP => self.sp = value,
             GameboyNamedRegister16::PC => self.pc = value,
-            _ => panic!("Invalid register: {:?}", register),
         }
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum GameboyInstructionPointerOp {
     Increment,
     Decrement,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum GameboyInstructionOperand {
     Register8(GameboyNamedRegister8),
     Register16(GameboyNamedRegister16),
@@ -131,9 +167,16 @@ pub enum GameboyInstructionOperand {
     ImmediateSigned8(i8),
     Immediate16(u16),
     Address(u16),
+    /// `(n16)`: memory at an immediate 16-bit address.
+    AddressPointer(u16),
+    /// `(C)`: memory at `$FF00` + the register.
+    HighPointer(GameboyNamedRegister8),
+    /// `SP+e8`, only used by `LD HL, SP+e8`.
+    StackPointerOffset(i8),
 }
 
-#[derive(Debug)]
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum GameboyInstructionFamily {
     NOP,
     JP,
@@ -155,9 +198,35 @@ pub enum GameboyInstructionFamily {
     ADD,
     BIT,
     SWAP,
+    ADC,
+    SUB,
+    SBC,
+    RLCA,
+    RRCA,
+    RLA,
+    RRA,
+    DAA,
+    CPL,
+    CCF,
+    HALT,
+    STOP,
+    RETI,
+    EI,
+    RST,
+    RLC,
+    RRC,
+    RL,
+    RR,
+    SLA,
+    SRA,
+    SRL,
+    RES,
+    SET,
+    /// One of the 11 unused opcodes, which hang the CPU when executed.
+    ILLEGAL,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum GameboyInstructionCondition {
     NZ,
     Z,
@@ -177,1217 +246,7069 @@ pub struct GameboyInstruction {
     size: u8,
 }
 
-#[derive(Debug)]
-pub struct Gameboy {
-    pub registers: GameboyRegisters,
-    pub memory: [u8; 0xFFFF],
-}
-
-impl Gameboy {
-    pub fn new() -> Self {
-        Gameboy {
-            registers: GameboyRegisters::default(),
-            memory: [0; 0xFFFF],
-        }
+impl GameboyInstruction {
+    pub fn opcode(&self) -> u8 {
+        self.opcode
     }
 
-    pub fn load_rom(&mut self, rom: Vec<u8>) {
-        self.memory[0..32768].copy_from_slice(&rom);
+    pub fn mnemonic(&self) -> &'static str {
+        self.mnemonic
     }
 
-    pub fn reset(&mut self) {
-        self.registers = GameboyRegisters::default();
+    pub fn instruction_family(&self) -> GameboyInstructionFamily {
+        self.instruction_family
     }
 
-    pub fn fetch(&mut self) -> u8 {
-        let opcode = self.memory[self.registers.pc as usize];
-        self.registers.pc += 1;
-
-        opcode
+    pub fn operand1(&self) -> Option<GameboyInstructionOperand> {
+        self.operand1
     }
 
-    pub fn fetch16(&mut self) -> u16 {
-        let low = self.fetch() as u16;
-        let high = self.fetch() as u16;
+    pub fn operand2(&self) -> Option<GameboyInstructionOperand> {
+        self.operand2
+    }
 
-        (high << 8) | low
+    pub fn condition(&self) -> Option<GameboyInstructionCondition> {
+        self.condition
     }
 
-    pub fn write_byte(&mut self, address: u16, byte: u8) {
-        self.memory[address as usize] = byte;
+    pub fn cycles(&self) -> u8 {
+        self.cycles
     }
 
-    pub fn write_bytes(&mut self, address: u16, bytes: &[u8]) {
-        self.memory[address as usize..(address + bytes.len() as u16) as usize].copy_from_slice(bytes);
+    pub fn size(&self) -> u8 {
+        self.size
     }
+}
 
-    pub fn read_bytes(&mut self, address: u16, length: u16) -> Vec<u8> {
-        self.memory[address as usize..(address + length) as usize].to_vec()
+/// Where the operands of an instruction are read from while decoding it: the CPU fetching at PC, or memory being
+/// disassembled.
+pub trait InstructionFetch {
+    fn fetch(&mut self) -> u8;
+
+    fn fetch16(&mut self) -> u16 {
+        let low = self.fetch() as u16;
+        let high = self.fetch() as u16;
+
+        (high << 8) | low
     }
+}
 
-    pub fn decode_prefix(&mut self, opcode: u8) -> GameboyInstruction {
+impl GameboyInstruction {
+    /// Decodes the instruction following a CB prefix.
+    pub fn decode_prefix(opcode: u8) -> GameboyInstruction {
         match opcode {
-            0x37 => GameboyInstruction {
+            0x00 => GameboyInstruction {
                 opcode,
-                mnemonic: "SWAP A",
-                instruction_family: GameboyInstructionFamily::SWAP,
-                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
+                mnemonic: "RLC B",
+                instruction_family: GameboyInstructionFamily::RLC,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::B)),
                 operand2: None,
                 condition: None,
                 cycles: 8,
                 size: 2,
             },
-            0x7C => GameboyInstruction {
+            0x01 => GameboyInstruction {
                 opcode,
-                mnemonic: "BIT 7, H",
-                instruction_family: GameboyInstructionFamily::BIT,
-                operand1: Some(GameboyInstructionOperand::Immediate8(7)),
-                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::H)),
+                mnemonic: "RLC C",
+                instruction_family: GameboyInstructionFamily::RLC,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::C)),
+                operand2: None,
                 condition: None,
                 cycles: 8,
                 size: 2,
             },
-            _ => panic!("Unknown prefix opcode: {:#04X}", opcode)
-        }
-    }
-
-    pub fn decode(&mut self, opcode: u8) -> GameboyInstruction {
-        match opcode {
-            0x00 => GameboyInstruction {
+            0x02 => GameboyInstruction {
                 opcode,
-                mnemonic: "NOP",
-                instruction_family: GameboyInstructionFamily::NOP,
-                operand1: None,
+                mnemonic: "RLC D",
+                instruction_family: GameboyInstructionFamily::RLC,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::D)),
                 operand2: None,
                 condition: None,
-                cycles: 4,
-                size: 1,
+                cycles: 8,
+                size: 2,
             },
-            0x01 => GameboyInstruction {
+            0x03 => GameboyInstruction {
                 opcode,
-                mnemonic: "LD BC, n16",
-                instruction_family: GameboyInstructionFamily::LD,
-                operand1: Some(GameboyInstructionOperand::Register16(GameboyNamedRegister16::BC)),
-                operand2: Some(GameboyInstructionOperand::Immediate16(self.fetch16())),
+                mnemonic: "RLC E",
+                instruction_family: GameboyInstructionFamily::RLC,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::E)),
+                operand2: None,
                 condition: None,
-                cycles: 12,
-                size: 3,
+                cycles: 8,
+                size: 2,
             },
-            0x03 => GameboyInstruction {
+            0x04 => GameboyInstruction {
                 opcode,
-                mnemonic: "INC BC",
-                instruction_family: GameboyInstructionFamily::INC,
-                operand1: Some(GameboyInstructionOperand::Register16(GameboyNamedRegister16::BC)),
+                mnemonic: "RLC H",
+                instruction_family: GameboyInstructionFamily::RLC,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::H)),
                 operand2: None,
                 condition: None,
                 cycles: 8,
-                size: 1,
+                size: 2,
             },
-            0x09 => GameboyInstruction {
+            0x05 => GameboyInstruction {
                 opcode,
-                mnemonic: "ADD HL, BC",
-                instruction_family: GameboyInstructionFamily::ADD,
-                operand1: Some(GameboyInstructionOperand::Register16(GameboyNamedRegister16::HL)),
-                operand2: Some(GameboyInstructionOperand::Register16(GameboyNamedRegister16::BC)),
+                mnemonic: "RLC L",
+                instruction_family: GameboyInstructionFamily::RLC,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::L)),
+                operand2: None,
                 condition: None,
                 cycles: 8,
-                size: 1,
+                size: 2,
             },
-            0x0B => GameboyInstruction {
+            0x06 => GameboyInstruction {
                 opcode,
-                mnemonic: "DEC BC",
-                instruction_family: GameboyInstructionFamily::DEC,
-                operand1: Some(GameboyInstructionOperand::Register16(GameboyNamedRegister16::BC)),
+                mnemonic: "RLC (HL)",
+                instruction_family: GameboyInstructionFamily::RLC,
+                operand1: Some(GameboyInstructionOperand::Pointer(GameboyNamedRegister16::HL, None)),
+                operand2: None,
+                condition: None,
+                cycles: 16,
+                size: 2,
+            },
+            0x07 => GameboyInstruction {
+                opcode,
+                mnemonic: "RLC A",
+                instruction_family: GameboyInstructionFamily::RLC,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
                 operand2: None,
                 condition: None,
                 cycles: 8,
-                size: 1,
+                size: 2,
             },
-            0x0C => GameboyInstruction {
+            0x08 => GameboyInstruction {
                 opcode,
-                mnemonic: "INC C",
-                instruction_family: GameboyInstructionFamily::INC,
-                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::C)),
+                mnemonic: "RRC B",
+                instruction_family: GameboyInstructionFamily::RRC,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::B)),
                 operand2: None,
                 condition: None,
-                cycles: 4,
-                size: 1,
+                cycles: 8,
+                size: 2,
             },
-            0x0D => GameboyInstruction {
+            0x09 => GameboyInstruction {
                 opcode,
-                mnemonic: "DEC C",
-                instruction_family: GameboyInstructionFamily::DEC,
+                mnemonic: "RRC C",
+                instruction_family: GameboyInstructionFamily::RRC,
                 operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::C)),
                 operand2: None,
                 condition: None,
-                cycles: 4,
-                size: 1,
+                cycles: 8,
+                size: 2,
             },
-            0x0E => GameboyInstruction {
+            0x0A => GameboyInstruction {
                 opcode,
-                mnemonic: "LD C, n8",
-                instruction_family: GameboyInstructionFamily::LD,
-                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::C)),
-                operand2: Some(GameboyInstructionOperand::Immediate8(self.fetch())),
+                mnemonic: "RRC D",
+                instruction_family: GameboyInstructionFamily::RRC,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::D)),
+                operand2: None,
                 condition: None,
                 cycles: 8,
                 size: 2,
             },
-            0x11 => GameboyInstruction {
+            0x0B => GameboyInstruction {
                 opcode,
-                mnemonic: "LD DE, n16",
-                instruction_family: GameboyInstructionFamily::LD,
-                operand1: Some(GameboyInstructionOperand::Register16(GameboyNamedRegister16::DE)),
-                operand2: Some(GameboyInstructionOperand::Immediate16(self.fetch16())),
+                mnemonic: "RRC E",
+                instruction_family: GameboyInstructionFamily::RRC,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::E)),
+                operand2: None,
                 condition: None,
-                cycles: 12,
-                size: 3,
+                cycles: 8,
+                size: 2,
             },
-            0x12 => GameboyInstruction {
+            0x0C => GameboyInstruction {
                 opcode,
-                mnemonic: "LD (DE), A",
-                instruction_family: GameboyInstructionFamily::LD,
-                operand1: Some(GameboyInstructionOperand::Pointer(GameboyNamedRegister16::DE, None)),
-                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
+                mnemonic: "RRC H",
+                instruction_family: GameboyInstructionFamily::RRC,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::H)),
+                operand2: None,
                 condition: None,
                 cycles: 8,
-                size: 1,
+                size: 2,
             },
-            0x13 => GameboyInstruction {
+            0x0D => GameboyInstruction {
                 opcode,
-                mnemonic: "INC DE",
-                instruction_family: GameboyInstructionFamily::INC,
-                operand1: Some(GameboyInstructionOperand::Register16(GameboyNamedRegister16::DE)),
+                mnemonic: "RRC L",
+                instruction_family: GameboyInstructionFamily::RRC,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::L)),
                 operand2: None,
                 condition: None,
                 cycles: 8,
-                size: 1,
+                size: 2,
             },
-            0x18 => GameboyInstruction {
+            0x0E => GameboyInstruction {
                 opcode,
-                mnemonic: "JR n8",
-                instruction_family: GameboyInstructionFamily::JR,
-                operand1: Some(GameboyInstructionOperand::ImmediateSigned8(self.fetch() as i8)),
+                mnemonic: "RRC (HL)",
+                instruction_family: GameboyInstructionFamily::RRC,
+                operand1: Some(GameboyInstructionOperand::Pointer(GameboyNamedRegister16::HL, None)),
                 operand2: None,
                 condition: None,
-                cycles: 12,
+                cycles: 16,
                 size: 2,
             },
-            0x1A => GameboyInstruction {
+            0x0F => GameboyInstruction {
                 opcode,
-                mnemonic: "LD A, (DE)",
-                instruction_family: GameboyInstructionFamily::LD,
+                mnemonic: "RRC A",
+                instruction_family: GameboyInstructionFamily::RRC,
                 operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
-                operand2: Some(GameboyInstructionOperand::Pointer(GameboyNamedRegister16::DE, None)),
+                operand2: None,
                 condition: None,
                 cycles: 8,
-                size: 1,
+                size: 2,
             },
-            0x1B => GameboyInstruction {
+            0x10 => GameboyInstruction {
                 opcode,
-                mnemonic: "DEC DE",
-                instruction_family: GameboyInstructionFamily::DEC,
-                operand1: Some(GameboyInstructionOperand::Register16(GameboyNamedRegister16::DE)),
+                mnemonic: "RL B",
+                instruction_family: GameboyInstructionFamily::RL,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::B)),
                 operand2: None,
                 condition: None,
                 cycles: 8,
-                size: 1,
+                size: 2,
             },
-            0x1C => GameboyInstruction {
+            0x11 => GameboyInstruction {
                 opcode,
-                mnemonic: "INC E",
-                instruction_family: GameboyInstructionFamily::INC,
-                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::E)),
+                mnemonic: "RL C",
+                instruction_family: GameboyInstructionFamily::RL,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::C)),
                 operand2: None,
                 condition: None,
-                cycles: 4,
-                size: 1,
+                cycles: 8,
+                size: 2,
             },
-            0x1E => GameboyInstruction {
+            0x12 => GameboyInstruction {
                 opcode,
-                mnemonic: "LD E, n8",
-                instruction_family: GameboyInstructionFamily::LD,
-                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::E)),
-                operand2: Some(GameboyInstructionOperand::Immediate8(self.fetch())),
+                mnemonic: "RL D",
+                instruction_family: GameboyInstructionFamily::RL,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::D)),
+                operand2: None,
                 condition: None,
                 cycles: 8,
                 size: 2,
             },
-            0x20 => GameboyInstruction {
+            0x13 => GameboyInstruction {
                 opcode,
-                mnemonic: "JR NZ, n8",
-                instruction_family: GameboyInstructionFamily::JR,
-                operand1: Some(GameboyInstructionOperand::ImmediateSigned8(self.fetch() as i8)),
+                mnemonic: "RL E",
+                instruction_family: GameboyInstructionFamily::RL,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::E)),
                 operand2: None,
-                condition: Some(GameboyInstructionCondition::NZ),
+                condition: None,
                 cycles: 8,
                 size: 2,
             },
-            0x21 => GameboyInstruction {
+            0x14 => GameboyInstruction {
                 opcode,
-                mnemonic: "LD HL, n16",
-                instruction_family: GameboyInstructionFamily::LD,
-                operand1: Some(GameboyInstructionOperand::Register16(GameboyNamedRegister16::HL)),
-                operand2: Some(GameboyInstructionOperand::Immediate16(self.fetch16())),
+                mnemonic: "RL H",
+                instruction_family: GameboyInstructionFamily::RL,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::H)),
+                operand2: None,
                 condition: None,
-                cycles: 12,
-                size: 3,
+                cycles: 8,
+                size: 2,
             },
-            0x22 => GameboyInstruction {
+            0x15 => GameboyInstruction {
                 opcode,
-                mnemonic: "LD (HL+), A",
-                instruction_family: GameboyInstructionFamily::LD,
-                operand1: Some(GameboyInstructionOperand::Pointer(GameboyNamedRegister16::HL, Some(GameboyInstructionPointerOp::Increment))),
-                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
+                mnemonic: "RL L",
+                instruction_family: GameboyInstructionFamily::RL,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::L)),
+                operand2: None,
                 condition: None,
                 cycles: 8,
-                size: 1,
+                size: 2,
             },
-            0x23 => GameboyInstruction {
+            0x16 => GameboyInstruction {
                 opcode,
-                mnemonic: "INC HL",
-                instruction_family: GameboyInstructionFamily::INC,
-                operand1: Some(GameboyInstructionOperand::Register16(GameboyNamedRegister16::HL)),
+                mnemonic: "RL (HL)",
+                instruction_family: GameboyInstructionFamily::RL,
+                operand1: Some(GameboyInstructionOperand::Pointer(GameboyNamedRegister16::HL, None)),
                 operand2: None,
                 condition: None,
-                cycles: 8,
-                size: 1,
+                cycles: 16,
+                size: 2,
             },
-            0x28 => GameboyInstruction {
+            0x17 => GameboyInstruction {
                 opcode,
-                mnemonic: "JR Z, n8",
-                instruction_family: GameboyInstructionFamily::JR,
-                operand1: Some(GameboyInstructionOperand::ImmediateSigned8(self.fetch() as i8)),
+                mnemonic: "RL A",
+                instruction_family: GameboyInstructionFamily::RL,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
                 operand2: None,
-                condition: Some(GameboyInstructionCondition::Z),
+                condition: None,
                 cycles: 8,
                 size: 2,
             },
-            0x2A => GameboyInstruction {
+            0x18 => GameboyInstruction {
                 opcode,
-                mnemonic: "LD A, (HL+)",
-                instruction_family: GameboyInstructionFamily::LD,
-                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
-                operand2: Some(GameboyInstructionOperand::Pointer(GameboyNamedRegister16::HL, Some(GameboyInstructionPointerOp::Increment))),
+                mnemonic: "RR B",
+                instruction_family: GameboyInstructionFamily::RR,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::B)),
+                operand2: None,
                 condition: None,
                 cycles: 8,
-                size: 1,
+                size: 2,
             },
-            0x31 => GameboyInstruction {
+            0x19 => GameboyInstruction {
                 opcode,
-                mnemonic: "LD SP, n16",
-                instruction_family: GameboyInstructionFamily::LD,
-                operand1: Some(GameboyInstructionOperand::Register16(GameboyNamedRegister16::SP)),
-                operand2: Some(GameboyInstructionOperand::Immediate16(self.fetch16())),
+                mnemonic: "RR C",
+                instruction_family: GameboyInstructionFamily::RR,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::C)),
+                operand2: None,
                 condition: None,
-                cycles: 12,
-                size: 3,
+                cycles: 8,
+                size: 2,
             },
-            0x37 => GameboyInstruction {
+            0x1A => GameboyInstruction {
                 opcode,
-                mnemonic: "SCF",
-                instruction_family: GameboyInstructionFamily::SCF,
-                operand1: None,
+                mnemonic: "RR D",
+                instruction_family: GameboyInstructionFamily::RR,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::D)),
                 operand2: None,
                 condition: None,
-                cycles: 4,
-                size: 1,
+                cycles: 8,
+                size: 2,
             },
-            0x38 => GameboyInstruction {
+            0x1B => GameboyInstruction {
                 opcode,
-                mnemonic: "JR C, n8",
-                instruction_family: GameboyInstructionFamily::JR,
-                operand1: Some(GameboyInstructionOperand::ImmediateSigned8(self.fetch() as i8)),
+                mnemonic: "RR E",
+                instruction_family: GameboyInstructionFamily::RR,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::E)),
                 operand2: None,
-                condition: Some(GameboyInstructionCondition::C),
+                condition: None,
                 cycles: 8,
                 size: 2,
             },
-            0x3E => GameboyInstruction {
+            0x1C => GameboyInstruction {
                 opcode,
-                mnemonic: "LD A, n8",
-                instruction_family: GameboyInstructionFamily::LD,
-                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
-                operand2: Some(GameboyInstructionOperand::Immediate8(self.fetch())),
+                mnemonic: "RR H",
+                instruction_family: GameboyInstructionFamily::RR,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::H)),
+                operand2: None,
                 condition: None,
                 cycles: 8,
                 size: 2,
             },
-            0x40 => GameboyInstruction {
+            0x1D => GameboyInstruction {
                 opcode,
-                mnemonic: "LD B, B",
-                instruction_family: GameboyInstructionFamily::LD,
-                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::B)),
-                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::B)),
+                mnemonic: "RR L",
+                instruction_family: GameboyInstructionFamily::RR,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::L)),
+                operand2: None,
                 condition: None,
-                cycles: 4,
-                size: 1,
+                cycles: 8,
+                size: 2,
             },
-            0x45 => GameboyInstruction {
+            0x1E => GameboyInstruction {
                 opcode,
-                mnemonic: "LD B, L",
-                instruction_family: GameboyInstructionFamily::LD,
-                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::B)),
-                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::L)),
+                mnemonic: "RR (HL)",
+                instruction_family: GameboyInstructionFamily::RR,
+                operand1: Some(GameboyInstructionOperand::Pointer(GameboyNamedRegister16::HL, None)),
+                operand2: None,
                 condition: None,
-                cycles: 4,
-                size: 1,
+                cycles: 16,
+                size: 2,
             },
-            0x46 => GameboyInstruction {
+            0x1F => GameboyInstruction {
                 opcode,
-                mnemonic: "LD B, (HL)",
-                instruction_family: GameboyInstructionFamily::LD,
-                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::B)),
-                operand2: Some(GameboyInstructionOperand::Pointer(GameboyNamedRegister16::HL, None)),
+                mnemonic: "RR A",
+                instruction_family: GameboyInstructionFamily::RR,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
+                operand2: None,
                 condition: None,
                 cycles: 8,
-                size: 1,
+                size: 2,
             },
-            0x47 => GameboyInstruction {
+            0x20 => GameboyInstruction {
                 opcode,
-                mnemonic: "LD B, A",
-                instruction_family: GameboyInstructionFamily::LD,
+                mnemonic: "SLA B",
+                instruction_family: GameboyInstructionFamily::SLA,
                 operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::B)),
-                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
+                operand2: None,
                 condition: None,
-                cycles: 4,
-                size: 1,
+                cycles: 8,
+                size: 2,
             },
-            0x4F => GameboyInstruction {
+            0x21 => GameboyInstruction {
                 opcode,
-                mnemonic: "LD C, A",
-                instruction_family: GameboyInstructionFamily::LD,
+                mnemonic: "SLA C",
+                instruction_family: GameboyInstructionFamily::SLA,
                 operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::C)),
-                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
+                operand2: None,
                 condition: None,
-                cycles: 4,
-                size: 1,
+                cycles: 8,
+                size: 2,
             },
-            0x54 => GameboyInstruction {
+            0x22 => GameboyInstruction {
                 opcode,
-                mnemonic: "LD D, H",
-                instruction_family: GameboyInstructionFamily::LD,
+                mnemonic: "SLA D",
+                instruction_family: GameboyInstructionFamily::SLA,
                 operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::D)),
-                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::H)),
-                condition: None,
-                cycles: 4,
-                size: 1,
-            },
-            0x60 => GameboyInstruction {
-                opcode,
-                mnemonic: "LD H, B",
-                instruction_family: GameboyInstructionFamily::LD,
-                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::H)),
-                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::B)),
+                operand2: None,
                 condition: None,
-                cycles: 4,
-                size: 1,
+                cycles: 8,
+                size: 2,
             },
-            0x66 => GameboyInstruction {
+            0x23 => GameboyInstruction {
                 opcode,
-                mnemonic: "LD H, (HL)",
-                instruction_family: GameboyInstructionFamily::LD,
-                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::H)),
-                operand2: Some(GameboyInstructionOperand::Pointer(GameboyNamedRegister16::HL, None)),
+                mnemonic: "SLA E",
+                instruction_family: GameboyInstructionFamily::SLA,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::E)),
+                operand2: None,
                 condition: None,
                 cycles: 8,
-                size: 1,
+                size: 2,
             },
-            0x67 => GameboyInstruction {
+            0x24 => GameboyInstruction {
                 opcode,
-                mnemonic: "LD H, A",
-                instruction_family: GameboyInstructionFamily::LD,
+                mnemonic: "SLA H",
+                instruction_family: GameboyInstructionFamily::SLA,
                 operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::H)),
-                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
+                operand2: None,
                 condition: None,
-                cycles: 4,
-                size: 1,
+                cycles: 8,
+                size: 2,
             },
-            0x69 => GameboyInstruction {
+            0x25 => GameboyInstruction {
                 opcode,
-                mnemonic: "LD L, C",
-                instruction_family: GameboyInstructionFamily::LD,
+                mnemonic: "SLA L",
+                instruction_family: GameboyInstructionFamily::SLA,
                 operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::L)),
-                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::C)),
+                operand2: None,
                 condition: None,
-                cycles: 4,
-                size: 1,
+                cycles: 8,
+                size: 2,
             },
-            0x6F => GameboyInstruction {
+            0x26 => GameboyInstruction {
                 opcode,
-                mnemonic: "LD L, A",
-                instruction_family: GameboyInstructionFamily::LD,
-                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::L)),
-                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
+                mnemonic: "SLA (HL)",
+                instruction_family: GameboyInstructionFamily::SLA,
+                operand1: Some(GameboyInstructionOperand::Pointer(GameboyNamedRegister16::HL, None)),
+                operand2: None,
                 condition: None,
-                cycles: 4,
-                size: 1,
+                cycles: 16,
+                size: 2,
             },
-            0x79 => GameboyInstruction {
+            0x27 => GameboyInstruction {
                 opcode,
-                mnemonic: "LD A, C",
-                instruction_family: GameboyInstructionFamily::LD,
+                mnemonic: "SLA A",
+                instruction_family: GameboyInstructionFamily::SLA,
                 operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
-                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::C)),
+                operand2: None,
                 condition: None,
-                cycles: 4,
-                size: 1,
+                cycles: 8,
+                size: 2,
             },
-            0x7B => GameboyInstruction {
+            0x28 => GameboyInstruction {
                 opcode,
-                mnemonic: "LD A, E",
-                instruction_family: GameboyInstructionFamily::LD,
-                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
-                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::E)),
+                mnemonic: "SRA B",
+                instruction_family: GameboyInstructionFamily::SRA,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::B)),
+                operand2: None,
                 condition: None,
-                cycles: 4,
-                size: 1,
+                cycles: 8,
+                size: 2,
             },
-            0x7C => GameboyInstruction {
+            0x29 => GameboyInstruction {
                 opcode,
-                mnemonic: "LD A, H",
-                instruction_family: GameboyInstructionFamily::LD,
-                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
-                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::H)),
+                mnemonic: "SRA C",
+                instruction_family: GameboyInstructionFamily::SRA,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::C)),
+                operand2: None,
                 condition: None,
-                cycles: 4,
-                size: 1,
+                cycles: 8,
+                size: 2,
             },
-            0x7D => GameboyInstruction {
+            0x2A => GameboyInstruction {
                 opcode,
-                mnemonic: "LD A, L",
-                instruction_family: GameboyInstructionFamily::LD,
-                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
-                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::L)),
+                mnemonic: "SRA D",
+                instruction_family: GameboyInstructionFamily::SRA,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::D)),
+                operand2: None,
                 condition: None,
-                cycles: 4,
-                size: 1,
+                cycles: 8,
+                size: 2,
             },
-            0x7E => GameboyInstruction {
+            0x2B => GameboyInstruction {
                 opcode,
-                mnemonic: "LD A, (HL)",
-                instruction_family: GameboyInstructionFamily::LD,
-                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
-                operand2: Some(GameboyInstructionOperand::Pointer(GameboyNamedRegister16::HL, None)),
+                mnemonic: "SRA E",
+                instruction_family: GameboyInstructionFamily::SRA,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::E)),
+                operand2: None,
                 condition: None,
                 cycles: 8,
-                size: 1,
+                size: 2,
             },
-            0xA7 => GameboyInstruction {
+            0x2C => GameboyInstruction {
                 opcode,
-                mnemonic: "AND A",
-                instruction_family: GameboyInstructionFamily::AND,
-                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
+                mnemonic: "SRA H",
+                instruction_family: GameboyInstructionFamily::SRA,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::H)),
                 operand2: None,
                 condition: None,
-                cycles: 4,
-                size: 1,
+                cycles: 8,
+                size: 2,
             },
-            0xAF => GameboyInstruction {
+            0x2D => GameboyInstruction {
                 opcode,
-                mnemonic: "XOR A",
-                instruction_family: GameboyInstructionFamily::XOR,
-                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
+                mnemonic: "SRA L",
+                instruction_family: GameboyInstructionFamily::SRA,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::L)),
                 operand2: None,
                 condition: None,
-                cycles: 4,
-                size: 1,
+                cycles: 8,
+                size: 2,
             },
-            0xB0 => GameboyInstruction {
+            0x2E => GameboyInstruction {
                 opcode,
-                mnemonic: "OR B",
-                instruction_family: GameboyInstructionFamily::OR,
-                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::B)),
+                mnemonic: "SRA (HL)",
+                instruction_family: GameboyInstructionFamily::SRA,
+                operand1: Some(GameboyInstructionOperand::Pointer(GameboyNamedRegister16::HL, None)),
                 operand2: None,
                 condition: None,
-                cycles: 4,
-                size: 1,
+                cycles: 16,
+                size: 2,
             },
-            0xB7 => GameboyInstruction {
+            0x2F => GameboyInstruction {
                 opcode,
-                mnemonic: "OR A",
-                instruction_family: GameboyInstructionFamily::OR,
+                mnemonic: "SRA A",
+                instruction_family: GameboyInstructionFamily::SRA,
                 operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
                 operand2: None,
                 condition: None,
-                cycles: 4,
-                size: 1,
+                cycles: 8,
+                size: 2,
             },
-            0xB8 => GameboyInstruction {
+            0x30 => GameboyInstruction {
                 opcode,
-                mnemonic: "CP B",
-                instruction_family: GameboyInstructionFamily::CP,
+                mnemonic: "SWAP B",
+                instruction_family: GameboyInstructionFamily::SWAP,
                 operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::B)),
                 operand2: None,
                 condition: None,
-                cycles: 4,
-                size: 1,
+                cycles: 8,
+                size: 2,
             },
-            0xB9 => GameboyInstruction {
+            0x31 => GameboyInstruction {
                 opcode,
-                mnemonic: "CP C",
-                instruction_family: GameboyInstructionFamily::CP,
+                mnemonic: "SWAP C",
+                instruction_family: GameboyInstructionFamily::SWAP,
                 operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::C)),
                 operand2: None,
                 condition: None,
-                cycles: 4,
-                size: 1,
+                cycles: 8,
+                size: 2,
             },
-            0xBE => GameboyInstruction {
+            0x32 => GameboyInstruction {
                 opcode,
-                mnemonic: "CP (HL)",
-                instruction_family: GameboyInstructionFamily::CP,
-                operand1: Some(GameboyInstructionOperand::Pointer(GameboyNamedRegister16::HL, None)),
+                mnemonic: "SWAP D",
+                instruction_family: GameboyInstructionFamily::SWAP,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::D)),
                 operand2: None,
                 condition: None,
                 cycles: 8,
-                size: 1,
+                size: 2,
             },
-            0xC1 => GameboyInstruction {
+            0x33 => GameboyInstruction {
                 opcode,
-                mnemonic: "POP BC",
-                instruction_family: GameboyInstructionFamily::POP,
-                operand1: Some(GameboyInstructionOperand::Register16(GameboyNamedRegister16::BC)),
+                mnemonic: "SWAP E",
+                instruction_family: GameboyInstructionFamily::SWAP,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::E)),
                 operand2: None,
                 condition: None,
-                cycles: 12,
-                size: 1,
+                cycles: 8,
+                size: 2,
             },
-            0xC3 => GameboyInstruction {
+            0x34 => GameboyInstruction {
                 opcode,
-                mnemonic: "JP",
-                instruction_family: GameboyInstructionFamily::JP,
-                operand1: Some(GameboyInstructionOperand::Address(self.fetch16())),
+                mnemonic: "SWAP H",
+                instruction_family: GameboyInstructionFamily::SWAP,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::H)),
                 operand2: None,
                 condition: None,
-                cycles: 16,
-                size: 3,
+                cycles: 8,
+                size: 2,
             },
-            0xC5 => GameboyInstruction {
+            0x35 => GameboyInstruction {
                 opcode,
-                mnemonic: "PUSH BC",
-                instruction_family: GameboyInstructionFamily::PUSH,
-                operand1: Some(GameboyInstructionOperand::Register16(GameboyNamedRegister16::BC)),
+                mnemonic: "SWAP L",
+                instruction_family: GameboyInstructionFamily::SWAP,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::L)),
+                operand2: None,
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0x36 => GameboyInstruction {
+                opcode,
+                mnemonic: "SWAP (HL)",
+                instruction_family: GameboyInstructionFamily::SWAP,
+                operand1: Some(GameboyInstructionOperand::Pointer(GameboyNamedRegister16::HL, None)),
                 operand2: None,
                 condition: None,
                 cycles: 16,
-                size: 1,
+                size: 2,
             },
-            0xC6 => GameboyInstruction {
+            0x37 => GameboyInstruction {
                 opcode,
-                mnemonic: "ADD A, n8",
-                instruction_family: GameboyInstructionFamily::ADD,
+                mnemonic: "SWAP A",
+                instruction_family: GameboyInstructionFamily::SWAP,
                 operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
-                operand2: Some(GameboyInstructionOperand::Immediate8(self.fetch())),
+                operand2: None,
                 condition: None,
                 cycles: 8,
                 size: 2,
             },
-            0xC8 => GameboyInstruction {
+            0x38 => GameboyInstruction {
                 opcode,
-                mnemonic: "RET Z",
-                instruction_family: GameboyInstructionFamily::RET,
-                operand1: None,
+                mnemonic: "SRL B",
+                instruction_family: GameboyInstructionFamily::SRL,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::B)),
                 operand2: None,
-                condition: Some(GameboyInstructionCondition::Z),
-                cycles: 20,
-                size: 1,
+                condition: None,
+                cycles: 8,
+                size: 2,
             },
-            0xC9 => GameboyInstruction {
+            0x39 => GameboyInstruction {
                 opcode,
-                mnemonic: "RET",
-                instruction_family: GameboyInstructionFamily::RET,
-                operand1: None,
+                mnemonic: "SRL C",
+                instruction_family: GameboyInstructionFamily::SRL,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::C)),
                 operand2: None,
                 condition: None,
-                cycles: 16,
-                size: 1,
+                cycles: 8,
+                size: 2,
             },
-            0xCA => GameboyInstruction {
+            0x3A => GameboyInstruction {
                 opcode,
-                mnemonic: "JP Z, n16",
-                instruction_family: GameboyInstructionFamily::JP,
-                operand1: Some(GameboyInstructionOperand::Address(self.fetch16())),
+                mnemonic: "SRL D",
+                instruction_family: GameboyInstructionFamily::SRL,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::D)),
                 operand2: None,
-                condition: Some(GameboyInstructionCondition::Z),
-                cycles: 16,
-                size: 3,
+                condition: None,
+                cycles: 8,
+                size: 2,
             },
-            0xCB => {
-                let prefix_opcode = self.fetch();
-                self.decode_prefix(prefix_opcode)
+            0x3B => GameboyInstruction {
+                opcode,
+                mnemonic: "SRL E",
+                instruction_family: GameboyInstructionFamily::SRL,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::E)),
+                operand2: None,
+                condition: None,
+                cycles: 8,
+                size: 2,
             },
-            0xCD => GameboyInstruction {
+            0x3C => GameboyInstruction {
                 opcode,
-                mnemonic: "CALL",
-                instruction_family: GameboyInstructionFamily::CALL,
-                operand1: Some(GameboyInstructionOperand::Address(self.fetch16())),
+                mnemonic: "SRL H",
+                instruction_family: GameboyInstructionFamily::SRL,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::H)),
                 operand2: None,
                 condition: None,
-                cycles: 24,
-                size: 3,
+                cycles: 8,
+                size: 2,
             },
-            0xD1 => GameboyInstruction {
+            0x3D => GameboyInstruction {
                 opcode,
-                mnemonic: "POP DE",
-                instruction_family: GameboyInstructionFamily::POP,
-                operand1: Some(GameboyInstructionOperand::Register16(GameboyNamedRegister16::DE)),
+                mnemonic: "SRL L",
+                instruction_family: GameboyInstructionFamily::SRL,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::L)),
                 operand2: None,
                 condition: None,
-                cycles: 12,
-                size: 1,
+                cycles: 8,
+                size: 2,
             },
-            0xD5 => GameboyInstruction {
+            0x3E => GameboyInstruction {
                 opcode,
-                mnemonic: "PUSH DE",
-                instruction_family: GameboyInstructionFamily::PUSH,
-                operand1: Some(GameboyInstructionOperand::Register16(GameboyNamedRegister16::DE)),
+                mnemonic: "SRL (HL)",
+                instruction_family: GameboyInstructionFamily::SRL,
+                operand1: Some(GameboyInstructionOperand::Pointer(GameboyNamedRegister16::HL, None)),
                 operand2: None,
                 condition: None,
                 cycles: 16,
-                size: 1,
+                size: 2,
             },
-            0xD8 => GameboyInstruction {
+            0x3F => GameboyInstruction {
                 opcode,
-                mnemonic: "RET C",
-                instruction_family: GameboyInstructionFamily::RET,
-                operand1: None,
+                mnemonic: "SRL A",
+                instruction_family: GameboyInstructionFamily::SRL,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
                 operand2: None,
-                condition: Some(GameboyInstructionCondition::C),
-                cycles: 20,
-                size: 1,
+                condition: None,
+                cycles: 8,
+                size: 2,
             },
-            0xDA => GameboyInstruction {
+            0x40 => GameboyInstruction {
                 opcode,
-                mnemonic: "JP C, n16",
-                instruction_family: GameboyInstructionFamily::JP,
-                operand1: Some(GameboyInstructionOperand::Address(self.fetch16())),
-                operand2: None,
-                condition: Some(GameboyInstructionCondition::C),
-                cycles: 16,
-                size: 3,
+                mnemonic: "BIT 0, B",
+                instruction_family: GameboyInstructionFamily::BIT,
+                operand1: Some(GameboyInstructionOperand::Immediate8(0)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::B)),
+                condition: None,
+                cycles: 8,
+                size: 2,
             },
-            0xE0 => GameboyInstruction {
+            0x41 => GameboyInstruction {
                 opcode,
-                mnemonic: "LDH (n8), A",
-                instruction_family: GameboyInstructionFamily::LDH,
-                operand1: Some(GameboyInstructionOperand::Immediate8(self.fetch())),
-                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
+                mnemonic: "BIT 0, C",
+                instruction_family: GameboyInstructionFamily::BIT,
+                operand1: Some(GameboyInstructionOperand::Immediate8(0)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::C)),
                 condition: None,
-                cycles: 12,
+                cycles: 8,
                 size: 2,
             },
-            0xE1 => GameboyInstruction {
+            0x42 => GameboyInstruction {
                 opcode,
-                mnemonic: "POP HL",
-                instruction_family: GameboyInstructionFamily::POP,
-                operand1: Some(GameboyInstructionOperand::Register16(GameboyNamedRegister16::HL)),
-                operand2: None,
+                mnemonic: "BIT 0, D",
+                instruction_family: GameboyInstructionFamily::BIT,
+                operand1: Some(GameboyInstructionOperand::Immediate8(0)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::D)),
                 condition: None,
-                cycles: 12,
-                size: 1,
+                cycles: 8,
+                size: 2,
             },
-            0xE5 => GameboyInstruction {
+            0x43 => GameboyInstruction {
                 opcode,
-                mnemonic: "PUSH HL",
-                instruction_family: GameboyInstructionFamily::PUSH,
-                operand1: Some(GameboyInstructionOperand::Register16(GameboyNamedRegister16::HL)),
-                operand2: None,
+                mnemonic: "BIT 0, E",
+                instruction_family: GameboyInstructionFamily::BIT,
+                operand1: Some(GameboyInstructionOperand::Immediate8(0)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::E)),
                 condition: None,
-                cycles: 16,
-                size: 1,
+                cycles: 8,
+                size: 2,
             },
-            0xE9 => GameboyInstruction {
+            0x44 => GameboyInstruction {
                 opcode,
-                mnemonic: "JP HL",
-                instruction_family: GameboyInstructionFamily::JP,
-                operand1: Some(GameboyInstructionOperand::Register16(GameboyNamedRegister16::HL)),
-                operand2: None,
+                mnemonic: "BIT 0, H",
+                instruction_family: GameboyInstructionFamily::BIT,
+                operand1: Some(GameboyInstructionOperand::Immediate8(0)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::H)),
                 condition: None,
-                cycles: 4,
-                size: 1,
+                cycles: 8,
+                size: 2,
             },
-            0xF0 => GameboyInstruction {
+            0x45 => GameboyInstruction {
                 opcode,
-                mnemonic: "LDH A, (n8)",
-                instruction_family: GameboyInstructionFamily::LDH,
-                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
-                operand2: Some(GameboyInstructionOperand::Immediate8(self.fetch())),
+                mnemonic: "BIT 0, L",
+                instruction_family: GameboyInstructionFamily::BIT,
+                operand1: Some(GameboyInstructionOperand::Immediate8(0)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::L)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0x46 => GameboyInstruction {
+                opcode,
+                mnemonic: "BIT 0, (HL)",
+                instruction_family: GameboyInstructionFamily::BIT,
+                operand1: Some(GameboyInstructionOperand::Immediate8(0)),
+                operand2: Some(GameboyInstructionOperand::Pointer(GameboyNamedRegister16::HL, None)),
                 condition: None,
                 cycles: 12,
                 size: 2,
             },
-            0xF3 => GameboyInstruction {
+            0x47 => GameboyInstruction {
                 opcode,
-                mnemonic: "DI",
-                instruction_family: GameboyInstructionFamily::DI,
-                operand1: None,
-                operand2: None,
+                mnemonic: "BIT 0, A",
+                instruction_family: GameboyInstructionFamily::BIT,
+                operand1: Some(GameboyInstructionOperand::Immediate8(0)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
                 condition: None,
-                cycles: 4,
-                size: 1,
+                cycles: 8,
+                size: 2,
             },
-            0xFE => GameboyInstruction {
+            0x48 => GameboyInstruction {
                 opcode,
-                mnemonic: "CP n8",
-                instruction_family: GameboyInstructionFamily::CP,
-                operand1: Some(GameboyInstructionOperand::Immediate8(self.fetch())),
-                operand2: None,
+                mnemonic: "BIT 1, B",
+                instruction_family: GameboyInstructionFamily::BIT,
+                operand1: Some(GameboyInstructionOperand::Immediate8(1)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::B)),
                 condition: None,
                 cycles: 8,
                 size: 2,
             },
-            _ => panic!("Unknown opcode: {:#04X}", opcode)
-        }
-    }
-
-    fn check_condition(&self, condition: Option<GameboyInstructionCondition>) -> bool {
-        match condition {
-            Some(GameboyInstructionCondition::NZ) => !self.registers.get_flag(GameboyRegisterFlags::Z),
-            Some(GameboyInstructionCondition::Z) => self.registers.get_flag(GameboyRegisterFlags::Z),
-            Some(GameboyInstructionCondition::NC) => !self.registers.get_flag(GameboyRegisterFlags::C),
-            Some(GameboyInstructionCondition::C) => self.registers.get_flag(GameboyRegisterFlags::C),
-            None => true,
-        }
-    }
-
-    pub fn execute(&mut self, instruction: GameboyInstruction) {
-        if !self.check_condition(instruction.condition) {
-            return;
-        }
-
-        match instruction.instruction_family {
-            GameboyInstructionFamily::NOP => {
-                // NOP
-                // println!("No operation");
-                panic!();
+            0x49 => GameboyInstruction {
+                opcode,
+                mnemonic: "BIT 1, C",
+                instruction_family: GameboyInstructionFamily::BIT,
+                operand1: Some(GameboyInstructionOperand::Immediate8(1)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::C)),
+                condition: None,
+                cycles: 8,
+                size: 2,
             },
-            GameboyInstructionFamily::JP => {
-                match instruction.operand1 {
-                    Some(GameboyInstructionOperand::Address(address)) => {
-                        // JP n16
-                        self.registers.pc = address;
-                    },
-                    Some(GameboyInstructionOperand::Register16(register)) => {
-                        // JP r16
-                        let address = self.registers.get_reg16(&register);
-                        self.registers.pc = address;
-                    },
-                    _ => panic!("Invalid operand for JP instruction"),
-                }
+            0x4A => GameboyInstruction {
+                opcode,
+                mnemonic: "BIT 1, D",
+                instruction_family: GameboyInstructionFamily::BIT,
+                operand1: Some(GameboyInstructionOperand::Immediate8(1)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::D)),
+                condition: None,
+                cycles: 8,
+                size: 2,
             },
-            GameboyInstructionFamily::DI => {
-                // DI
-                // println!("Interrupts disabled");
+            0x4B => GameboyInstruction {
+                opcode,
+                mnemonic: "BIT 1, E",
+                instruction_family: GameboyInstructionFamily::BIT,
+                operand1: Some(GameboyInstructionOperand::Immediate8(1)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::E)),
+                condition: None,
+                cycles: 8,
+                size: 2,
             },
-            GameboyInstructionFamily::XOR => {
-                match instruction.operand1 {
-                    Some(GameboyInstructionOperand::Register8(register)) => {
-                        // XOR A, r8
-                        let value = self.registers.get_reg8(&register);
-                        let result = self.registers.get_reg8(&GameboyNamedRegister8::A) ^ value;
-                        self.registers.set_reg8(&GameboyNamedRegister8::A, result);
-
-                        self.registers.set_flag(GameboyRegisterFlags::Z, result == 0);
-                        self.registers.set_flag(GameboyRegisterFlags::N, false);
-                        self.registers.set_flag(GameboyRegisterFlags::H, false);
-                        self.registers.set_flag(GameboyRegisterFlags::C, false);
-                    },
-                    _ => panic!("Invalid operand for XOR instruction"),
-                }
+            0x4C => GameboyInstruction {
+                opcode,
+                mnemonic: "BIT 1, H",
+                instruction_family: GameboyInstructionFamily::BIT,
+                operand1: Some(GameboyInstructionOperand::Immediate8(1)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::H)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0x4D => GameboyInstruction {
+                opcode,
+                mnemonic: "BIT 1, L",
+                instruction_family: GameboyInstructionFamily::BIT,
+                operand1: Some(GameboyInstructionOperand::Immediate8(1)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::L)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0x4E => GameboyInstruction {
+                opcode,
+                mnemonic: "BIT 1, (HL)",
+                instruction_family: GameboyInstructionFamily::BIT,
+                operand1: Some(GameboyInstructionOperand::Immediate8(1)),
+                operand2: Some(GameboyInstructionOperand::Pointer(GameboyNamedRegister16::HL, None)),
+                condition: None,
+                cycles: 12,
+                size: 2,
+            },
+            0x4F => GameboyInstruction {
+                opcode,
+                mnemonic: "BIT 1, A",
+                instruction_family: GameboyInstructionFamily::BIT,
+                operand1: Some(GameboyInstructionOperand::Immediate8(1)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0x50 => GameboyInstruction {
+                opcode,
+                mnemonic: "BIT 2, B",
+                instruction_family: GameboyInstructionFamily::BIT,
+                operand1: Some(GameboyInstructionOperand::Immediate8(2)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::B)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0x51 => GameboyInstruction {
+                opcode,
+                mnemonic: "BIT 2, C",
+                instruction_family: GameboyInstructionFamily::BIT,
+                operand1: Some(GameboyInstructionOperand::Immediate8(2)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::C)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0x52 => GameboyInstruction {
+                opcode,
+                mnemonic: "BIT 2, D",
+                instruction_family: GameboyInstructionFamily::BIT,
+                operand1: Some(GameboyInstructionOperand::Immediate8(2)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::D)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0x53 => GameboyInstruction {
+                opcode,
+                mnemonic: "BIT 2, E",
+                instruction_family: GameboyInstructionFamily::BIT,
+                operand1: Some(GameboyInstructionOperand::Immediate8(2)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::E)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0x54 => GameboyInstruction {
+                opcode,
+                mnemonic: "BIT 2, H",
+                instruction_family: GameboyInstructionFamily::BIT,
+                operand1: Some(GameboyInstructionOperand::Immediate8(2)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::H)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0x55 => GameboyInstruction {
+                opcode,
+                mnemonic: "BIT 2, L",
+                instruction_family: GameboyInstructionFamily::BIT,
+                operand1: Some(GameboyInstructionOperand::Immediate8(2)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::L)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0x56 => GameboyInstruction {
+                opcode,
+                mnemonic: "BIT 2, (HL)",
+                instruction_family: GameboyInstructionFamily::BIT,
+                operand1: Some(GameboyInstructionOperand::Immediate8(2)),
+                operand2: Some(GameboyInstructionOperand::Pointer(GameboyNamedRegister16::HL, None)),
+                condition: None,
+                cycles: 12,
+                size: 2,
+            },
+            0x57 => GameboyInstruction {
+                opcode,
+                mnemonic: "BIT 2, A",
+                instruction_family: GameboyInstructionFamily::BIT,
+                operand1: Some(GameboyInstructionOperand::Immediate8(2)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0x58 => GameboyInstruction {
+                opcode,
+                mnemonic: "BIT 3, B",
+                instruction_family: GameboyInstructionFamily::BIT,
+                operand1: Some(GameboyInstructionOperand::Immediate8(3)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::B)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0x59 => GameboyInstruction {
+                opcode,
+                mnemonic: "BIT 3, C",
+                instruction_family: GameboyInstructionFamily::BIT,
+                operand1: Some(GameboyInstructionOperand::Immediate8(3)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::C)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0x5A => GameboyInstruction {
+                opcode,
+                mnemonic: "BIT 3, D",
+                instruction_family: GameboyInstructionFamily::BIT,
+                operand1: Some(GameboyInstructionOperand::Immediate8(3)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::D)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0x5B => GameboyInstruction {
+                opcode,
+                mnemonic: "BIT 3, E",
+                instruction_family: GameboyInstructionFamily::BIT,
+                operand1: Some(GameboyInstructionOperand::Immediate8(3)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::E)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0x5C => GameboyInstruction {
+                opcode,
+                mnemonic: "BIT 3, H",
+                instruction_family: GameboyInstructionFamily::BIT,
+                operand1: Some(GameboyInstructionOperand::Immediate8(3)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::H)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0x5D => GameboyInstruction {
+                opcode,
+                mnemonic: "BIT 3, L",
+                instruction_family: GameboyInstructionFamily::BIT,
+                operand1: Some(GameboyInstructionOperand::Immediate8(3)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::L)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0x5E => GameboyInstruction {
+                opcode,
+                mnemonic: "BIT 3, (HL)",
+                instruction_family: GameboyInstructionFamily::BIT,
+                operand1: Some(GameboyInstructionOperand::Immediate8(3)),
+                operand2: Some(GameboyInstructionOperand::Pointer(GameboyNamedRegister16::HL, None)),
+                condition: None,
+                cycles: 12,
+                size: 2,
+            },
+            0x5F => GameboyInstruction {
+                opcode,
+                mnemonic: "BIT 3, A",
+                instruction_family: GameboyInstructionFamily::BIT,
+                operand1: Some(GameboyInstructionOperand::Immediate8(3)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0x60 => GameboyInstruction {
+                opcode,
+                mnemonic: "BIT 4, B",
+                instruction_family: GameboyInstructionFamily::BIT,
+                operand1: Some(GameboyInstructionOperand::Immediate8(4)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::B)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0x61 => GameboyInstruction {
+                opcode,
+                mnemonic: "BIT 4, C",
+                instruction_family: GameboyInstructionFamily::BIT,
+                operand1: Some(GameboyInstructionOperand::Immediate8(4)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::C)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0x62 => GameboyInstruction {
+                opcode,
+                mnemonic: "BIT 4, D",
+                instruction_family: GameboyInstructionFamily::BIT,
+                operand1: Some(GameboyInstructionOperand::Immediate8(4)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::D)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0x63 => GameboyInstruction {
+                opcode,
+                mnemonic: "BIT 4, E",
+                instruction_family: GameboyInstructionFamily::BIT,
+                operand1: Some(GameboyInstructionOperand::Immediate8(4)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::E)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0x64 => GameboyInstruction {
+                opcode,
+                mnemonic: "BIT 4, H",
+                instruction_family: GameboyInstructionFamily::BIT,
+                operand1: Some(GameboyInstructionOperand::Immediate8(4)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::H)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0x65 => GameboyInstruction {
+                opcode,
+                mnemonic: "BIT 4, L",
+                instruction_family: GameboyInstructionFamily::BIT,
+                operand1: Some(GameboyInstructionOperand::Immediate8(4)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::L)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0x66 => GameboyInstruction {
+                opcode,
+                mnemonic: "BIT 4, (HL)",
+                instruction_family: GameboyInstructionFamily::BIT,
+                operand1: Some(GameboyInstructionOperand::Immediate8(4)),
+                operand2: Some(GameboyInstructionOperand::Pointer(GameboyNamedRegister16::HL, None)),
+                condition: None,
+                cycles: 12,
+                size: 2,
+            },
+            0x67 => GameboyInstruction {
+                opcode,
+                mnemonic: "BIT 4, A",
+                instruction_family: GameboyInstructionFamily::BIT,
+                operand1: Some(GameboyInstructionOperand::Immediate8(4)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0x68 => GameboyInstruction {
+                opcode,
+                mnemonic: "BIT 5, B",
+                instruction_family: GameboyInstructionFamily::BIT,
+                operand1: Some(GameboyInstructionOperand::Immediate8(5)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::B)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0x69 => GameboyInstruction {
+                opcode,
+                mnemonic: "BIT 5, C",
+                instruction_family: GameboyInstructionFamily::BIT,
+                operand1: Some(GameboyInstructionOperand::Immediate8(5)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::C)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0x6A => GameboyInstruction {
+                opcode,
+                mnemonic: "BIT 5, D",
+                instruction_family: GameboyInstructionFamily::BIT,
+                operand1: Some(GameboyInstructionOperand::Immediate8(5)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::D)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0x6B => GameboyInstruction {
+                opcode,
+                mnemonic: "BIT 5, E",
+                instruction_family: GameboyInstructionFamily::BIT,
+                operand1: Some(GameboyInstructionOperand::Immediate8(5)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::E)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0x6C => GameboyInstruction {
+                opcode,
+                mnemonic: "BIT 5, H",
+                instruction_family: GameboyInstructionFamily::BIT,
+                operand1: Some(GameboyInstructionOperand::Immediate8(5)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::H)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0x6D => GameboyInstruction {
+                opcode,
+                mnemonic: "BIT 5, L",
+                instruction_family: GameboyInstructionFamily::BIT,
+                operand1: Some(GameboyInstructionOperand::Immediate8(5)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::L)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0x6E => GameboyInstruction {
+                opcode,
+                mnemonic: "BIT 5, (HL)",
+                instruction_family: GameboyInstructionFamily::BIT,
+                operand1: Some(GameboyInstructionOperand::Immediate8(5)),
+                operand2: Some(GameboyInstructionOperand::Pointer(GameboyNamedRegister16::HL, None)),
+                condition: None,
+                cycles: 12,
+                size: 2,
+            },
+            0x6F => GameboyInstruction {
+                opcode,
+                mnemonic: "BIT 5, A",
+                instruction_family: GameboyInstructionFamily::BIT,
+                operand1: Some(GameboyInstructionOperand::Immediate8(5)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0x70 => GameboyInstruction {
+                opcode,
+                mnemonic: "BIT 6, B",
+                instruction_family: GameboyInstructionFamily::BIT,
+                operand1: Some(GameboyInstructionOperand::Immediate8(6)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::B)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0x71 => GameboyInstruction {
+                opcode,
+                mnemonic: "BIT 6, C",
+                instruction_family: GameboyInstructionFamily::BIT,
+                operand1: Some(GameboyInstructionOperand::Immediate8(6)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::C)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0x72 => GameboyInstruction {
+                opcode,
+                mnemonic: "BIT 6, D",
+                instruction_family: GameboyInstructionFamily::BIT,
+                operand1: Some(GameboyInstructionOperand::Immediate8(6)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::D)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0x73 => GameboyInstruction {
+                opcode,
+                mnemonic: "BIT 6, E",
+                instruction_family: GameboyInstructionFamily::BIT,
+                operand1: Some(GameboyInstructionOperand::Immediate8(6)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::E)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0x74 => GameboyInstruction {
+                opcode,
+                mnemonic: "BIT 6, H",
+                instruction_family: GameboyInstructionFamily::BIT,
+                operand1: Some(GameboyInstructionOperand::Immediate8(6)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::H)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0x75 => GameboyInstruction {
+                opcode,
+                mnemonic: "BIT 6, L",
+                instruction_family: GameboyInstructionFamily::BIT,
+                operand1: Some(GameboyInstructionOperand::Immediate8(6)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::L)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0x76 => GameboyInstruction {
+                opcode,
+                mnemonic: "BIT 6, (HL)",
+                instruction_family: GameboyInstructionFamily::BIT,
+                operand1: Some(GameboyInstructionOperand::Immediate8(6)),
+                operand2: Some(GameboyInstructionOperand::Pointer(GameboyNamedRegister16::HL, None)),
+                condition: None,
+                cycles: 12,
+                size: 2,
+            },
+            0x77 => GameboyInstruction {
+                opcode,
+                mnemonic: "BIT 6, A",
+                instruction_family: GameboyInstructionFamily::BIT,
+                operand1: Some(GameboyInstructionOperand::Immediate8(6)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0x78 => GameboyInstruction {
+                opcode,
+                mnemonic: "BIT 7, B",
+                instruction_family: GameboyInstructionFamily::BIT,
+                operand1: Some(GameboyInstructionOperand::Immediate8(7)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::B)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0x79 => GameboyInstruction {
+                opcode,
+                mnemonic: "BIT 7, C",
+                instruction_family: GameboyInstructionFamily::BIT,
+                operand1: Some(GameboyInstructionOperand::Immediate8(7)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::C)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0x7A => GameboyInstruction {
+                opcode,
+                mnemonic: "BIT 7, D",
+                instruction_family: GameboyInstructionFamily::BIT,
+                operand1: Some(GameboyInstructionOperand::Immediate8(7)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::D)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0x7B => GameboyInstruction {
+                opcode,
+                mnemonic: "BIT 7, E",
+                instruction_family: GameboyInstructionFamily::BIT,
+                operand1: Some(GameboyInstructionOperand::Immediate8(7)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::E)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0x7C => GameboyInstruction {
+                opcode,
+                mnemonic: "BIT 7, H",
+                instruction_family: GameboyInstructionFamily::BIT,
+                operand1: Some(GameboyInstructionOperand::Immediate8(7)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::H)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0x7D => GameboyInstruction {
+                opcode,
+                mnemonic: "BIT 7, L",
+                instruction_family: GameboyInstructionFamily::BIT,
+                operand1: Some(GameboyInstructionOperand::Immediate8(7)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::L)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0x7E => GameboyInstruction {
+                opcode,
+                mnemonic: "BIT 7, (HL)",
+                instruction_family: GameboyInstructionFamily::BIT,
+                operand1: Some(GameboyInstructionOperand::Immediate8(7)),
+                operand2: Some(GameboyInstructionOperand::Pointer(GameboyNamedRegister16::HL, None)),
+                condition: None,
+                cycles: 12,
+                size: 2,
+            },
+            0x7F => GameboyInstruction {
+                opcode,
+                mnemonic: "BIT 7, A",
+                instruction_family: GameboyInstructionFamily::BIT,
+                operand1: Some(GameboyInstructionOperand::Immediate8(7)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0x80 => GameboyInstruction {
+                opcode,
+                mnemonic: "RES 0, B",
+                instruction_family: GameboyInstructionFamily::RES,
+                operand1: Some(GameboyInstructionOperand::Immediate8(0)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::B)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0x81 => GameboyInstruction {
+                opcode,
+                mnemonic: "RES 0, C",
+                instruction_family: GameboyInstructionFamily::RES,
+                operand1: Some(GameboyInstructionOperand::Immediate8(0)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::C)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0x82 => GameboyInstruction {
+                opcode,
+                mnemonic: "RES 0, D",
+                instruction_family: GameboyInstructionFamily::RES,
+                operand1: Some(GameboyInstructionOperand::Immediate8(0)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::D)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0x83 => GameboyInstruction {
+                opcode,
+                mnemonic: "RES 0, E",
+                instruction_family: GameboyInstructionFamily::RES,
+                operand1: Some(GameboyInstructionOperand::Immediate8(0)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::E)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0x84 => GameboyInstruction {
+                opcode,
+                mnemonic: "RES 0, H",
+                instruction_family: GameboyInstructionFamily::RES,
+                operand1: Some(GameboyInstructionOperand::Immediate8(0)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::H)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0x85 => GameboyInstruction {
+                opcode,
+                mnemonic: "RES 0, L",
+                instruction_family: GameboyInstructionFamily::RES,
+                operand1: Some(GameboyInstructionOperand::Immediate8(0)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::L)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0x86 => GameboyInstruction {
+                opcode,
+                mnemonic: "RES 0, (HL)",
+                instruction_family: GameboyInstructionFamily::RES,
+                operand1: Some(GameboyInstructionOperand::Immediate8(0)),
+                operand2: Some(GameboyInstructionOperand::Pointer(GameboyNamedRegister16::HL, None)),
+                condition: None,
+                cycles: 16,
+                size: 2,
+            },
+            0x87 => GameboyInstruction {
+                opcode,
+                mnemonic: "RES 0, A",
+                instruction_family: GameboyInstructionFamily::RES,
+                operand1: Some(GameboyInstructionOperand::Immediate8(0)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0x88 => GameboyInstruction {
+                opcode,
+                mnemonic: "RES 1, B",
+                instruction_family: GameboyInstructionFamily::RES,
+                operand1: Some(GameboyInstructionOperand::Immediate8(1)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::B)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0x89 => GameboyInstruction {
+                opcode,
+                mnemonic: "RES 1, C",
+                instruction_family: GameboyInstructionFamily::RES,
+                operand1: Some(GameboyInstructionOperand::Immediate8(1)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::C)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0x8A => GameboyInstruction {
+                opcode,
+                mnemonic: "RES 1, D",
+                instruction_family: GameboyInstructionFamily::RES,
+                operand1: Some(GameboyInstructionOperand::Immediate8(1)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::D)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0x8B => GameboyInstruction {
+                opcode,
+                mnemonic: "RES 1, E",
+                instruction_family: GameboyInstructionFamily::RES,
+                operand1: Some(GameboyInstructionOperand::Immediate8(1)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::E)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0x8C => GameboyInstruction {
+                opcode,
+                mnemonic: "RES 1, H",
+                instruction_family: GameboyInstructionFamily::RES,
+                operand1: Some(GameboyInstructionOperand::Immediate8(1)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::H)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0x8D => GameboyInstruction {
+                opcode,
+                mnemonic: "RES 1, L",
+                instruction_family: GameboyInstructionFamily::RES,
+                operand1: Some(GameboyInstructionOperand::Immediate8(1)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::L)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0x8E => GameboyInstruction {
+                opcode,
+                mnemonic: "RES 1, (HL)",
+                instruction_family: GameboyInstructionFamily::RES,
+                operand1: Some(GameboyInstructionOperand::Immediate8(1)),
+                operand2: Some(GameboyInstructionOperand::Pointer(GameboyNamedRegister16::HL, None)),
+                condition: None,
+                cycles: 16,
+                size: 2,
+            },
+            0x8F => GameboyInstruction {
+                opcode,
+                mnemonic: "RES 1, A",
+                instruction_family: GameboyInstructionFamily::RES,
+                operand1: Some(GameboyInstructionOperand::Immediate8(1)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0x90 => GameboyInstruction {
+                opcode,
+                mnemonic: "RES 2, B",
+                instruction_family: GameboyInstructionFamily::RES,
+                operand1: Some(GameboyInstructionOperand::Immediate8(2)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::B)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0x91 => GameboyInstruction {
+                opcode,
+                mnemonic: "RES 2, C",
+                instruction_family: GameboyInstructionFamily::RES,
+                operand1: Some(GameboyInstructionOperand::Immediate8(2)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::C)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0x92 => GameboyInstruction {
+                opcode,
+                mnemonic: "RES 2, D",
+                instruction_family: GameboyInstructionFamily::RES,
+                operand1: Some(GameboyInstructionOperand::Immediate8(2)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::D)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0x93 => GameboyInstruction {
+                opcode,
+                mnemonic: "RES 2, E",
+                instruction_family: GameboyInstructionFamily::RES,
+                operand1: Some(GameboyInstructionOperand::Immediate8(2)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::E)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0x94 => GameboyInstruction {
+                opcode,
+                mnemonic: "RES 2, H",
+                instruction_family: GameboyInstructionFamily::RES,
+                operand1: Some(GameboyInstructionOperand::Immediate8(2)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::H)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0x95 => GameboyInstruction {
+                opcode,
+                mnemonic: "RES 2, L",
+                instruction_family: GameboyInstructionFamily::RES,
+                operand1: Some(GameboyInstructionOperand::Immediate8(2)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::L)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0x96 => GameboyInstruction {
+                opcode,
+                mnemonic: "RES 2, (HL)",
+                instruction_family: GameboyInstructionFamily::RES,
+                operand1: Some(GameboyInstructionOperand::Immediate8(2)),
+                operand2: Some(GameboyInstructionOperand::Pointer(GameboyNamedRegister16::HL, None)),
+                condition: None,
+                cycles: 16,
+                size: 2,
+            },
+            0x97 => GameboyInstruction {
+                opcode,
+                mnemonic: "RES 2, A",
+                instruction_family: GameboyInstructionFamily::RES,
+                operand1: Some(GameboyInstructionOperand::Immediate8(2)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0x98 => GameboyInstruction {
+                opcode,
+                mnemonic: "RES 3, B",
+                instruction_family: GameboyInstructionFamily::RES,
+                operand1: Some(GameboyInstructionOperand::Immediate8(3)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::B)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0x99 => GameboyInstruction {
+                opcode,
+                mnemonic: "RES 3, C",
+                instruction_family: GameboyInstructionFamily::RES,
+                operand1: Some(GameboyInstructionOperand::Immediate8(3)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::C)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0x9A => GameboyInstruction {
+                opcode,
+                mnemonic: "RES 3, D",
+                instruction_family: GameboyInstructionFamily::RES,
+                operand1: Some(GameboyInstructionOperand::Immediate8(3)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::D)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0x9B => GameboyInstruction {
+                opcode,
+                mnemonic: "RES 3, E",
+                instruction_family: GameboyInstructionFamily::RES,
+                operand1: Some(GameboyInstructionOperand::Immediate8(3)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::E)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0x9C => GameboyInstruction {
+                opcode,
+                mnemonic: "RES 3, H",
+                instruction_family: GameboyInstructionFamily::RES,
+                operand1: Some(GameboyInstructionOperand::Immediate8(3)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::H)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0x9D => GameboyInstruction {
+                opcode,
+                mnemonic: "RES 3, L",
+                instruction_family: GameboyInstructionFamily::RES,
+                operand1: Some(GameboyInstructionOperand::Immediate8(3)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::L)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0x9E => GameboyInstruction {
+                opcode,
+                mnemonic: "RES 3, (HL)",
+                instruction_family: GameboyInstructionFamily::RES,
+                operand1: Some(GameboyInstructionOperand::Immediate8(3)),
+                operand2: Some(GameboyInstructionOperand::Pointer(GameboyNamedRegister16::HL, None)),
+                condition: None,
+                cycles: 16,
+                size: 2,
+            },
+            0x9F => GameboyInstruction {
+                opcode,
+                mnemonic: "RES 3, A",
+                instruction_family: GameboyInstructionFamily::RES,
+                operand1: Some(GameboyInstructionOperand::Immediate8(3)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0xA0 => GameboyInstruction {
+                opcode,
+                mnemonic: "RES 4, B",
+                instruction_family: GameboyInstructionFamily::RES,
+                operand1: Some(GameboyInstructionOperand::Immediate8(4)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::B)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0xA1 => GameboyInstruction {
+                opcode,
+                mnemonic: "RES 4, C",
+                instruction_family: GameboyInstructionFamily::RES,
+                operand1: Some(GameboyInstructionOperand::Immediate8(4)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::C)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0xA2 => GameboyInstruction {
+                opcode,
+                mnemonic: "RES 4, D",
+                instruction_family: GameboyInstructionFamily::RES,
+                operand1: Some(GameboyInstructionOperand::Immediate8(4)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::D)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0xA3 => GameboyInstruction {
+                opcode,
+                mnemonic: "RES 4, E",
+                instruction_family: GameboyInstructionFamily::RES,
+                operand1: Some(GameboyInstructionOperand::Immediate8(4)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::E)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0xA4 => GameboyInstruction {
+                opcode,
+                mnemonic: "RES 4, H",
+                instruction_family: GameboyInstructionFamily::RES,
+                operand1: Some(GameboyInstructionOperand::Immediate8(4)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::H)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0xA5 => GameboyInstruction {
+                opcode,
+                mnemonic: "RES 4, L",
+                instruction_family: GameboyInstructionFamily::RES,
+                operand1: Some(GameboyInstructionOperand::Immediate8(4)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::L)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0xA6 => GameboyInstruction {
+                opcode,
+                mnemonic: "RES 4, (HL)",
+                instruction_family: GameboyInstructionFamily::RES,
+                operand1: Some(GameboyInstructionOperand::Immediate8(4)),
+                operand2: Some(GameboyInstructionOperand::Pointer(GameboyNamedRegister16::HL, None)),
+                condition: None,
+                cycles: 16,
+                size: 2,
+            },
+            0xA7 => GameboyInstruction {
+                opcode,
+                mnemonic: "RES 4, A",
+                instruction_family: GameboyInstructionFamily::RES,
+                operand1: Some(GameboyInstructionOperand::Immediate8(4)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0xA8 => GameboyInstruction {
+                opcode,
+                mnemonic: "RES 5, B",
+                instruction_family: GameboyInstructionFamily::RES,
+                operand1: Some(GameboyInstructionOperand::Immediate8(5)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::B)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0xA9 => GameboyInstruction {
+                opcode,
+                mnemonic: "RES 5, C",
+                instruction_family: GameboyInstructionFamily::RES,
+                operand1: Some(GameboyInstructionOperand::Immediate8(5)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::C)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0xAA => GameboyInstruction {
+                opcode,
+                mnemonic: "RES 5, D",
+                instruction_family: GameboyInstructionFamily::RES,
+                operand1: Some(GameboyInstructionOperand::Immediate8(5)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::D)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0xAB => GameboyInstruction {
+                opcode,
+                mnemonic: "RES 5, E",
+                instruction_family: GameboyInstructionFamily::RES,
+                operand1: Some(GameboyInstructionOperand::Immediate8(5)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::E)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0xAC => GameboyInstruction {
+                opcode,
+                mnemonic: "RES 5, H",
+                instruction_family: GameboyInstructionFamily::RES,
+                operand1: Some(GameboyInstructionOperand::Immediate8(5)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::H)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0xAD => GameboyInstruction {
+                opcode,
+                mnemonic: "RES 5, L",
+                instruction_family: GameboyInstructionFamily::RES,
+                operand1: Some(GameboyInstructionOperand::Immediate8(5)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::L)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0xAE => GameboyInstruction {
+                opcode,
+                mnemonic: "RES 5, (HL)",
+                instruction_family: GameboyInstructionFamily::RES,
+                operand1: Some(GameboyInstructionOperand::Immediate8(5)),
+                operand2: Some(GameboyInstructionOperand::Pointer(GameboyNamedRegister16::HL, None)),
+                condition: None,
+                cycles: 16,
+                size: 2,
+            },
+            0xAF => GameboyInstruction {
+                opcode,
+                mnemonic: "RES 5, A",
+                instruction_family: GameboyInstructionFamily::RES,
+                operand1: Some(GameboyInstructionOperand::Immediate8(5)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0xB0 => GameboyInstruction {
+                opcode,
+                mnemonic: "RES 6, B",
+                instruction_family: GameboyInstructionFamily::RES,
+                operand1: Some(GameboyInstructionOperand::Immediate8(6)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::B)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0xB1 => GameboyInstruction {
+                opcode,
+                mnemonic: "RES 6, C",
+                instruction_family: GameboyInstructionFamily::RES,
+                operand1: Some(GameboyInstructionOperand::Immediate8(6)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::C)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0xB2 => GameboyInstruction {
+                opcode,
+                mnemonic: "RES 6, D",
+                instruction_family: GameboyInstructionFamily::RES,
+                operand1: Some(GameboyInstructionOperand::Immediate8(6)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::D)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0xB3 => GameboyInstruction {
+                opcode,
+                mnemonic: "RES 6, E",
+                instruction_family: GameboyInstructionFamily::RES,
+                operand1: Some(GameboyInstructionOperand::Immediate8(6)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::E)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0xB4 => GameboyInstruction {
+                opcode,
+                mnemonic: "RES 6, H",
+                instruction_family: GameboyInstructionFamily::RES,
+                operand1: Some(GameboyInstructionOperand::Immediate8(6)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::H)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0xB5 => GameboyInstruction {
+                opcode,
+                mnemonic: "RES 6, L",
+                instruction_family: GameboyInstructionFamily::RES,
+                operand1: Some(GameboyInstructionOperand::Immediate8(6)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::L)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0xB6 => GameboyInstruction {
+                opcode,
+                mnemonic: "RES 6, (HL)",
+                instruction_family: GameboyInstructionFamily::RES,
+                operand1: Some(GameboyInstructionOperand::Immediate8(6)),
+                operand2: Some(GameboyInstructionOperand::Pointer(GameboyNamedRegister16::HL, None)),
+                condition: None,
+                cycles: 16,
+                size: 2,
+            },
+            0xB7 => GameboyInstruction {
+                opcode,
+                mnemonic: "RES 6, A",
+                instruction_family: GameboyInstructionFamily::RES,
+                operand1: Some(GameboyInstructionOperand::Immediate8(6)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0xB8 => GameboyInstruction {
+                opcode,
+                mnemonic: "RES 7, B",
+                instruction_family: GameboyInstructionFamily::RES,
+                operand1: Some(GameboyInstructionOperand::Immediate8(7)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::B)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0xB9 => GameboyInstruction {
+                opcode,
+                mnemonic: "RES 7, C",
+                instruction_family: GameboyInstructionFamily::RES,
+                operand1: Some(GameboyInstructionOperand::Immediate8(7)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::C)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0xBA => GameboyInstruction {
+                opcode,
+                mnemonic: "RES 7, D",
+                instruction_family: GameboyInstructionFamily::RES,
+                operand1: Some(GameboyInstructionOperand::Immediate8(7)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::D)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0xBB => GameboyInstruction {
+                opcode,
+                mnemonic: "RES 7, E",
+                instruction_family: GameboyInstructionFamily::RES,
+                operand1: Some(GameboyInstructionOperand::Immediate8(7)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::E)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0xBC => GameboyInstruction {
+                opcode,
+                mnemonic: "RES 7, H",
+                instruction_family: GameboyInstructionFamily::RES,
+                operand1: Some(GameboyInstructionOperand::Immediate8(7)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::H)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0xBD => GameboyInstruction {
+                opcode,
+                mnemonic: "RES 7, L",
+                instruction_family: GameboyInstructionFamily::RES,
+                operand1: Some(GameboyInstructionOperand::Immediate8(7)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::L)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0xBE => GameboyInstruction {
+                opcode,
+                mnemonic: "RES 7, (HL)",
+                instruction_family: GameboyInstructionFamily::RES,
+                operand1: Some(GameboyInstructionOperand::Immediate8(7)),
+                operand2: Some(GameboyInstructionOperand::Pointer(GameboyNamedRegister16::HL, None)),
+                condition: None,
+                cycles: 16,
+                size: 2,
+            },
+            0xBF => GameboyInstruction {
+                opcode,
+                mnemonic: "RES 7, A",
+                instruction_family: GameboyInstructionFamily::RES,
+                operand1: Some(GameboyInstructionOperand::Immediate8(7)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0xC0 => GameboyInstruction {
+                opcode,
+                mnemonic: "SET 0, B",
+                instruction_family: GameboyInstructionFamily::SET,
+                operand1: Some(GameboyInstructionOperand::Immediate8(0)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::B)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0xC1 => GameboyInstruction {
+                opcode,
+                mnemonic: "SET 0, C",
+                instruction_family: GameboyInstructionFamily::SET,
+                operand1: Some(GameboyInstructionOperand::Immediate8(0)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::C)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0xC2 => GameboyInstruction {
+                opcode,
+                mnemonic: "SET 0, D",
+                instruction_family: GameboyInstructionFamily::SET,
+                operand1: Some(GameboyInstructionOperand::Immediate8(0)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::D)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0xC3 => GameboyInstruction {
+                opcode,
+                mnemonic: "SET 0, E",
+                instruction_family: GameboyInstructionFamily::SET,
+                operand1: Some(GameboyInstructionOperand::Immediate8(0)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::E)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0xC4 => GameboyInstruction {
+                opcode,
+                mnemonic: "SET 0, H",
+                instruction_family: GameboyInstructionFamily::SET,
+                operand1: Some(GameboyInstructionOperand::Immediate8(0)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::H)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0xC5 => GameboyInstruction {
+                opcode,
+                mnemonic: "SET 0, L",
+                instruction_family: GameboyInstructionFamily::SET,
+                operand1: Some(GameboyInstructionOperand::Immediate8(0)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::L)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0xC6 => GameboyInstruction {
+                opcode,
+                mnemonic: "SET 0, (HL)",
+                instruction_family: GameboyInstructionFamily::SET,
+                operand1: Some(GameboyInstructionOperand::Immediate8(0)),
+                operand2: Some(GameboyInstructionOperand::Pointer(GameboyNamedRegister16::HL, None)),
+                condition: None,
+                cycles: 16,
+                size: 2,
+            },
+            0xC7 => GameboyInstruction {
+                opcode,
+                mnemonic: "SET 0, A",
+                instruction_family: GameboyInstructionFamily::SET,
+                operand1: Some(GameboyInstructionOperand::Immediate8(0)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0xC8 => GameboyInstruction {
+                opcode,
+                mnemonic: "SET 1, B",
+                instruction_family: GameboyInstructionFamily::SET,
+                operand1: Some(GameboyInstructionOperand::Immediate8(1)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::B)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0xC9 => GameboyInstruction {
+                opcode,
+                mnemonic: "SET 1, C",
+                instruction_family: GameboyInstructionFamily::SET,
+                operand1: Some(GameboyInstructionOperand::Immediate8(1)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::C)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0xCA => GameboyInstruction {
+                opcode,
+                mnemonic: "SET 1, D",
+                instruction_family: GameboyInstructionFamily::SET,
+                operand1: Some(GameboyInstructionOperand::Immediate8(1)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::D)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0xCB => GameboyInstruction {
+                opcode,
+                mnemonic: "SET 1, E",
+                instruction_family: GameboyInstructionFamily::SET,
+                operand1: Some(GameboyInstructionOperand::Immediate8(1)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::E)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0xCC => GameboyInstruction {
+                opcode,
+                mnemonic: "SET 1, H",
+                instruction_family: GameboyInstructionFamily::SET,
+                operand1: Some(GameboyInstructionOperand::Immediate8(1)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::H)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0xCD => GameboyInstruction {
+                opcode,
+                mnemonic: "SET 1, L",
+                instruction_family: GameboyInstructionFamily::SET,
+                operand1: Some(GameboyInstructionOperand::Immediate8(1)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::L)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0xCE => GameboyInstruction {
+                opcode,
+                mnemonic: "SET 1, (HL)",
+                instruction_family: GameboyInstructionFamily::SET,
+                operand1: Some(GameboyInstructionOperand::Immediate8(1)),
+                operand2: Some(GameboyInstructionOperand::Pointer(GameboyNamedRegister16::HL, None)),
+                condition: None,
+                cycles: 16,
+                size: 2,
+            },
+            0xCF => GameboyInstruction {
+                opcode,
+                mnemonic: "SET 1, A",
+                instruction_family: GameboyInstructionFamily::SET,
+                operand1: Some(GameboyInstructionOperand::Immediate8(1)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0xD0 => GameboyInstruction {
+                opcode,
+                mnemonic: "SET 2, B",
+                instruction_family: GameboyInstructionFamily::SET,
+                operand1: Some(GameboyInstructionOperand::Immediate8(2)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::B)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0xD1 => GameboyInstruction {
+                opcode,
+                mnemonic: "SET 2, C",
+                instruction_family: GameboyInstructionFamily::SET,
+                operand1: Some(GameboyInstructionOperand::Immediate8(2)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::C)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0xD2 => GameboyInstruction {
+                opcode,
+                mnemonic: "SET 2, D",
+                instruction_family: GameboyInstructionFamily::SET,
+                operand1: Some(GameboyInstructionOperand::Immediate8(2)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::D)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0xD3 => GameboyInstruction {
+                opcode,
+                mnemonic: "SET 2, E",
+                instruction_family: GameboyInstructionFamily::SET,
+                operand1: Some(GameboyInstructionOperand::Immediate8(2)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::E)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0xD4 => GameboyInstruction {
+                opcode,
+                mnemonic: "SET 2, H",
+                instruction_family: GameboyInstructionFamily::SET,
+                operand1: Some(GameboyInstructionOperand::Immediate8(2)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::H)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0xD5 => GameboyInstruction {
+                opcode,
+                mnemonic: "SET 2, L",
+                instruction_family: GameboyInstructionFamily::SET,
+                operand1: Some(GameboyInstructionOperand::Immediate8(2)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::L)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0xD6 => GameboyInstruction {
+                opcode,
+                mnemonic: "SET 2, (HL)",
+                instruction_family: GameboyInstructionFamily::SET,
+                operand1: Some(GameboyInstructionOperand::Immediate8(2)),
+                operand2: Some(GameboyInstructionOperand::Pointer(GameboyNamedRegister16::HL, None)),
+                condition: None,
+                cycles: 16,
+                size: 2,
+            },
+            0xD7 => GameboyInstruction {
+                opcode,
+                mnemonic: "SET 2, A",
+                instruction_family: GameboyInstructionFamily::SET,
+                operand1: Some(GameboyInstructionOperand::Immediate8(2)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0xD8 => GameboyInstruction {
+                opcode,
+                mnemonic: "SET 3, B",
+                instruction_family: GameboyInstructionFamily::SET,
+                operand1: Some(GameboyInstructionOperand::Immediate8(3)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::B)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0xD9 => GameboyInstruction {
+                opcode,
+                mnemonic: "SET 3, C",
+                instruction_family: GameboyInstructionFamily::SET,
+                operand1: Some(GameboyInstructionOperand::Immediate8(3)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::C)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0xDA => GameboyInstruction {
+                opcode,
+                mnemonic: "SET 3, D",
+                instruction_family: GameboyInstructionFamily::SET,
+                operand1: Some(GameboyInstructionOperand::Immediate8(3)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::D)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0xDB => GameboyInstruction {
+                opcode,
+                mnemonic: "SET 3, E",
+                instruction_family: GameboyInstructionFamily::SET,
+                operand1: Some(GameboyInstructionOperand::Immediate8(3)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::E)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0xDC => GameboyInstruction {
+                opcode,
+                mnemonic: "SET 3, H",
+                instruction_family: GameboyInstructionFamily::SET,
+                operand1: Some(GameboyInstructionOperand::Immediate8(3)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::H)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0xDD => GameboyInstruction {
+                opcode,
+                mnemonic: "SET 3, L",
+                instruction_family: GameboyInstructionFamily::SET,
+                operand1: Some(GameboyInstructionOperand::Immediate8(3)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::L)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0xDE => GameboyInstruction {
+                opcode,
+                mnemonic: "SET 3, (HL)",
+                instruction_family: GameboyInstructionFamily::SET,
+                operand1: Some(GameboyInstructionOperand::Immediate8(3)),
+                operand2: Some(GameboyInstructionOperand::Pointer(GameboyNamedRegister16::HL, None)),
+                condition: None,
+                cycles: 16,
+                size: 2,
+            },
+            0xDF => GameboyInstruction {
+                opcode,
+                mnemonic: "SET 3, A",
+                instruction_family: GameboyInstructionFamily::SET,
+                operand1: Some(GameboyInstructionOperand::Immediate8(3)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0xE0 => GameboyInstruction {
+                opcode,
+                mnemonic: "SET 4, B",
+                instruction_family: GameboyInstructionFamily::SET,
+                operand1: Some(GameboyInstructionOperand::Immediate8(4)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::B)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0xE1 => GameboyInstruction {
+                opcode,
+                mnemonic: "SET 4, C",
+                instruction_family: GameboyInstructionFamily::SET,
+                operand1: Some(GameboyInstructionOperand::Immediate8(4)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::C)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0xE2 => GameboyInstruction {
+                opcode,
+                mnemonic: "SET 4, D",
+                instruction_family: GameboyInstructionFamily::SET,
+                operand1: Some(GameboyInstructionOperand::Immediate8(4)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::D)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0xE3 => GameboyInstruction {
+                opcode,
+                mnemonic: "SET 4, E",
+                instruction_family: GameboyInstructionFamily::SET,
+                operand1: Some(GameboyInstructionOperand::Immediate8(4)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::E)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0xE4 => GameboyInstruction {
+                opcode,
+                mnemonic: "SET 4, H",
+                instruction_family: GameboyInstructionFamily::SET,
+                operand1: Some(GameboyInstructionOperand::Immediate8(4)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::H)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0xE5 => GameboyInstruction {
+                opcode,
+                mnemonic: "SET 4, L",
+                instruction_family: GameboyInstructionFamily::SET,
+                operand1: Some(GameboyInstructionOperand::Immediate8(4)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::L)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0xE6 => GameboyInstruction {
+                opcode,
+                mnemonic: "SET 4, (HL)",
+                instruction_family: GameboyInstructionFamily::SET,
+                operand1: Some(GameboyInstructionOperand::Immediate8(4)),
+                operand2: Some(GameboyInstructionOperand::Pointer(GameboyNamedRegister16::HL, None)),
+                condition: None,
+                cycles: 16,
+                size: 2,
+            },
+            0xE7 => GameboyInstruction {
+                opcode,
+                mnemonic: "SET 4, A",
+                instruction_family: GameboyInstructionFamily::SET,
+                operand1: Some(GameboyInstructionOperand::Immediate8(4)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0xE8 => GameboyInstruction {
+                opcode,
+                mnemonic: "SET 5, B",
+                instruction_family: GameboyInstructionFamily::SET,
+                operand1: Some(GameboyInstructionOperand::Immediate8(5)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::B)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0xE9 => GameboyInstruction {
+                opcode,
+                mnemonic: "SET 5, C",
+                instruction_family: GameboyInstructionFamily::SET,
+                operand1: Some(GameboyInstructionOperand::Immediate8(5)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::C)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0xEA => GameboyInstruction {
+                opcode,
+                mnemonic: "SET 5, D",
+                instruction_family: GameboyInstructionFamily::SET,
+                operand1: Some(GameboyInstructionOperand::Immediate8(5)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::D)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0xEB => GameboyInstruction {
+                opcode,
+                mnemonic: "SET 5, E",
+                instruction_family: GameboyInstructionFamily::SET,
+                operand1: Some(GameboyInstructionOperand::Immediate8(5)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::E)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0xEC => GameboyInstruction {
+                opcode,
+                mnemonic: "SET 5, H",
+                instruction_family: GameboyInstructionFamily::SET,
+                operand1: Some(GameboyInstructionOperand::Immediate8(5)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::H)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0xED => GameboyInstruction {
+                opcode,
+                mnemonic: "SET 5, L",
+                instruction_family: GameboyInstructionFamily::SET,
+                operand1: Some(GameboyInstructionOperand::Immediate8(5)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::L)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0xEE => GameboyInstruction {
+                opcode,
+                mnemonic: "SET 5, (HL)",
+                instruction_family: GameboyInstructionFamily::SET,
+                operand1: Some(GameboyInstructionOperand::Immediate8(5)),
+                operand2: Some(GameboyInstructionOperand::Pointer(GameboyNamedRegister16::HL, None)),
+                condition: None,
+                cycles: 16,
+                size: 2,
+            },
+            0xEF => GameboyInstruction {
+                opcode,
+                mnemonic: "SET 5, A",
+                instruction_family: GameboyInstructionFamily::SET,
+                operand1: Some(GameboyInstructionOperand::Immediate8(5)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0xF0 => GameboyInstruction {
+                opcode,
+                mnemonic: "SET 6, B",
+                instruction_family: GameboyInstructionFamily::SET,
+                operand1: Some(GameboyInstructionOperand::Immediate8(6)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::B)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0xF1 => GameboyInstruction {
+                opcode,
+                mnemonic: "SET 6, C",
+                instruction_family: GameboyInstructionFamily::SET,
+                operand1: Some(GameboyInstructionOperand::Immediate8(6)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::C)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0xF2 => GameboyInstruction {
+                opcode,
+                mnemonic: "SET 6, D",
+                instruction_family: GameboyInstructionFamily::SET,
+                operand1: Some(GameboyInstructionOperand::Immediate8(6)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::D)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0xF3 => GameboyInstruction {
+                opcode,
+                mnemonic: "SET 6, E",
+                instruction_family: GameboyInstructionFamily::SET,
+                operand1: Some(GameboyInstructionOperand::Immediate8(6)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::E)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0xF4 => GameboyInstruction {
+                opcode,
+                mnemonic: "SET 6, H",
+                instruction_family: GameboyInstructionFamily::SET,
+                operand1: Some(GameboyInstructionOperand::Immediate8(6)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::H)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0xF5 => GameboyInstruction {
+                opcode,
+                mnemonic: "SET 6, L",
+                instruction_family: GameboyInstructionFamily::SET,
+                operand1: Some(GameboyInstructionOperand::Immediate8(6)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::L)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0xF6 => GameboyInstruction {
+                opcode,
+                mnemonic: "SET 6, (HL)",
+                instruction_family: GameboyInstructionFamily::SET,
+                operand1: Some(GameboyInstructionOperand::Immediate8(6)),
+                operand2: Some(GameboyInstructionOperand::Pointer(GameboyNamedRegister16::HL, None)),
+                condition: None,
+                cycles: 16,
+                size: 2,
+            },
+            0xF7 => GameboyInstruction {
+                opcode,
+                mnemonic: "SET 6, A",
+                instruction_family: GameboyInstructionFamily::SET,
+                operand1: Some(GameboyInstructionOperand::Immediate8(6)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0xF8 => GameboyInstruction {
+                opcode,
+                mnemonic: "SET 7, B",
+                instruction_family: GameboyInstructionFamily::SET,
+                operand1: Some(GameboyInstructionOperand::Immediate8(7)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::B)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0xF9 => GameboyInstruction {
+                opcode,
+                mnemonic: "SET 7, C",
+                instruction_family: GameboyInstructionFamily::SET,
+                operand1: Some(GameboyInstructionOperand::Immediate8(7)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::C)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0xFA => GameboyInstruction {
+                opcode,
+                mnemonic: "SET 7, D",
+                instruction_family: GameboyInstructionFamily::SET,
+                operand1: Some(GameboyInstructionOperand::Immediate8(7)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::D)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0xFB => GameboyInstruction {
+                opcode,
+                mnemonic: "SET 7, E",
+                instruction_family: GameboyInstructionFamily::SET,
+                operand1: Some(GameboyInstructionOperand::Immediate8(7)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::E)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0xFC => GameboyInstruction {
+                opcode,
+                mnemonic: "SET 7, H",
+                instruction_family: GameboyInstructionFamily::SET,
+                operand1: Some(GameboyInstructionOperand::Immediate8(7)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::H)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0xFD => GameboyInstruction {
+                opcode,
+                mnemonic: "SET 7, L",
+                instruction_family: GameboyInstructionFamily::SET,
+                operand1: Some(GameboyInstructionOperand::Immediate8(7)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::L)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0xFE => GameboyInstruction {
+                opcode,
+                mnemonic: "SET 7, (HL)",
+                instruction_family: GameboyInstructionFamily::SET,
+                operand1: Some(GameboyInstructionOperand::Immediate8(7)),
+                operand2: Some(GameboyInstructionOperand::Pointer(GameboyNamedRegister16::HL, None)),
+                condition: None,
+                cycles: 16,
+                size: 2,
+            },
+            0xFF => GameboyInstruction {
+                opcode,
+                mnemonic: "SET 7, A",
+                instruction_family: GameboyInstructionFamily::SET,
+                operand1: Some(GameboyInstructionOperand::Immediate8(7)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+        }
+    }
+
+    /// Decodes an instruction, reading its operands from `source`.
+    pub fn decode(source: &mut impl InstructionFetch, opcode: u8) -> GameboyInstruction {
+        match opcode {
+            0x00 => GameboyInstruction {
+                opcode,
+                mnemonic: "NOP",
+                instruction_family: GameboyInstructionFamily::NOP,
+                operand1: None,
+                operand2: None,
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0x01 => GameboyInstruction {
+                opcode,
+                mnemonic: "LD BC, n16",
+                instruction_family: GameboyInstructionFamily::LD,
+                operand1: Some(GameboyInstructionOperand::Register16(GameboyNamedRegister16::BC)),
+                operand2: Some(GameboyInstructionOperand::Immediate16(source.fetch16())),
+                condition: None,
+                cycles: 12,
+                size: 3,
+            },
+            0x02 => GameboyInstruction {
+                opcode,
+                mnemonic: "LD (BC), A",
+                instruction_family: GameboyInstructionFamily::LD,
+                operand1: Some(GameboyInstructionOperand::Pointer(GameboyNamedRegister16::BC, None)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
+                condition: None,
+                cycles: 8,
+                size: 1,
+            },
+            0x03 => GameboyInstruction {
+                opcode,
+                mnemonic: "INC BC",
+                instruction_family: GameboyInstructionFamily::INC,
+                operand1: Some(GameboyInstructionOperand::Register16(GameboyNamedRegister16::BC)),
+                operand2: None,
+                condition: None,
+                cycles: 8,
+                size: 1,
+            },
+            0x04 => GameboyInstruction {
+                opcode,
+                mnemonic: "INC B",
+                instruction_family: GameboyInstructionFamily::INC,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::B)),
+                operand2: None,
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0x05 => GameboyInstruction {
+                opcode,
+                mnemonic: "DEC B",
+                instruction_family: GameboyInstructionFamily::DEC,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::B)),
+                operand2: None,
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0x06 => GameboyInstruction {
+                opcode,
+                mnemonic: "LD B, n8",
+                instruction_family: GameboyInstructionFamily::LD,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::B)),
+                operand2: Some(GameboyInstructionOperand::Immediate8(source.fetch())),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0x07 => GameboyInstruction {
+                opcode,
+                mnemonic: "RLCA",
+                instruction_family: GameboyInstructionFamily::RLCA,
+                operand1: None,
+                operand2: None,
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0x08 => GameboyInstruction {
+                opcode,
+                mnemonic: "LD (n16), SP",
+                instruction_family: GameboyInstructionFamily::LD,
+                operand1: Some(GameboyInstructionOperand::AddressPointer(source.fetch16())),
+                operand2: Some(GameboyInstructionOperand::Register16(GameboyNamedRegister16::SP)),
+                condition: None,
+                cycles: 20,
+                size: 3,
+            },
+            0x09 => GameboyInstruction {
+                opcode,
+                mnemonic: "ADD HL, BC",
+                instruction_family: GameboyInstructionFamily::ADD,
+                operand1: Some(GameboyInstructionOperand::Register16(GameboyNamedRegister16::HL)),
+                operand2: Some(GameboyInstructionOperand::Register16(GameboyNamedRegister16::BC)),
+                condition: None,
+                cycles: 8,
+                size: 1,
+            },
+            0x0A => GameboyInstruction {
+                opcode,
+                mnemonic: "LD A, (BC)",
+                instruction_family: GameboyInstructionFamily::LD,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
+                operand2: Some(GameboyInstructionOperand::Pointer(GameboyNamedRegister16::BC, None)),
+                condition: None,
+                cycles: 8,
+                size: 1,
+            },
+            0x0B => GameboyInstruction {
+                opcode,
+                mnemonic: "DEC BC",
+                instruction_family: GameboyInstructionFamily::DEC,
+                operand1: Some(GameboyInstructionOperand::Register16(GameboyNamedRegister16::BC)),
+                operand2: None,
+                condition: None,
+                cycles: 8,
+                size: 1,
+            },
+            0x0C => GameboyInstruction {
+                opcode,
+                mnemonic: "INC C",
+                instruction_family: GameboyInstructionFamily::INC,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::C)),
+                operand2: None,
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0x0D => GameboyInstruction {
+                opcode,
+                mnemonic: "DEC C",
+                instruction_family: GameboyInstructionFamily::DEC,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::C)),
+                operand2: None,
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0x0E => GameboyInstruction {
+                opcode,
+                mnemonic: "LD C, n8",
+                instruction_family: GameboyInstructionFamily::LD,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::C)),
+                operand2: Some(GameboyInstructionOperand::Immediate8(source.fetch())),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0x0F => GameboyInstruction {
+                opcode,
+                mnemonic: "RRCA",
+                instruction_family: GameboyInstructionFamily::RRCA,
+                operand1: None,
+                operand2: None,
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0x10 => GameboyInstruction {
+                opcode,
+                mnemonic: "STOP",
+                instruction_family: GameboyInstructionFamily::STOP,
+                operand1: None,
+                operand2: None,
+                condition: None,
+                cycles: 4,
+                size: 2,
+            },
+            0x11 => GameboyInstruction {
+                opcode,
+                mnemonic: "LD DE, n16",
+                instruction_family: GameboyInstructionFamily::LD,
+                operand1: Some(GameboyInstructionOperand::Register16(GameboyNamedRegister16::DE)),
+                operand2: Some(GameboyInstructionOperand::Immediate16(source.fetch16())),
+                condition: None,
+                cycles: 12,
+                size: 3,
+            },
+            0x12 => GameboyInstruction {
+                opcode,
+                mnemonic: "LD (DE), A",
+                instruction_family: GameboyInstructionFamily::LD,
+                operand1: Some(GameboyInstructionOperand::Pointer(GameboyNamedRegister16::DE, None)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
+                condition: None,
+                cycles: 8,
+                size: 1,
+            },
+            0x13 => GameboyInstruction {
+                opcode,
+                mnemonic: "INC DE",
+                instruction_family: GameboyInstructionFamily::INC,
+                operand1: Some(GameboyInstructionOperand::Register16(GameboyNamedRegister16::DE)),
+                operand2: None,
+                condition: None,
+                cycles: 8,
+                size: 1,
+            },
+            0x14 => GameboyInstruction {
+                opcode,
+                mnemonic: "INC D",
+                instruction_family: GameboyInstructionFamily::INC,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::D)),
+                operand2: None,
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0x15 => GameboyInstruction {
+                opcode,
+                mnemonic: "DEC D",
+                instruction_family: GameboyInstructionFamily::DEC,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::D)),
+                operand2: None,
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0x16 => GameboyInstruction {
+                opcode,
+                mnemonic: "LD D, n8",
+                instruction_family: GameboyInstructionFamily::LD,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::D)),
+                operand2: Some(GameboyInstructionOperand::Immediate8(source.fetch())),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0x17 => GameboyInstruction {
+                opcode,
+                mnemonic: "RLA",
+                instruction_family: GameboyInstructionFamily::RLA,
+                operand1: None,
+                operand2: None,
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0x18 => GameboyInstruction {
+                opcode,
+                mnemonic: "JR e8",
+                instruction_family: GameboyInstructionFamily::JR,
+                operand1: Some(GameboyInstructionOperand::ImmediateSigned8(source.fetch() as i8)),
+                operand2: None,
+                condition: None,
+                cycles: 12,
+                size: 2,
+            },
+            0x19 => GameboyInstruction {
+                opcode,
+                mnemonic: "ADD HL, DE",
+                instruction_family: GameboyInstructionFamily::ADD,
+                operand1: Some(GameboyInstructionOperand::Register16(GameboyNamedRegister16::HL)),
+                operand2: Some(GameboyInstructionOperand::Register16(GameboyNamedRegister16::DE)),
+                condition: None,
+                cycles: 8,
+                size: 1,
+            },
+            0x1A => GameboyInstruction {
+                opcode,
+                mnemonic: "LD A, (DE)",
+                instruction_family: GameboyInstructionFamily::LD,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
+                operand2: Some(GameboyInstructionOperand::Pointer(GameboyNamedRegister16::DE, None)),
+                condition: None,
+                cycles: 8,
+                size: 1,
+            },
+            0x1B => GameboyInstruction {
+                opcode,
+                mnemonic: "DEC DE",
+                instruction_family: GameboyInstructionFamily::DEC,
+                operand1: Some(GameboyInstructionOperand::Register16(GameboyNamedRegister16::DE)),
+                operand2: None,
+                condition: None,
+                cycles: 8,
+                size: 1,
+            },
+            0x1C => GameboyInstruction {
+                opcode,
+                mnemonic: "INC E",
+                instruction_family: GameboyInstructionFamily::INC,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::E)),
+                operand2: None,
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0x1D => GameboyInstruction {
+                opcode,
+                mnemonic: "DEC E",
+                instruction_family: GameboyInstructionFamily::DEC,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::E)),
+                operand2: None,
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0x1E => GameboyInstruction {
+                opcode,
+                mnemonic: "LD E, n8",
+                instruction_family: GameboyInstructionFamily::LD,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::E)),
+                operand2: Some(GameboyInstructionOperand::Immediate8(source.fetch())),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0x1F => GameboyInstruction {
+                opcode,
+                mnemonic: "RRA",
+                instruction_family: GameboyInstructionFamily::RRA,
+                operand1: None,
+                operand2: None,
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0x20 => GameboyInstruction {
+                opcode,
+                mnemonic: "JR NZ, e8",
+                instruction_family: GameboyInstructionFamily::JR,
+                operand1: Some(GameboyInstructionOperand::ImmediateSigned8(source.fetch() as i8)),
+                operand2: None,
+                condition: Some(GameboyInstructionCondition::NZ),
+                cycles: 8,
+                size: 2,
+            },
+            0x21 => GameboyInstruction {
+                opcode,
+                mnemonic: "LD HL, n16",
+                instruction_family: GameboyInstructionFamily::LD,
+                operand1: Some(GameboyInstructionOperand::Register16(GameboyNamedRegister16::HL)),
+                operand2: Some(GameboyInstructionOperand::Immediate16(source.fetch16())),
+                condition: None,
+                cycles: 12,
+                size: 3,
+            },
+            0x22 => GameboyInstruction {
+                opcode,
+                mnemonic: "LD (HL+), A",
+                instruction_family: GameboyInstructionFamily::LD,
+                operand1: Some(GameboyInstructionOperand::Pointer(GameboyNamedRegister16::HL, Some(GameboyInstructionPointerOp::Increment))),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
+                condition: None,
+                cycles: 8,
+                size: 1,
+            },
+            0x23 => GameboyInstruction {
+                opcode,
+                mnemonic: "INC HL",
+                instruction_family: GameboyInstructionFamily::INC,
+                operand1: Some(GameboyInstructionOperand::Register16(GameboyNamedRegister16::HL)),
+                operand2: None,
+                condition: None,
+                cycles: 8,
+                size: 1,
+            },
+            0x24 => GameboyInstruction {
+                opcode,
+                mnemonic: "INC H",
+                instruction_family: GameboyInstructionFamily::INC,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::H)),
+                operand2: None,
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0x25 => GameboyInstruction {
+                opcode,
+                mnemonic: "DEC H",
+                instruction_family: GameboyInstructionFamily::DEC,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::H)),
+                operand2: None,
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0x26 => GameboyInstruction {
+                opcode,
+                mnemonic: "LD H, n8",
+                instruction_family: GameboyInstructionFamily::LD,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::H)),
+                operand2: Some(GameboyInstructionOperand::Immediate8(source.fetch())),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0x27 => GameboyInstruction {
+                opcode,
+                mnemonic: "DAA",
+                instruction_family: GameboyInstructionFamily::DAA,
+                operand1: None,
+                operand2: None,
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0x28 => GameboyInstruction {
+                opcode,
+                mnemonic: "JR Z, e8",
+                instruction_family: GameboyInstructionFamily::JR,
+                operand1: Some(GameboyInstructionOperand::ImmediateSigned8(source.fetch() as i8)),
+                operand2: None,
+                condition: Some(GameboyInstructionCondition::Z),
+                cycles: 8,
+                size: 2,
+            },
+            0x29 => GameboyInstruction {
+                opcode,
+                mnemonic: "ADD HL, HL",
+                instruction_family: GameboyInstructionFamily::ADD,
+                operand1: Some(GameboyInstructionOperand::Register16(GameboyNamedRegister16::HL)),
+                operand2: Some(GameboyInstructionOperand::Register16(GameboyNamedRegister16::HL)),
+                condition: None,
+                cycles: 8,
+                size: 1,
+            },
+            0x2A => GameboyInstruction {
+                opcode,
+                mnemonic: "LD A, (HL+)",
+                instruction_family: GameboyInstructionFamily::LD,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
+                operand2: Some(GameboyInstructionOperand::Pointer(GameboyNamedRegister16::HL, Some(GameboyInstructionPointerOp::Increment))),
+                condition: None,
+                cycles: 8,
+                size: 1,
+            },
+            0x2B => GameboyInstruction {
+                opcode,
+                mnemonic: "DEC HL",
+                instruction_family: GameboyInstructionFamily::DEC,
+                operand1: Some(GameboyInstructionOperand::Register16(GameboyNamedRegister16::HL)),
+                operand2: None,
+                condition: None,
+                cycles: 8,
+                size: 1,
+            },
+            0x2C => GameboyInstruction {
+                opcode,
+                mnemonic: "INC L",
+                instruction_family: GameboyInstructionFamily::INC,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::L)),
+                operand2: None,
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0x2D => GameboyInstruction {
+                opcode,
+                mnemonic: "DEC L",
+                instruction_family: GameboyInstructionFamily::DEC,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::L)),
+                operand2: None,
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0x2E => GameboyInstruction {
+                opcode,
+                mnemonic: "LD L, n8",
+                instruction_family: GameboyInstructionFamily::LD,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::L)),
+                operand2: Some(GameboyInstructionOperand::Immediate8(source.fetch())),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0x2F => GameboyInstruction {
+                opcode,
+                mnemonic: "CPL",
+                instruction_family: GameboyInstructionFamily::CPL,
+                operand1: None,
+                operand2: None,
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0x30 => GameboyInstruction {
+                opcode,
+                mnemonic: "JR NC, e8",
+                instruction_family: GameboyInstructionFamily::JR,
+                operand1: Some(GameboyInstructionOperand::ImmediateSigned8(source.fetch() as i8)),
+                operand2: None,
+                condition: Some(GameboyInstructionCondition::NC),
+                cycles: 8,
+                size: 2,
+            },
+            0x31 => GameboyInstruction {
+                opcode,
+                mnemonic: "LD SP, n16",
+                instruction_family: GameboyInstructionFamily::LD,
+                operand1: Some(GameboyInstructionOperand::Register16(GameboyNamedRegister16::SP)),
+                operand2: Some(GameboyInstructionOperand::Immediate16(source.fetch16())),
+                condition: None,
+                cycles: 12,
+                size: 3,
+            },
+            0x32 => GameboyInstruction {
+                opcode,
+                mnemonic: "LD (HL-), A",
+                instruction_family: GameboyInstructionFamily::LD,
+                operand1: Some(GameboyInstructionOperand::Pointer(GameboyNamedRegister16::HL, Some(GameboyInstructionPointerOp::Decrement))),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
+                condition: None,
+                cycles: 8,
+                size: 1,
+            },
+            0x33 => GameboyInstruction {
+                opcode,
+                mnemonic: "INC SP",
+                instruction_family: GameboyInstructionFamily::INC,
+                operand1: Some(GameboyInstructionOperand::Register16(GameboyNamedRegister16::SP)),
+                operand2: None,
+                condition: None,
+                cycles: 8,
+                size: 1,
+            },
+            0x34 => GameboyInstruction {
+                opcode,
+                mnemonic: "INC (HL)",
+                instruction_family: GameboyInstructionFamily::INC,
+                operand1: Some(GameboyInstructionOperand::Pointer(GameboyNamedRegister16::HL, None)),
+                operand2: None,
+                condition: None,
+                cycles: 12,
+                size: 1,
+            },
+            0x35 => GameboyInstruction {
+                opcode,
+                mnemonic: "DEC (HL)",
+                instruction_family: GameboyInstructionFamily::DEC,
+                operand1: Some(GameboyInstructionOperand::Pointer(GameboyNamedRegister16::HL, None)),
+                operand2: None,
+                condition: None,
+                cycles: 12,
+                size: 1,
+            },
+            0x36 => GameboyInstruction {
+                opcode,
+                mnemonic: "LD (HL), n8",
+                instruction_family: GameboyInstructionFamily::LD,
+                operand1: Some(GameboyInstructionOperand::Pointer(GameboyNamedRegister16::HL, None)),
+                operand2: Some(GameboyInstructionOperand::Immediate8(source.fetch())),
+                condition: None,
+                cycles: 12,
+                size: 2,
+            },
+            0x37 => GameboyInstruction {
+                opcode,
+                mnemonic: "SCF",
+                instruction_family: GameboyInstructionFamily::SCF,
+                operand1: None,
+                operand2: None,
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0x38 => GameboyInstruction {
+                opcode,
+                mnemonic: "JR C, e8",
+                instruction_family: GameboyInstructionFamily::JR,
+                operand1: Some(GameboyInstructionOperand::ImmediateSigned8(source.fetch() as i8)),
+                operand2: None,
+                condition: Some(GameboyInstructionCondition::C),
+                cycles: 8,
+                size: 2,
+            },
+            0x39 => GameboyInstruction {
+                opcode,
+                mnemonic: "ADD HL, SP",
+                instruction_family: GameboyInstructionFamily::ADD,
+                operand1: Some(GameboyInstructionOperand::Register16(GameboyNamedRegister16::HL)),
+                operand2: Some(GameboyInstructionOperand::Register16(GameboyNamedRegister16::SP)),
+                condition: None,
+                cycles: 8,
+                size: 1,
+            },
+            0x3A => GameboyInstruction {
+                opcode,
+                mnemonic: "LD A, (HL-)",
+                instruction_family: GameboyInstructionFamily::LD,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
+                operand2: Some(GameboyInstructionOperand::Pointer(GameboyNamedRegister16::HL, Some(GameboyInstructionPointerOp::Decrement))),
+                condition: None,
+                cycles: 8,
+                size: 1,
+            },
+            0x3B => GameboyInstruction {
+                opcode,
+                mnemonic: "DEC SP",
+                instruction_family: GameboyInstructionFamily::DEC,
+                operand1: Some(GameboyInstructionOperand::Register16(GameboyNamedRegister16::SP)),
+                operand2: None,
+                condition: None,
+                cycles: 8,
+                size: 1,
+            },
+            0x3C => GameboyInstruction {
+                opcode,
+                mnemonic: "INC A",
+                instruction_family: GameboyInstructionFamily::INC,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
+                operand2: None,
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0x3D => GameboyInstruction {
+                opcode,
+                mnemonic: "DEC A",
+                instruction_family: GameboyInstructionFamily::DEC,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
+                operand2: None,
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0x3E => GameboyInstruction {
+                opcode,
+                mnemonic: "LD A, n8",
+                instruction_family: GameboyInstructionFamily::LD,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
+                operand2: Some(GameboyInstructionOperand::Immediate8(source.fetch())),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0x3F => GameboyInstruction {
+                opcode,
+                mnemonic: "CCF",
+                instruction_family: GameboyInstructionFamily::CCF,
+                operand1: None,
+                operand2: None,
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0x40 => GameboyInstruction {
+                opcode,
+                mnemonic: "LD B, B",
+                instruction_family: GameboyInstructionFamily::LD,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::B)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::B)),
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0x41 => GameboyInstruction {
+                opcode,
+                mnemonic: "LD B, C",
+                instruction_family: GameboyInstructionFamily::LD,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::B)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::C)),
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0x42 => GameboyInstruction {
+                opcode,
+                mnemonic: "LD B, D",
+                instruction_family: GameboyInstructionFamily::LD,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::B)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::D)),
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0x43 => GameboyInstruction {
+                opcode,
+                mnemonic: "LD B, E",
+                instruction_family: GameboyInstructionFamily::LD,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::B)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::E)),
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0x44 => GameboyInstruction {
+                opcode,
+                mnemonic: "LD B, H",
+                instruction_family: GameboyInstructionFamily::LD,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::B)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::H)),
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0x45 => GameboyInstruction {
+                opcode,
+                mnemonic: "LD B, L",
+                instruction_family: GameboyInstructionFamily::LD,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::B)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::L)),
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0x46 => GameboyInstruction {
+                opcode,
+                mnemonic: "LD B, (HL)",
+                instruction_family: GameboyInstructionFamily::LD,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::B)),
+                operand2: Some(GameboyInstructionOperand::Pointer(GameboyNamedRegister16::HL, None)),
+                condition: None,
+                cycles: 8,
+                size: 1,
+            },
+            0x47 => GameboyInstruction {
+                opcode,
+                mnemonic: "LD B, A",
+                instruction_family: GameboyInstructionFamily::LD,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::B)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0x48 => GameboyInstruction {
+                opcode,
+                mnemonic: "LD C, B",
+                instruction_family: GameboyInstructionFamily::LD,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::C)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::B)),
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0x49 => GameboyInstruction {
+                opcode,
+                mnemonic: "LD C, C",
+                instruction_family: GameboyInstructionFamily::LD,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::C)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::C)),
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0x4A => GameboyInstruction {
+                opcode,
+                mnemonic: "LD C, D",
+                instruction_family: GameboyInstructionFamily::LD,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::C)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::D)),
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0x4B => GameboyInstruction {
+                opcode,
+                mnemonic: "LD C, E",
+                instruction_family: GameboyInstructionFamily::LD,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::C)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::E)),
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0x4C => GameboyInstruction {
+                opcode,
+                mnemonic: "LD C, H",
+                instruction_family: GameboyInstructionFamily::LD,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::C)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::H)),
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0x4D => GameboyInstruction {
+                opcode,
+                mnemonic: "LD C, L",
+                instruction_family: GameboyInstructionFamily::LD,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::C)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::L)),
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0x4E => GameboyInstruction {
+                opcode,
+                mnemonic: "LD C, (HL)",
+                instruction_family: GameboyInstructionFamily::LD,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::C)),
+                operand2: Some(GameboyInstructionOperand::Pointer(GameboyNamedRegister16::HL, None)),
+                condition: None,
+                cycles: 8,
+                size: 1,
+            },
+            0x4F => GameboyInstruction {
+                opcode,
+                mnemonic: "LD C, A",
+                instruction_family: GameboyInstructionFamily::LD,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::C)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0x50 => GameboyInstruction {
+                opcode,
+                mnemonic: "LD D, B",
+                instruction_family: GameboyInstructionFamily::LD,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::D)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::B)),
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0x51 => GameboyInstruction {
+                opcode,
+                mnemonic: "LD D, C",
+                instruction_family: GameboyInstructionFamily::LD,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::D)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::C)),
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0x52 => GameboyInstruction {
+                opcode,
+                mnemonic: "LD D, D",
+                instruction_family: GameboyInstructionFamily::LD,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::D)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::D)),
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0x53 => GameboyInstruction {
+                opcode,
+                mnemonic: "LD D, E",
+                instruction_family: GameboyInstructionFamily::LD,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::D)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::E)),
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0x54 => GameboyInstruction {
+                opcode,
+                mnemonic: "LD D, H",
+                instruction_family: GameboyInstructionFamily::LD,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::D)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::H)),
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0x55 => GameboyInstruction {
+                opcode,
+                mnemonic: "LD D, L",
+                instruction_family: GameboyInstructionFamily::LD,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::D)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::L)),
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0x56 => GameboyInstruction {
+                opcode,
+                mnemonic: "LD D, (HL)",
+                instruction_family: GameboyInstructionFamily::LD,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::D)),
+                operand2: Some(GameboyInstructionOperand::Pointer(GameboyNamedRegister16::HL, None)),
+                condition: None,
+                cycles: 8,
+                size: 1,
+            },
+            0x57 => GameboyInstruction {
+                opcode,
+                mnemonic: "LD D, A",
+                instruction_family: GameboyInstructionFamily::LD,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::D)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0x58 => GameboyInstruction {
+                opcode,
+                mnemonic: "LD E, B",
+                instruction_family: GameboyInstructionFamily::LD,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::E)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::B)),
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0x59 => GameboyInstruction {
+                opcode,
+                mnemonic: "LD E, C",
+                instruction_family: GameboyInstructionFamily::LD,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::E)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::C)),
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0x5A => GameboyInstruction {
+                opcode,
+                mnemonic: "LD E, D",
+                instruction_family: GameboyInstructionFamily::LD,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::E)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::D)),
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0x5B => GameboyInstruction {
+                opcode,
+                mnemonic: "LD E, E",
+                instruction_family: GameboyInstructionFamily::LD,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::E)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::E)),
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0x5C => GameboyInstruction {
+                opcode,
+                mnemonic: "LD E, H",
+                instruction_family: GameboyInstructionFamily::LD,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::E)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::H)),
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0x5D => GameboyInstruction {
+                opcode,
+                mnemonic: "LD E, L",
+                instruction_family: GameboyInstructionFamily::LD,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::E)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::L)),
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0x5E => GameboyInstruction {
+                opcode,
+                mnemonic: "LD E, (HL)",
+                instruction_family: GameboyInstructionFamily::LD,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::E)),
+                operand2: Some(GameboyInstructionOperand::Pointer(GameboyNamedRegister16::HL, None)),
+                condition: None,
+                cycles: 8,
+                size: 1,
+            },
+            0x5F => GameboyInstruction {
+                opcode,
+                mnemonic: "LD E, A",
+                instruction_family: GameboyInstructionFamily::LD,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::E)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0x60 => GameboyInstruction {
+                opcode,
+                mnemonic: "LD H, B",
+                instruction_family: GameboyInstructionFamily::LD,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::H)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::B)),
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0x61 => GameboyInstruction {
+                opcode,
+                mnemonic: "LD H, C",
+                instruction_family: GameboyInstructionFamily::LD,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::H)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::C)),
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0x62 => GameboyInstruction {
+                opcode,
+                mnemonic: "LD H, D",
+                instruction_family: GameboyInstructionFamily::LD,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::H)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::D)),
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0x63 => GameboyInstruction {
+                opcode,
+                mnemonic: "LD H, E",
+                instruction_family: GameboyInstructionFamily::LD,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::H)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::E)),
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0x64 => GameboyInstruction {
+                opcode,
+                mnemonic: "LD H, H",
+                instruction_family: GameboyInstructionFamily::LD,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::H)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::H)),
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0x65 => GameboyInstruction {
+                opcode,
+                mnemonic: "LD H, L",
+                instruction_family: GameboyInstructionFamily::LD,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::H)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::L)),
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0x66 => GameboyInstruction {
+                opcode,
+                mnemonic: "LD H, (HL)",
+                instruction_family: GameboyInstructionFamily::LD,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::H)),
+                operand2: Some(GameboyInstructionOperand::Pointer(GameboyNamedRegister16::HL, None)),
+                condition: None,
+                cycles: 8,
+                size: 1,
+            },
+            0x67 => GameboyInstruction {
+                opcode,
+                mnemonic: "LD H, A",
+                instruction_family: GameboyInstructionFamily::LD,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::H)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0x68 => GameboyInstruction {
+                opcode,
+                mnemonic: "LD L, B",
+                instruction_family: GameboyInstructionFamily::LD,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::L)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::B)),
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0x69 => GameboyInstruction {
+                opcode,
+                mnemonic: "LD L, C",
+                instruction_family: GameboyInstructionFamily::LD,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::L)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::C)),
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0x6A => GameboyInstruction {
+                opcode,
+                mnemonic: "LD L, D",
+                instruction_family: GameboyInstructionFamily::LD,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::L)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::D)),
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0x6B => GameboyInstruction {
+                opcode,
+                mnemonic: "LD L, E",
+                instruction_family: GameboyInstructionFamily::LD,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::L)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::E)),
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0x6C => GameboyInstruction {
+                opcode,
+                mnemonic: "LD L, H",
+                instruction_family: GameboyInstructionFamily::LD,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::L)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::H)),
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0x6D => GameboyInstruction {
+                opcode,
+                mnemonic: "LD L, L",
+                instruction_family: GameboyInstructionFamily::LD,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::L)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::L)),
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0x6E => GameboyInstruction {
+                opcode,
+                mnemonic: "LD L, (HL)",
+                instruction_family: GameboyInstructionFamily::LD,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::L)),
+                operand2: Some(GameboyInstructionOperand::Pointer(GameboyNamedRegister16::HL, None)),
+                condition: None,
+                cycles: 8,
+                size: 1,
+            },
+            0x6F => GameboyInstruction {
+                opcode,
+                mnemonic: "LD L, A",
+                instruction_family: GameboyInstructionFamily::LD,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::L)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0x70 => GameboyInstruction {
+                opcode,
+                mnemonic: "LD (HL), B",
+                instruction_family: GameboyInstructionFamily::LD,
+                operand1: Some(GameboyInstructionOperand::Pointer(GameboyNamedRegister16::HL, None)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::B)),
+                condition: None,
+                cycles: 8,
+                size: 1,
+            },
+            0x71 => GameboyInstruction {
+                opcode,
+                mnemonic: "LD (HL), C",
+                instruction_family: GameboyInstructionFamily::LD,
+                operand1: Some(GameboyInstructionOperand::Pointer(GameboyNamedRegister16::HL, None)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::C)),
+                condition: None,
+                cycles: 8,
+                size: 1,
+            },
+            0x72 => GameboyInstruction {
+                opcode,
+                mnemonic: "LD (HL), D",
+                instruction_family: GameboyInstructionFamily::LD,
+                operand1: Some(GameboyInstructionOperand::Pointer(GameboyNamedRegister16::HL, None)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::D)),
+                condition: None,
+                cycles: 8,
+                size: 1,
+            },
+            0x73 => GameboyInstruction {
+                opcode,
+                mnemonic: "LD (HL), E",
+                instruction_family: GameboyInstructionFamily::LD,
+                operand1: Some(GameboyInstructionOperand::Pointer(GameboyNamedRegister16::HL, None)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::E)),
+                condition: None,
+                cycles: 8,
+                size: 1,
+            },
+            0x74 => GameboyInstruction {
+                opcode,
+                mnemonic: "LD (HL), H",
+                instruction_family: GameboyInstructionFamily::LD,
+                operand1: Some(GameboyInstructionOperand::Pointer(GameboyNamedRegister16::HL, None)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::H)),
+                condition: None,
+                cycles: 8,
+                size: 1,
+            },
+            0x75 => GameboyInstruction {
+                opcode,
+                mnemonic: "LD (HL), L",
+                instruction_family: GameboyInstructionFamily::LD,
+                operand1: Some(GameboyInstructionOperand::Pointer(GameboyNamedRegister16::HL, None)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::L)),
+                condition: None,
+                cycles: 8,
+                size: 1,
+            },
+            0x76 => GameboyInstruction {
+                opcode,
+                mnemonic: "HALT",
+                instruction_family: GameboyInstructionFamily::HALT,
+                operand1: None,
+                operand2: None,
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0x77 => GameboyInstruction {
+                opcode,
+                mnemonic: "LD (HL), A",
+                instruction_family: GameboyInstructionFamily::LD,
+                operand1: Some(GameboyInstructionOperand::Pointer(GameboyNamedRegister16::HL, None)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
+                condition: None,
+                cycles: 8,
+                size: 1,
+            },
+            0x78 => GameboyInstruction {
+                opcode,
+                mnemonic: "LD A, B",
+                instruction_family: GameboyInstructionFamily::LD,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::B)),
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0x79 => GameboyInstruction {
+                opcode,
+                mnemonic: "LD A, C",
+                instruction_family: GameboyInstructionFamily::LD,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::C)),
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0x7A => GameboyInstruction {
+                opcode,
+                mnemonic: "LD A, D",
+                instruction_family: GameboyInstructionFamily::LD,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::D)),
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0x7B => GameboyInstruction {
+                opcode,
+                mnemonic: "LD A, E",
+                instruction_family: GameboyInstructionFamily::LD,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::E)),
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0x7C => GameboyInstruction {
+                opcode,
+                mnemonic: "LD A, H",
+                instruction_family: GameboyInstructionFamily::LD,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::H)),
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0x7D => GameboyInstruction {
+                opcode,
+                mnemonic: "LD A, L",
+                instruction_family: GameboyInstructionFamily::LD,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::L)),
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0x7E => GameboyInstruction {
+                opcode,
+                mnemonic: "LD A, (HL)",
+                instruction_family: GameboyInstructionFamily::LD,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
+                operand2: Some(GameboyInstructionOperand::Pointer(GameboyNamedRegister16::HL, None)),
+                condition: None,
+                cycles: 8,
+                size: 1,
+            },
+            0x7F => GameboyInstruction {
+                opcode,
+                mnemonic: "LD A, A",
+                instruction_family: GameboyInstructionFamily::LD,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0x80 => GameboyInstruction {
+                opcode,
+                mnemonic: "ADD A, B",
+                instruction_family: GameboyInstructionFamily::ADD,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::B)),
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0x81 => GameboyInstruction {
+                opcode,
+                mnemonic: "ADD A, C",
+                instruction_family: GameboyInstructionFamily::ADD,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::C)),
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0x82 => GameboyInstruction {
+                opcode,
+                mnemonic: "ADD A, D",
+                instruction_family: GameboyInstructionFamily::ADD,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::D)),
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0x83 => GameboyInstruction {
+                opcode,
+                mnemonic: "ADD A, E",
+                instruction_family: GameboyInstructionFamily::ADD,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::E)),
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0x84 => GameboyInstruction {
+                opcode,
+                mnemonic: "ADD A, H",
+                instruction_family: GameboyInstructionFamily::ADD,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::H)),
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0x85 => GameboyInstruction {
+                opcode,
+                mnemonic: "ADD A, L",
+                instruction_family: GameboyInstructionFamily::ADD,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::L)),
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0x86 => GameboyInstruction {
+                opcode,
+                mnemonic: "ADD A, (HL)",
+                instruction_family: GameboyInstructionFamily::ADD,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
+                operand2: Some(GameboyInstructionOperand::Pointer(GameboyNamedRegister16::HL, None)),
+                condition: None,
+                cycles: 8,
+                size: 1,
+            },
+            0x87 => GameboyInstruction {
+                opcode,
+                mnemonic: "ADD A, A",
+                instruction_family: GameboyInstructionFamily::ADD,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0x88 => GameboyInstruction {
+                opcode,
+                mnemonic: "ADC A, B",
+                instruction_family: GameboyInstructionFamily::ADC,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::B)),
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0x89 => GameboyInstruction {
+                opcode,
+                mnemonic: "ADC A, C",
+                instruction_family: GameboyInstructionFamily::ADC,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::C)),
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0x8A => GameboyInstruction {
+                opcode,
+                mnemonic: "ADC A, D",
+                instruction_family: GameboyInstructionFamily::ADC,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::D)),
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0x8B => GameboyInstruction {
+                opcode,
+                mnemonic: "ADC A, E",
+                instruction_family: GameboyInstructionFamily::ADC,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::E)),
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0x8C => GameboyInstruction {
+                opcode,
+                mnemonic: "ADC A, H",
+                instruction_family: GameboyInstructionFamily::ADC,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::H)),
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0x8D => GameboyInstruction {
+                opcode,
+                mnemonic: "ADC A, L",
+                instruction_family: GameboyInstructionFamily::ADC,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::L)),
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0x8E => GameboyInstruction {
+                opcode,
+                mnemonic: "ADC A, (HL)",
+                instruction_family: GameboyInstructionFamily::ADC,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
+                operand2: Some(GameboyInstructionOperand::Pointer(GameboyNamedRegister16::HL, None)),
+                condition: None,
+                cycles: 8,
+                size: 1,
+            },
+            0x8F => GameboyInstruction {
+                opcode,
+                mnemonic: "ADC A, A",
+                instruction_family: GameboyInstructionFamily::ADC,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0x90 => GameboyInstruction {
+                opcode,
+                mnemonic: "SUB A, B",
+                instruction_family: GameboyInstructionFamily::SUB,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::B)),
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0x91 => GameboyInstruction {
+                opcode,
+                mnemonic: "SUB A, C",
+                instruction_family: GameboyInstructionFamily::SUB,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::C)),
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0x92 => GameboyInstruction {
+                opcode,
+                mnemonic: "SUB A, D",
+                instruction_family: GameboyInstructionFamily::SUB,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::D)),
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0x93 => GameboyInstruction {
+                opcode,
+                mnemonic: "SUB A, E",
+                instruction_family: GameboyInstructionFamily::SUB,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::E)),
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0x94 => GameboyInstruction {
+                opcode,
+                mnemonic: "SUB A, H",
+                instruction_family: GameboyInstructionFamily::SUB,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::H)),
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0x95 => GameboyInstruction {
+                opcode,
+                mnemonic: "SUB A, L",
+                instruction_family: GameboyInstructionFamily::SUB,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::L)),
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0x96 => GameboyInstruction {
+                opcode,
+                mnemonic: "SUB A, (HL)",
+                instruction_family: GameboyInstructionFamily::SUB,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
+                operand2: Some(GameboyInstructionOperand::Pointer(GameboyNamedRegister16::HL, None)),
+                condition: None,
+                cycles: 8,
+                size: 1,
+            },
+            0x97 => GameboyInstruction {
+                opcode,
+                mnemonic: "SUB A, A",
+                instruction_family: GameboyInstructionFamily::SUB,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0x98 => GameboyInstruction {
+                opcode,
+                mnemonic: "SBC A, B",
+                instruction_family: GameboyInstructionFamily::SBC,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::B)),
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0x99 => GameboyInstruction {
+                opcode,
+                mnemonic: "SBC A, C",
+                instruction_family: GameboyInstructionFamily::SBC,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::C)),
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0x9A => GameboyInstruction {
+                opcode,
+                mnemonic: "SBC A, D",
+                instruction_family: GameboyInstructionFamily::SBC,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::D)),
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0x9B => GameboyInstruction {
+                opcode,
+                mnemonic: "SBC A, E",
+                instruction_family: GameboyInstructionFamily::SBC,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::E)),
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0x9C => GameboyInstruction {
+                opcode,
+                mnemonic: "SBC A, H",
+                instruction_family: GameboyInstructionFamily::SBC,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::H)),
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0x9D => GameboyInstruction {
+                opcode,
+                mnemonic: "SBC A, L",
+                instruction_family: GameboyInstructionFamily::SBC,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::L)),
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0x9E => GameboyInstruction {
+                opcode,
+                mnemonic: "SBC A, (HL)",
+                instruction_family: GameboyInstructionFamily::SBC,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
+                operand2: Some(GameboyInstructionOperand::Pointer(GameboyNamedRegister16::HL, None)),
+                condition: None,
+                cycles: 8,
+                size: 1,
+            },
+            0x9F => GameboyInstruction {
+                opcode,
+                mnemonic: "SBC A, A",
+                instruction_family: GameboyInstructionFamily::SBC,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0xA0 => GameboyInstruction {
+                opcode,
+                mnemonic: "AND B",
+                instruction_family: GameboyInstructionFamily::AND,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::B)),
+                operand2: None,
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0xA1 => GameboyInstruction {
+                opcode,
+                mnemonic: "AND C",
+                instruction_family: GameboyInstructionFamily::AND,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::C)),
+                operand2: None,
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0xA2 => GameboyInstruction {
+                opcode,
+                mnemonic: "AND D",
+                instruction_family: GameboyInstructionFamily::AND,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::D)),
+                operand2: None,
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0xA3 => GameboyInstruction {
+                opcode,
+                mnemonic: "AND E",
+                instruction_family: GameboyInstructionFamily::AND,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::E)),
+                operand2: None,
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0xA4 => GameboyInstruction {
+                opcode,
+                mnemonic: "AND H",
+                instruction_family: GameboyInstructionFamily::AND,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::H)),
+                operand2: None,
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0xA5 => GameboyInstruction {
+                opcode,
+                mnemonic: "AND L",
+                instruction_family: GameboyInstructionFamily::AND,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::L)),
+                operand2: None,
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0xA6 => GameboyInstruction {
+                opcode,
+                mnemonic: "AND (HL)",
+                instruction_family: GameboyInstructionFamily::AND,
+                operand1: Some(GameboyInstructionOperand::Pointer(GameboyNamedRegister16::HL, None)),
+                operand2: None,
+                condition: None,
+                cycles: 8,
+                size: 1,
+            },
+            0xA7 => GameboyInstruction {
+                opcode,
+                mnemonic: "AND A",
+                instruction_family: GameboyInstructionFamily::AND,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
+                operand2: None,
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0xA8 => GameboyInstruction {
+                opcode,
+                mnemonic: "XOR B",
+                instruction_family: GameboyInstructionFamily::XOR,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::B)),
+                operand2: None,
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0xA9 => GameboyInstruction {
+                opcode,
+                mnemonic: "XOR C",
+                instruction_family: GameboyInstructionFamily::XOR,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::C)),
+                operand2: None,
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0xAA => GameboyInstruction {
+                opcode,
+                mnemonic: "XOR D",
+                instruction_family: GameboyInstructionFamily::XOR,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::D)),
+                operand2: None,
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0xAB => GameboyInstruction {
+                opcode,
+                mnemonic: "XOR E",
+                instruction_family: GameboyInstructionFamily::XOR,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::E)),
+                operand2: None,
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0xAC => GameboyInstruction {
+                opcode,
+                mnemonic: "XOR H",
+                instruction_family: GameboyInstructionFamily::XOR,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::H)),
+                operand2: None,
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0xAD => GameboyInstruction {
+                opcode,
+                mnemonic: "XOR L",
+                instruction_family: GameboyInstructionFamily::XOR,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::L)),
+                operand2: None,
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0xAE => GameboyInstruction {
+                opcode,
+                mnemonic: "XOR (HL)",
+                instruction_family: GameboyInstructionFamily::XOR,
+                operand1: Some(GameboyInstructionOperand::Pointer(GameboyNamedRegister16::HL, None)),
+                operand2: None,
+                condition: None,
+                cycles: 8,
+                size: 1,
+            },
+            0xAF => GameboyInstruction {
+                opcode,
+                mnemonic: "XOR A",
+                instruction_family: GameboyInstructionFamily::XOR,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
+                operand2: None,
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0xB0 => GameboyInstruction {
+                opcode,
+                mnemonic: "OR B",
+                instruction_family: GameboyInstructionFamily::OR,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::B)),
+                operand2: None,
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0xB1 => GameboyInstruction {
+                opcode,
+                mnemonic: "OR C",
+                instruction_family: GameboyInstructionFamily::OR,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::C)),
+                operand2: None,
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0xB2 => GameboyInstruction {
+                opcode,
+                mnemonic: "OR D",
+                instruction_family: GameboyInstructionFamily::OR,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::D)),
+                operand2: None,
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0xB3 => GameboyInstruction {
+                opcode,
+                mnemonic: "OR E",
+                instruction_family: GameboyInstructionFamily::OR,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::E)),
+                operand2: None,
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0xB4 => GameboyInstruction {
+                opcode,
+                mnemonic: "OR H",
+                instruction_family: GameboyInstructionFamily::OR,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::H)),
+                operand2: None,
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0xB5 => GameboyInstruction {
+                opcode,
+                mnemonic: "OR L",
+                instruction_family: GameboyInstructionFamily::OR,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::L)),
+                operand2: None,
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0xB6 => GameboyInstruction {
+                opcode,
+                mnemonic: "OR (HL)",
+                instruction_family: GameboyInstructionFamily::OR,
+                operand1: Some(GameboyInstructionOperand::Pointer(GameboyNamedRegister16::HL, None)),
+                operand2: None,
+                condition: None,
+                cycles: 8,
+                size: 1,
+            },
+            0xB7 => GameboyInstruction {
+                opcode,
+                mnemonic: "OR A",
+                instruction_family: GameboyInstructionFamily::OR,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
+                operand2: None,
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0xB8 => GameboyInstruction {
+                opcode,
+                mnemonic: "CP B",
+                instruction_family: GameboyInstructionFamily::CP,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::B)),
+                operand2: None,
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0xB9 => GameboyInstruction {
+                opcode,
+                mnemonic: "CP C",
+                instruction_family: GameboyInstructionFamily::CP,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::C)),
+                operand2: None,
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0xBA => GameboyInstruction {
+                opcode,
+                mnemonic: "CP D",
+                instruction_family: GameboyInstructionFamily::CP,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::D)),
+                operand2: None,
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0xBB => GameboyInstruction {
+                opcode,
+                mnemonic: "CP E",
+                instruction_family: GameboyInstructionFamily::CP,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::E)),
+                operand2: None,
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0xBC => GameboyInstruction {
+                opcode,
+                mnemonic: "CP H",
+                instruction_family: GameboyInstructionFamily::CP,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::H)),
+                operand2: None,
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0xBD => GameboyInstruction {
+                opcode,
+                mnemonic: "CP L",
+                instruction_family: GameboyInstructionFamily::CP,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::L)),
+                operand2: None,
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0xBE => GameboyInstruction {
+                opcode,
+                mnemonic: "CP (HL)",
+                instruction_family: GameboyInstructionFamily::CP,
+                operand1: Some(GameboyInstructionOperand::Pointer(GameboyNamedRegister16::HL, None)),
+                operand2: None,
+                condition: None,
+                cycles: 8,
+                size: 1,
+            },
+            0xBF => GameboyInstruction {
+                opcode,
+                mnemonic: "CP A",
+                instruction_family: GameboyInstructionFamily::CP,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
+                operand2: None,
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0xC0 => GameboyInstruction {
+                opcode,
+                mnemonic: "RET NZ",
+                instruction_family: GameboyInstructionFamily::RET,
+                operand1: None,
+                operand2: None,
+                condition: Some(GameboyInstructionCondition::NZ),
+                cycles: 8,
+                size: 1,
+            },
+            0xC1 => GameboyInstruction {
+                opcode,
+                mnemonic: "POP BC",
+                instruction_family: GameboyInstructionFamily::POP,
+                operand1: Some(GameboyInstructionOperand::Register16(GameboyNamedRegister16::BC)),
+                operand2: None,
+                condition: None,
+                cycles: 12,
+                size: 1,
+            },
+            0xC2 => GameboyInstruction {
+                opcode,
+                mnemonic: "JP NZ, n16",
+                instruction_family: GameboyInstructionFamily::JP,
+                operand1: Some(GameboyInstructionOperand::Address(source.fetch16())),
+                operand2: None,
+                condition: Some(GameboyInstructionCondition::NZ),
+                cycles: 12,
+                size: 3,
+            },
+            0xC3 => GameboyInstruction {
+                opcode,
+                mnemonic: "JP n16",
+                instruction_family: GameboyInstructionFamily::JP,
+                operand1: Some(GameboyInstructionOperand::Address(source.fetch16())),
+                operand2: None,
+                condition: None,
+                cycles: 16,
+                size: 3,
+            },
+            0xC4 => GameboyInstruction {
+                opcode,
+                mnemonic: "CALL NZ, n16",
+                instruction_family: GameboyInstructionFamily::CALL,
+                operand1: Some(GameboyInstructionOperand::Address(source.fetch16())),
+                operand2: None,
+                condition: Some(GameboyInstructionCondition::NZ),
+                cycles: 12,
+                size: 3,
+            },
+            0xC5 => GameboyInstruction {
+                opcode,
+                mnemonic: "PUSH BC",
+                instruction_family: GameboyInstructionFamily::PUSH,
+                operand1: Some(GameboyInstructionOperand::Register16(GameboyNamedRegister16::BC)),
+                operand2: None,
+                condition: None,
+                cycles: 16,
+                size: 1,
+            },
+            0xC6 => GameboyInstruction {
+                opcode,
+                mnemonic: "ADD A, n8",
+                instruction_family: GameboyInstructionFamily::ADD,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
+                operand2: Some(GameboyInstructionOperand::Immediate8(source.fetch())),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0xC7 => GameboyInstruction {
+                opcode,
+                mnemonic: "RST $00",
+                instruction_family: GameboyInstructionFamily::RST,
+                operand1: Some(GameboyInstructionOperand::Address(0x0000)),
+                operand2: None,
+                condition: None,
+                cycles: 16,
+                size: 1,
+            },
+            0xC8 => GameboyInstruction {
+                opcode,
+                mnemonic: "RET Z",
+                instruction_family: GameboyInstructionFamily::RET,
+                operand1: None,
+                operand2: None,
+                condition: Some(GameboyInstructionCondition::Z),
+                cycles: 8,
+                size: 1,
+            },
+            0xC9 => GameboyInstruction {
+                opcode,
+                mnemonic: "RET",
+                instruction_family: GameboyInstructionFamily::RET,
+                operand1: None,
+                operand2: None,
+                condition: None,
+                cycles: 16,
+                size: 1,
+            },
+            0xCA => GameboyInstruction {
+                opcode,
+                mnemonic: "JP Z, n16",
+                instruction_family: GameboyInstructionFamily::JP,
+                operand1: Some(GameboyInstructionOperand::Address(source.fetch16())),
+                operand2: None,
+                condition: Some(GameboyInstructionCondition::Z),
+                cycles: 12,
+                size: 3,
+            },
+            0xCB => {
+                let prefix_opcode = source.fetch();
+                GameboyInstruction::decode_prefix(prefix_opcode)
+            },
+            0xCC => GameboyInstruction {
+                opcode,
+                mnemonic: "CALL Z, n16",
+                instruction_family: GameboyInstructionFamily::CALL,
+                operand1: Some(GameboyInstructionOperand::Address(source.fetch16())),
+                operand2: None,
+                condition: Some(GameboyInstructionCondition::Z),
+                cycles: 12,
+                size: 3,
+            },
+            0xCD => GameboyInstruction {
+                opcode,
+                mnemonic: "CALL n16",
+                instruction_family: GameboyInstructionFamily::CALL,
+                operand1: Some(GameboyInstructionOperand::Address(source.fetch16())),
+                operand2: None,
+                condition: None,
+                cycles: 24,
+                size: 3,
+            },
+            0xCE => GameboyInstruction {
+                opcode,
+                mnemonic: "ADC A, n8",
+                instruction_family: GameboyInstructionFamily::ADC,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
+                operand2: Some(GameboyInstructionOperand::Immediate8(source.fetch())),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0xCF => GameboyInstruction {
+                opcode,
+                mnemonic: "RST $08",
+                instruction_family: GameboyInstructionFamily::RST,
+                operand1: Some(GameboyInstructionOperand::Address(0x0008)),
+                operand2: None,
+                condition: None,
+                cycles: 16,
+                size: 1,
+            },
+            0xD0 => GameboyInstruction {
+                opcode,
+                mnemonic: "RET NC",
+                instruction_family: GameboyInstructionFamily::RET,
+                operand1: None,
+                operand2: None,
+                condition: Some(GameboyInstructionCondition::NC),
+                cycles: 8,
+                size: 1,
+            },
+            0xD1 => GameboyInstruction {
+                opcode,
+                mnemonic: "POP DE",
+                instruction_family: GameboyInstructionFamily::POP,
+                operand1: Some(GameboyInstructionOperand::Register16(GameboyNamedRegister16::DE)),
+                operand2: None,
+                condition: None,
+                cycles: 12,
+                size: 1,
+            },
+            0xD2 => GameboyInstruction {
+                opcode,
+                mnemonic: "JP NC, n16",
+                instruction_family: GameboyInstructionFamily::JP,
+                operand1: Some(GameboyInstructionOperand::Address(source.fetch16())),
+                operand2: None,
+                condition: Some(GameboyInstructionCondition::NC),
+                cycles: 12,
+                size: 3,
+            },
+            0xD3 => GameboyInstruction {
+                opcode,
+                mnemonic: "ILLEGAL",
+                instruction_family: GameboyInstructionFamily::ILLEGAL,
+                operand1: None,
+                operand2: None,
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0xD4 => GameboyInstruction {
+                opcode,
+                mnemonic: "CALL NC, n16",
+                instruction_family: GameboyInstructionFamily::CALL,
+                operand1: Some(GameboyInstructionOperand::Address(source.fetch16())),
+                operand2: None,
+                condition: Some(GameboyInstructionCondition::NC),
+                cycles: 12,
+                size: 3,
+            },
+            0xD5 => GameboyInstruction {
+                opcode,
+                mnemonic: "PUSH DE",
+                instruction_family: GameboyInstructionFamily::PUSH,
+                operand1: Some(GameboyInstructionOperand::Register16(GameboyNamedRegister16::DE)),
+                operand2: None,
+                condition: None,
+                cycles: 16,
+                size: 1,
+            },
+            0xD6 => GameboyInstruction {
+                opcode,
+                mnemonic: "SUB A, n8",
+                instruction_family: GameboyInstructionFamily::SUB,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
+                operand2: Some(GameboyInstructionOperand::Immediate8(source.fetch())),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0xD7 => GameboyInstruction {
+                opcode,
+                mnemonic: "RST $10",
+                instruction_family: GameboyInstructionFamily::RST,
+                operand1: Some(GameboyInstructionOperand::Address(0x0010)),
+                operand2: None,
+                condition: None,
+                cycles: 16,
+                size: 1,
+            },
+            0xD8 => GameboyInstruction {
+                opcode,
+                mnemonic: "RET C",
+                instruction_family: GameboyInstructionFamily::RET,
+                operand1: None,
+                operand2: None,
+                condition: Some(GameboyInstructionCondition::C),
+                cycles: 8,
+                size: 1,
+            },
+            0xD9 => GameboyInstruction {
+                opcode,
+                mnemonic: "RETI",
+                instruction_family: GameboyInstructionFamily::RETI,
+                operand1: None,
+                operand2: None,
+                condition: None,
+                cycles: 16,
+                size: 1,
+            },
+            0xDA => GameboyInstruction {
+                opcode,
+                mnemonic: "JP C, n16",
+                instruction_family: GameboyInstructionFamily::JP,
+                operand1: Some(GameboyInstructionOperand::Address(source.fetch16())),
+                operand2: None,
+                condition: Some(GameboyInstructionCondition::C),
+                cycles: 12,
+                size: 3,
+            },
+            0xDB => GameboyInstruction {
+                opcode,
+                mnemonic: "ILLEGAL",
+                instruction_family: GameboyInstructionFamily::ILLEGAL,
+                operand1: None,
+                operand2: None,
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0xDC => GameboyInstruction {
+                opcode,
+                mnemonic: "CALL C, n16",
+                instruction_family: GameboyInstructionFamily::CALL,
+                operand1: Some(GameboyInstructionOperand::Address(source.fetch16())),
+                operand2: None,
+                condition: Some(GameboyInstructionCondition::C),
+                cycles: 12,
+                size: 3,
+            },
+            0xDD => GameboyInstruction {
+                opcode,
+                mnemonic: "ILLEGAL",
+                instruction_family: GameboyInstructionFamily::ILLEGAL,
+                operand1: None,
+                operand2: None,
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0xDE => GameboyInstruction {
+                opcode,
+                mnemonic: "SBC A, n8",
+                instruction_family: GameboyInstructionFamily::SBC,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
+                operand2: Some(GameboyInstructionOperand::Immediate8(source.fetch())),
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0xDF => GameboyInstruction {
+                opcode,
+                mnemonic: "RST $18",
+                instruction_family: GameboyInstructionFamily::RST,
+                operand1: Some(GameboyInstructionOperand::Address(0x0018)),
+                operand2: None,
+                condition: None,
+                cycles: 16,
+                size: 1,
+            },
+            0xE0 => GameboyInstruction {
+                opcode,
+                mnemonic: "LDH (n8), A",
+                instruction_family: GameboyInstructionFamily::LDH,
+                operand1: Some(GameboyInstructionOperand::Immediate8(source.fetch())),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
+                condition: None,
+                cycles: 12,
+                size: 2,
+            },
+            0xE1 => GameboyInstruction {
+                opcode,
+                mnemonic: "POP HL",
+                instruction_family: GameboyInstructionFamily::POP,
+                operand1: Some(GameboyInstructionOperand::Register16(GameboyNamedRegister16::HL)),
+                operand2: None,
+                condition: None,
+                cycles: 12,
+                size: 1,
+            },
+            0xE2 => GameboyInstruction {
+                opcode,
+                mnemonic: "LDH (C), A",
+                instruction_family: GameboyInstructionFamily::LDH,
+                operand1: Some(GameboyInstructionOperand::HighPointer(GameboyNamedRegister8::C)),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
+                condition: None,
+                cycles: 8,
+                size: 1,
+            },
+            0xE3 => GameboyInstruction {
+                opcode,
+                mnemonic: "ILLEGAL",
+                instruction_family: GameboyInstructionFamily::ILLEGAL,
+                operand1: None,
+                operand2: None,
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0xE4 => GameboyInstruction {
+                opcode,
+                mnemonic: "ILLEGAL",
+                instruction_family: GameboyInstructionFamily::ILLEGAL,
+                operand1: None,
+                operand2: None,
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0xE5 => GameboyInstruction {
+                opcode,
+                mnemonic: "PUSH HL",
+                instruction_family: GameboyInstructionFamily::PUSH,
+                operand1: Some(GameboyInstructionOperand::Register16(GameboyNamedRegister16::HL)),
+                operand2: None,
+                condition: None,
+                cycles: 16,
+                size: 1,
+            },
+            0xE6 => GameboyInstruction {
+                opcode,
+                mnemonic: "AND n8",
+                instruction_family: GameboyInstructionFamily::AND,
+                operand1: Some(GameboyInstructionOperand::Immediate8(source.fetch())),
+                operand2: None,
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0xE7 => GameboyInstruction {
+                opcode,
+                mnemonic: "RST $20",
+                instruction_family: GameboyInstructionFamily::RST,
+                operand1: Some(GameboyInstructionOperand::Address(0x0020)),
+                operand2: None,
+                condition: None,
+                cycles: 16,
+                size: 1,
+            },
+            0xE8 => GameboyInstruction {
+                opcode,
+                mnemonic: "ADD SP, e8",
+                instruction_family: GameboyInstructionFamily::ADD,
+                operand1: Some(GameboyInstructionOperand::Register16(GameboyNamedRegister16::SP)),
+                operand2: Some(GameboyInstructionOperand::ImmediateSigned8(source.fetch() as i8)),
+                condition: None,
+                cycles: 16,
+                size: 2,
+            },
+            0xE9 => GameboyInstruction {
+                opcode,
+                mnemonic: "JP HL",
+                instruction_family: GameboyInstructionFamily::JP,
+                operand1: Some(GameboyInstructionOperand::Register16(GameboyNamedRegister16::HL)),
+                operand2: None,
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0xEA => GameboyInstruction {
+                opcode,
+                mnemonic: "LD (n16), A",
+                instruction_family: GameboyInstructionFamily::LD,
+                operand1: Some(GameboyInstructionOperand::AddressPointer(source.fetch16())),
+                operand2: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
+                condition: None,
+                cycles: 16,
+                size: 3,
+            },
+            0xEB => GameboyInstruction {
+                opcode,
+                mnemonic: "ILLEGAL",
+                instruction_family: GameboyInstructionFamily::ILLEGAL,
+                operand1: None,
+                operand2: None,
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0xEC => GameboyInstruction {
+                opcode,
+                mnemonic: "ILLEGAL",
+                instruction_family: GameboyInstructionFamily::ILLEGAL,
+                operand1: None,
+                operand2: None,
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0xED => GameboyInstruction {
+                opcode,
+                mnemonic: "ILLEGAL",
+                instruction_family: GameboyInstructionFamily::ILLEGAL,
+                operand1: None,
+                operand2: None,
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0xEE => GameboyInstruction {
+                opcode,
+                mnemonic: "XOR n8",
+                instruction_family: GameboyInstructionFamily::XOR,
+                operand1: Some(GameboyInstructionOperand::Immediate8(source.fetch())),
+                operand2: None,
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0xEF => GameboyInstruction {
+                opcode,
+                mnemonic: "RST $28",
+                instruction_family: GameboyInstructionFamily::RST,
+                operand1: Some(GameboyInstructionOperand::Address(0x0028)),
+                operand2: None,
+                condition: None,
+                cycles: 16,
+                size: 1,
+            },
+            0xF0 => GameboyInstruction {
+                opcode,
+                mnemonic: "LDH A, (n8)",
+                instruction_family: GameboyInstructionFamily::LDH,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
+                operand2: Some(GameboyInstructionOperand::Immediate8(source.fetch())),
+                condition: None,
+                cycles: 12,
+                size: 2,
+            },
+            0xF1 => GameboyInstruction {
+                opcode,
+                mnemonic: "POP AF",
+                instruction_family: GameboyInstructionFamily::POP,
+                operand1: Some(GameboyInstructionOperand::Register16(GameboyNamedRegister16::AF)),
+                operand2: None,
+                condition: None,
+                cycles: 12,
+                size: 1,
+            },
+            0xF2 => GameboyInstruction {
+                opcode,
+                mnemonic: "LDH A, (C)",
+                instruction_family: GameboyInstructionFamily::LDH,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
+                operand2: Some(GameboyInstructionOperand::HighPointer(GameboyNamedRegister8::C)),
+                condition: None,
+                cycles: 8,
+                size: 1,
+            },
+            0xF3 => GameboyInstruction {
+                opcode,
+                mnemonic: "DI",
+                instruction_family: GameboyInstructionFamily::DI,
+                operand1: None,
+                operand2: None,
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0xF4 => GameboyInstruction {
+                opcode,
+                mnemonic: "ILLEGAL",
+                instruction_family: GameboyInstructionFamily::ILLEGAL,
+                operand1: None,
+                operand2: None,
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0xF5 => GameboyInstruction {
+                opcode,
+                mnemonic: "PUSH AF",
+                instruction_family: GameboyInstructionFamily::PUSH,
+                operand1: Some(GameboyInstructionOperand::Register16(GameboyNamedRegister16::AF)),
+                operand2: None,
+                condition: None,
+                cycles: 16,
+                size: 1,
+            },
+            0xF6 => GameboyInstruction {
+                opcode,
+                mnemonic: "OR n8",
+                instruction_family: GameboyInstructionFamily::OR,
+                operand1: Some(GameboyInstructionOperand::Immediate8(source.fetch())),
+                operand2: None,
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0xF7 => GameboyInstruction {
+                opcode,
+                mnemonic: "RST $30",
+                instruction_family: GameboyInstructionFamily::RST,
+                operand1: Some(GameboyInstructionOperand::Address(0x0030)),
+                operand2: None,
+                condition: None,
+                cycles: 16,
+                size: 1,
+            },
+            0xF8 => GameboyInstruction {
+                opcode,
+                mnemonic: "LD HL, SP+e8",
+                instruction_family: GameboyInstructionFamily::LD,
+                operand1: Some(GameboyInstructionOperand::Register16(GameboyNamedRegister16::HL)),
+                operand2: Some(GameboyInstructionOperand::StackPointerOffset(source.fetch() as i8)),
+                condition: None,
+                cycles: 12,
+                size: 2,
+            },
+            0xF9 => GameboyInstruction {
+                opcode,
+                mnemonic: "LD SP, HL",
+                instruction_family: GameboyInstructionFamily::LD,
+                operand1: Some(GameboyInstructionOperand::Register16(GameboyNamedRegister16::SP)),
+                operand2: Some(GameboyInstructionOperand::Register16(GameboyNamedRegister16::HL)),
+                condition: None,
+                cycles: 8,
+                size: 1,
+            },
+            0xFA => GameboyInstruction {
+                opcode,
+                mnemonic: "LD A, (n16)",
+                instruction_family: GameboyInstructionFamily::LD,
+                operand1: Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)),
+                operand2: Some(GameboyInstructionOperand::AddressPointer(source.fetch16())),
+                condition: None,
+                cycles: 16,
+                size: 3,
+            },
+            0xFB => GameboyInstruction {
+                opcode,
+                mnemonic: "EI",
+                instruction_family: GameboyInstructionFamily::EI,
+                operand1: None,
+                operand2: None,
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0xFC => GameboyInstruction {
+                opcode,
+                mnemonic: "ILLEGAL",
+                instruction_family: GameboyInstructionFamily::ILLEGAL,
+                operand1: None,
+                operand2: None,
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0xFD => GameboyInstruction {
+                opcode,
+                mnemonic: "ILLEGAL",
+                instruction_family: GameboyInstructionFamily::ILLEGAL,
+                operand1: None,
+                operand2: None,
+                condition: None,
+                cycles: 4,
+                size: 1,
+            },
+            0xFE => GameboyInstruction {
+                opcode,
+                mnemonic: "CP n8",
+                instruction_family: GameboyInstructionFamily::CP,
+                operand1: Some(GameboyInstructionOperand::Immediate8(source.fetch())),
+                operand2: None,
+                condition: None,
+                cycles: 8,
+                size: 2,
+            },
+            0xFF => GameboyInstruction {
+                opcode,
+                mnemonic: "RST $38",
+                instruction_family: GameboyInstructionFamily::RST,
+                operand1: Some(GameboyInstructionOperand::Address(0x0038)),
+                operand2: None,
+                condition: None,
+                cycles: 16,
+                size: 1,
+            },
+        }
+    }
+}
+
+/// What to do when the CPU is about to execute an instruction that usually means it went off the rails.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SuspiciousExecutionPolicy {
+    /// Execute the instruction like any other.
+    #[default]
+    Execute,
+    /// Print the instruction to stderr, then execute it.
+    Log,
+    /// Don't execute the instruction, and return the reason from [`Gameboy::step`].
+    Stop,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SuspiciousExecution {
+    /// A NOP, which is what running through zeroed memory looks like.
+    Nop { pc: u16 },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StepOutcome {
+    /// An instruction was executed.
+    Normal,
+    /// The CPU is sleeping after a HALT instruction.
+    Halted,
+    /// The CPU is sleeping after a STOP instruction.
+    Stopped,
+    /// A software breakpoint (`LD B, B`) was executed.
+    Breakpoint,
+    /// No instruction was executed, the CPU jumped to the handler of this interrupt instead.
+    /// `None` if the dispatch got cancelled, in which case the CPU jumped to 0000h.
+    Interrupt(Option<Interrupt>),
+}
+
+/// The hardware being emulated, which decides the state the boot ROM leaves the system in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameboyModel {
+    Dmg,
+    /// Game Boy Pocket.
+    Mgb,
+    Cgb,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct GameboyConfig {
+    pub suspicious_execution: SuspiciousExecutionPolicy,
+    /// Report `LD B, B` as a breakpoint, like BGB and most debugging emulators do.
+    pub software_breakpoints: bool,
+    /// `None` picks the CGB for games that support it, the DMG otherwise.
+    pub model: Option<GameboyModel>,
+}
+
+/// An in-memory copy of the whole system, which [`Gameboy::restore_checkpoint`] restores by only copying back the
+/// memory pages written since.
+#[derive(Debug, Clone)]
+pub struct Checkpoint {
+    id: u64,
+    registers: GameboyRegisters,
+    ime: bool,
+    ime_scheduled: bool,
+    halted: bool,
+    halt_bug: bool,
+    stopped: bool,
+    locked: bool,
+    cycles: u64,
+    bus: Bus,
+}
+
+static NEXT_CHECKPOINT_ID: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Debug)]
+pub struct Gameboy {
+    pub config: GameboyConfig,
+    /// Edits made between steps must be followed by [`Gameboy::invalidate_history`].
+    pub registers: GameboyRegisters,
+    pub bus: Bus,
+    /// Interrupt master enable flag.
+    pub ime: bool,
+    /// Set by EI, IME gets enabled after the next instruction.
+    ime_scheduled: bool,
+    pub halted: bool,
+    /// Set when HALT is executed with IME disabled and an interrupt pending: the next opcode gets read twice.
+    halt_bug: bool,
+    pub stopped: bool,
+    /// Set after executing an illegal opcode, the CPU won't execute anything anymore.
+    pub locked: bool,
+    /// T-cycles elapsed since power on.
+    cycles: u64,
+    /// Where the external RAM gets flushed to by [`Gameboy::flush_save_file`].
+    save_file_path: Option<PathBuf>,
+    /// Buttons to press at every frame, see [`Gameboy::load_input_movie`].
+    input_movie: Option<InputMovie>,
+    /// The checkpoint the dirty pages of the bus are relative to, if they are relative to one.
+    checkpoint_id: Option<u64>,
+    /// Slots indexed by breakpoint id, `None` once removed.
+    breakpoints: Vec<Option<Breakpoint>>,
+    hooks: Hooks,
+    /// Set when any hook was added with [`Gameboy::on_exec`], which is checked before every instruction.
+    exec_hooks: bool,
+    /// Labels of the running ROM, see [`Gameboy::load_symbols`].
+    symbols: SymbolTable,
+    tracer: Option<Tracer>,
+    /// Steps recorded to go back in time, see [`Gameboy::enable_history`].
+    history: Option<History>,
+}
+
+impl Default for Gameboy {
+    fn default() -> Self {
+        Gameboy::new()
+    }
+}
+
+impl InstructionFetch for Gameboy {
+    fn fetch(&mut self) -> u8 {
+        Gameboy::fetch(self)
+    }
+
+    fn fetch16(&mut self) -> u16 {
+        Gameboy::fetch16(self)
+    }
+}
+
+impl Gameboy {
+    pub fn new() -> Self {
+        Gameboy::with_config(GameboyConfig::default())
+    }
+
+    pub fn with_config(config: GameboyConfig) -> Self {
+        Gameboy {
+            config,
+            registers: GameboyRegisters::default(),
+            bus: Bus::default(),
+            ime: false,
+            ime_scheduled: false,
+            halted: false,
+            halt_bug: false,
+            stopped: false,
+            locked: false,
+            cycles: 0,
+            save_file_path: None,
+            input_movie: None,
+            checkpoint_id: None,
+            breakpoints: Vec::new(),
+            hooks: Hooks::default(),
+            exec_hooks: false,
+            symbols: SymbolTable::default(),
+            tracer: None,
+            history: None,
+        }
+    }
+
+    pub fn load_rom(&mut self, rom: Vec<u8>) -> Result<(), Error> {
+        self.load_cartridge(Cartridge::new(rom)?);
+
+        Ok(())
+    }
+
+    /// Inserts a cartridge and powers the system on.
+    pub fn load_cartridge(&mut self, cartridge: Cartridge) {
+        self.bus.cartridge = cartridge;
+        self.reset();
+    }
+
+    /// Maps a DMG or CGB boot ROM at 0000h, which runs on every reset until it unmaps itself through FF50h.
+    pub fn load_boot_rom(&mut self, rom: Vec<u8>) -> Result<(), Error> {
+        if rom.len() != 0x100 && rom.len() != 0x900 {
+            return Err(Error::InvalidBootRomSize(rom.len()));
+        }
+
+        self.bus.boot_rom = rom;
+        self.reset();
+
+        Ok(())
+    }
+
+    fn supports_cgb(&self) -> bool {
+        self.bus.cartridge.header().is_some_and(|header| header.supports_cgb())
+    }
+
+    /// Returns the model being emulated, see [`GameboyConfig::model`].
+    pub fn model(&self) -> GameboyModel {
+        match self.config.model {
+            Some(model) => model,
+            None if self.supports_cgb() => GameboyModel::Cgb,
+            None => GameboyModel::Dmg,
+        }
+    }
+
+    /// Loads a `.sav` file into the external RAM of the cartridge, and remembers it for [`Gameboy::flush_save_file`].
+    /// A missing file is not an error: the RAM is left as is and will be written there on flush.
+    pub fn load_save_file(&mut self, path: &Path) -> Result<(), Error> {
+        if path.exists() {
+            let data = fs::read(path).map_err(Error::IoError)?;
+            self.bus.cartridge.load_save_data(&data)?;
+            // The save isn't part of any checkpoint taken before
+            self.checkpoint_id = None;
+        }
+
+        self.save_file_path = Some(path.to_owned());
+
+        Ok(())
+    }
+
+    /// Writes the external RAM of the cartridge back to the file given to [`Gameboy::load_save_file`].
+    pub fn flush_save_file(&self) -> Result<(), Error> {
+        let path = self.save_file_path.as_ref().ok_or(Error::NoSaveFile)?;
+        fs::write(path, self.bus.cartridge.save_data()).map_err(Error::IoError)
+    }
+
+    /// Returns the last complete frame, as 160x144 RGB triplets.
+    pub fn framebuffer(&self) -> &[u8] {
+        self.bus.ppu.framebuffer()
+    }
+
+    /// Number of frames completed since power on.
+    pub fn frame(&self) -> u64 {
+        self.bus.ppu.frame()
+    }
+
+    /// Runs until `frame` frames have been completed since power on.
+    pub fn run_until_frame(&mut self, frame: u64) -> Result<(), EmuError> {
+        while self.frame() < frame {
+            self.step()?;
+        }
+
+        Ok(())
+    }
+
+    /// Writes the last complete frame to a PNG file.
+    pub fn save_screenshot(&self, path: &Path) -> Result<(), Error> {
+        ppu::write_png(path, self.framebuffer())
+    }
+
+    /// Sets the pressed buttons, one bit per [`crate::joypad::Button`].
+    pub fn set_buttons(&mut self, pressed: u8) {
+        self.bus.set_buttons(pressed);
+    }
+
+    /// Plays `movie` back from now on: the buttons are updated at the start of every frame, from the frame count
+    /// since power on.
+    pub fn load_input_movie(&mut self, movie: InputMovie) {
+        self.bus.set_buttons(movie.buttons_at(self.frame()));
+        self.input_movie = Some(movie);
+    }
+
+    /// Returns the bytes sent over the serial port so far, if they are being captured.
+    pub fn serial_output(&self) -> &[u8] {
+        self.bus.serial.captured()
+    }
+
+    /// Returns the contents of the external RAM, all banks included.
+    pub fn sram(&self) -> &[u8] {
+        self.bus.cartridge.ram()
+    }
+
+    /// Powers the system back on: starts the boot ROM if there is one, otherwise puts the CPU and I/O registers in
+    /// the state the boot ROM of the model would have left them in.
+    pub fn reset(&mut self) {
+        let model = self.model();
+
+        self.bus.reset();
+        self.cycles = 0;
+        self.checkpoint_id = None;
+        self.invalidate_history();
+
+        if self.bus.boot_rom.is_empty() {
+            let cgb_game = model == GameboyModel::Cgb && self.supports_cgb();
+
+            self.bus.set_cgb_mode(cgb_game);
+            self.bus.apply_post_boot_state(model);
+            self.registers = GameboyRegisters::post_boot(model, cgb_game);
+        } else {
+            // The CGB boot ROM starts in CGB mode, and falls back to DMG mode itself for DMG games
+            self.bus.set_cgb_mode(model == GameboyModel::Cgb);
+            self.bus.map_boot_rom();
+            self.registers = GameboyRegisters {
+                af: 0x0000,
+                bc: 0x0000,
+                de: 0x0000,
+                hl: 0x0000,
+                pc: 0x0000,
+                sp: 0x0000,
+            };
+        }
+
+        self.ime = false;
+        self.ime_scheduled = false;
+        self.halted = false;
+        self.halt_bug = false;
+        self.stopped = false;
+        self.locked = false;
+    }
+
+    /// Saves the whole state of the system: CPU, memory, I/O and every component on the bus.
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot::new(|writer| {
+            for register in [
+                self.registers.af,
+                self.registers.bc,
+                self.registers.de,
+                self.registers.hl,
+                self.registers.pc,
+                self.registers.sp,
+            ] {
+                writer.u16(register);
+            }
+
+            for flag in [self.ime, self.ime_scheduled, self.halted, self.halt_bug, self.stopped, self.locked] {
+                writer.bool(flag);
+            }
+
+            writer.u64(self.cycles);
+            self.bus.save_state(writer);
+        })
+    }
+
+    /// Restores a state saved by [`Gameboy::snapshot`], with the same cartridge inserted.
+    /// The system is left in an unspecified state if the snapshot is corrupted.
+    pub fn restore(&mut self, snapshot: &Snapshot) -> Result<(), Error> {
+        self.invalidate_history();
+        self.load_snapshot(snapshot)
+    }
+
+    /// Restores a snapshot without touching the history.
+    fn load_snapshot(&mut self, snapshot: &Snapshot) -> Result<(), Error> {
+        let mut reader = snapshot.reader()?;
+
+        for register in [
+            &mut self.registers.af,
+            &mut self.registers.bc,
+            &mut self.registers.de,
+            &mut self.registers.hl,
+            &mut self.registers.pc,
+            &mut self.registers.sp,
+        ] {
+            *register = reader.u16()?;
+        }
+
+        for flag in [
+            &mut self.ime,
+            &mut self.ime_scheduled,
+            &mut self.halted,
+            &mut self.halt_bug,
+            &mut self.stopped,
+            &mut self.locked,
+        ] {
+            *flag = reader.bool()?;
+        }
+
+        self.cycles = reader.u64()?;
+        self.checkpoint_id = None;
+        self.bus.load_state(&mut reader)
+    }
+
+    /// Copies the whole system in memory, and starts tracking the memory pages written from now on.
+    pub fn checkpoint(&mut self) -> Checkpoint {
+        let id = NEXT_CHECKPOINT_ID.fetch_add(1, Ordering::Relaxed);
+
+        self.bus.clear_dirty_pages();
+        self.checkpoint_id = Some(id);
+
+        Checkpoint {
+            id,
+            registers: self.registers,
+            ime: self.ime,
+            ime_scheduled: self.ime_scheduled,
+            halted: self.halted,
+            halt_bug: self.halt_bug,
+            stopped: self.stopped,
+            locked: self.locked,
+            cycles: self.cycles,
+            bus: self.bus.clone(),
+        }
+    }
+
+    /// Goes back to a checkpoint taken with the same cartridge. When it is the last checkpoint taken or restored, only
+    /// the memory pages written since are copied back, which is much faster than [`Gameboy::restore`].
+    pub fn restore_checkpoint(&mut self, checkpoint: &Checkpoint) {
+        let full = self.checkpoint_id != Some(checkpoint.id);
+        self.invalidate_history();
+
+        self.registers = checkpoint.registers;
+        self.ime = checkpoint.ime;
+        self.ime_scheduled = checkpoint.ime_scheduled;
+        self.halted = checkpoint.halted;
+        self.halt_bug = checkpoint.halt_bug;
+        self.stopped = checkpoint.stopped;
+        self.locked = checkpoint.locked;
+        self.cycles = checkpoint.cycles;
+        self.bus.restore_from(&checkpoint.bus, full);
+        self.checkpoint_id = Some(checkpoint.id);
+    }
+
+    /// Returns the number of T-cycles elapsed since power on.
+    pub fn cycles(&self) -> u64 {
+        self.cycles
+    }
+
+    /// Lets `cycles` T-cycles pass for everything that isn't the CPU.
+    fn tick(&mut self, cycles: u32) {
+        let frame = self.frame();
+
+        self.cycles += cycles as u64;
+        self.bus.tick(cycles);
+
+        if self.frame() != frame {
+            if let Some(movie) = &self.input_movie {
+                let pressed = movie.buttons_at(self.frame());
+                self.bus.set_buttons(pressed);
+            }
+        }
+    }
+
+    pub fn fetch(&mut self) -> u8 {
+        let opcode = self.bus.fetch(self.registers.pc);
+        self.registers.pc = self.registers.pc.wrapping_add(1);
+
+        opcode
+    }
+
+    pub fn fetch16(&mut self) -> u16 {
+        let low = self.fetch() as u16;
+        let high = self.fetch() as u16;
+
+        (high << 8) | low
+    }
+
+    pub fn read_byte(&self, address: u16) -> u8 {
+        self.bus.read(address)
+    }
+
+    /// Writes a byte from outside of the emulated program, which clears the history.
+    pub fn write_byte(&mut self, address: u16, byte: u8) {
+        self.invalidate_history();
+        self.bus.write(address, byte);
+    }
+
+    /// Writes `bytes` starting at `address`, wrapping around to 0000h after FFFFh. Clears the history like
+    /// [`Gameboy::write_byte`].
+    pub fn write_bytes(&mut self, address: u16, bytes: &[u8]) {
+        self.invalidate_history();
+
+        for (i, &byte) in bytes.iter().enumerate() {
+            self.bus.write(address.wrapping_add(i as u16), byte);
+        }
+    }
+
+    /// Reads `length` bytes starting at `address`, wrapping around to 0000h after FFFFh.
+    pub fn read_bytes(&mut self, address: u16, length: u16) -> Vec<u8> {
+        (0..length).map(|i| self.read_byte(address.wrapping_add(i))).collect()
+    }
+
+    fn read_word(&self, address: u16) -> u16 {
+        let low = self.read_byte(address) as u16;
+        let high = self.read_byte(address.wrapping_add(1)) as u16;
+
+        (high << 8) | low
+    }
+
+    fn write_word(&mut self, address: u16, value: u16) {
+        self.bus.write(address, value as u8);
+        self.bus.write(address.wrapping_add(1), (value >> 8) as u8);
+    }
+
+    fn push(&mut self, value: u16) {
+        self.registers.sp = self.registers.sp.wrapping_sub(2);
+        self.write_word(self.registers.sp, value);
+    }
+
+    fn pop(&mut self) -> u16 {
+        let value = self.read_word(self.registers.sp);
+        self.registers.sp = self.registers.sp.wrapping_add(2);
+
+        value
+    }
+
+    pub fn decode(&mut self, opcode: u8) -> GameboyInstruction {
+        GameboyInstruction::decode(self, opcode)
+    }
+
+    fn check_condition(&self, condition: Option<GameboyInstructionCondition>) -> bool {
+        match condition {
+            Some(GameboyInstructionCondition::NZ) => !self.registers.get_flag(GameboyRegisterFlags::Z),
+            Some(GameboyInstructionCondition::Z) => self.registers.get_flag(GameboyRegisterFlags::Z),
+            Some(GameboyInstructionCondition::NC) => !self.registers.get_flag(GameboyRegisterFlags::C),
+            Some(GameboyInstructionCondition::C) => self.registers.get_flag(GameboyRegisterFlags::C),
+            None => true,
+        }
+    }
+
+    fn apply_pointer_op(&mut self, register: &GameboyNamedRegister16, address: u16, pointer_op: Option<GameboyInstructionPointerOp>) {
+        if let Some(operation) = pointer_op {
+            match operation {
+                GameboyInstructionPointerOp::Increment => {
+                    self.registers.set_reg16(register, address.wrapping_add(1));
+                },
+                GameboyInstructionPointerOp::Decrement => {
+                    self.registers.set_reg16(register, address.wrapping_sub(1));
+                },
+            }
+        }
+    }
+
+    fn read_operand8(&mut self, operand: &GameboyInstructionOperand) -> Result<u8, EmuErrorKind> {
+        let value = match *operand {
+            GameboyInstructionOperand::Register8(register) => self.registers.get_reg8(&register),
+            GameboyInstructionOperand::Immediate8(value) => value,
+            GameboyInstructionOperand::Pointer(register, pointer_op) => {
+                let address = self.registers.get_reg16(&register);
+                let value = self.read_byte(address);
+                self.apply_pointer_op(&register, address, pointer_op);
+
+                value
+            },
+            GameboyInstructionOperand::AddressPointer(address) => self.read_byte(address),
+            GameboyInstructionOperand::HighPointer(register) => {
+                self.read_byte(0xFF00 | self.registers.get_reg8(&register) as u16)
+            },
+            _ => return Err(EmuErrorKind::InvalidOperand(*operand)),
+        };
+
+        Ok(value)
+    }
+
+    fn write_operand8(&mut self, operand: &GameboyInstructionOperand, value: u8) -> Result<(), EmuErrorKind> {
+        match *operand {
+            GameboyInstructionOperand::Register8(register) => self.registers.set_reg8(&register, value),
+            GameboyInstructionOperand::Pointer(register, pointer_op) => {
+                let address = self.registers.get_reg16(&register);
+                self.bus.write(address, value);
+                self.apply_pointer_op(&register, address, pointer_op);
+            },
+            GameboyInstructionOperand::AddressPointer(address) => self.bus.write(address, value),
+            GameboyInstructionOperand::HighPointer(register) => {
+                self.bus.write(0xFF00 | self.registers.get_reg8(&register) as u16, value)
+            },
+            _ => return Err(EmuErrorKind::InvalidOperand(*operand)),
+        }
+
+        Ok(())
+    }
+
+    fn set_flags(&mut self, z: bool, n: bool, h: bool, c: bool) {
+        self.registers.set_flag(GameboyRegisterFlags::Z, z);
+        self.registers.set_flag(GameboyRegisterFlags::N, n);
+        self.registers.set_flag(GameboyRegisterFlags::H, h);
+        self.registers.set_flag(GameboyRegisterFlags::C, c);
+    }
+
+    /// Adds `value` (and the carry flag if `with_carry` is set) to A, and updates the flags.
+    fn alu_add(&mut self, value: u8, with_carry: bool) {
+        let a = self.registers.get_reg8(&GameboyNamedRegister8::A);
+        let carry = (with_carry && self.registers.get_flag(GameboyRegisterFlags::C)) as u8;
+        let result = a.wrapping_add(value).wrapping_add(carry);
+
+        self.set_flags(
+            result == 0,
+            false,
+            (a & 0x0F) + (value & 0x0F) + carry > 0x0F,
+            a as u16 + value as u16 + carry as u16 > 0xFF,
+        );
+
+        self.registers.set_reg8(&GameboyNamedRegister8::A, result);
+    }
+
+    /// Subtracts `value` (and the carry flag if `with_carry` is set) from A, and updates the flags.
+    /// The result is only stored back into A if `store` is set, which is what CP relies on.
+    fn alu_sub(&mut self, value: u8, with_carry: bool, store: bool) {
+        let a = self.registers.get_reg8(&GameboyNamedRegister8::A);
+        let carry = (with_carry && self.registers.get_flag(GameboyRegisterFlags::C)) as u8;
+        let result = a.wrapping_sub(value).wrapping_sub(carry);
+
+        self.set_flags(
+            result == 0,
+            true,
+            (a & 0x0F) < (value & 0x0F) + carry,
+            (a as u16) < value as u16 + carry as u16,
+        );
+
+        if store {
+            self.registers.set_reg8(&GameboyNamedRegister8::A, result);
+        }
+    }
+
+    /// Computes SP + e8 and sets the flags the way `ADD SP, e8` and `LD HL, SP+e8` do.
+    fn add_sp_offset(&mut self, offset: i8) -> u16 {
+        let sp = self.registers.sp;
+        let value = offset as u8 as u16;
+
+        self.set_flags(
+            false,
+            false,
+            (sp & 0x000F) + (value & 0x000F) > 0x000F,
+            (sp & 0x00FF) + (value & 0x00FF) > 0x00FF,
+        );
+
+        sp.wrapping_add(offset as i16 as u16)
+    }
+
+    /// Executes one of the CB-prefixed shift/rotate operations on `value`, and updates the flags.
+    fn shift_rotate(&mut self, family: GameboyInstructionFamily, value: u8) -> Result<u8, EmuErrorKind> {
+        let carry_in = self.registers.get_flag(GameboyRegisterFlags::C) as u8;
+
+        let (result, carry_out) = match family {
+            GameboyInstructionFamily::RLC => (value.rotate_left(1), value & 0x80 != 0),
+            GameboyInstructionFamily::RRC => (value.rotate_right(1), value & 0x01 != 0),
+            GameboyInstructionFamily::RL => ((value << 1) | carry_in, value & 0x80 != 0),
+            GameboyInstructionFamily::RR => ((value >> 1) | (carry_in << 7), value & 0x01 != 0),
+            GameboyInstructionFamily::SLA => (value << 1, value & 0x80 != 0),
+            GameboyInstructionFamily::SRA => ((value >> 1) | (value & 0x80), value & 0x01 != 0),
+            GameboyInstructionFamily::SRL => (value >> 1, value & 0x01 != 0),
+            GameboyInstructionFamily::SWAP => (value.rotate_left(4), false),
+            _ => return Err(EmuErrorKind::InvalidOperands(family)),
+        };
+
+        self.set_flags(result == 0, false, false, carry_out);
+
+        Ok(result)
+    }
+
+    /// Executes an instruction, returns how many T-cycles it took.
+    pub fn execute(&mut self, instruction: GameboyInstruction) -> Result<u8, EmuErrorKind> {
+        if !self.check_condition(instruction.condition) {
+            return Ok(instruction.cycles);
+        }
+
+        // The cycles in the instruction are for the branch not being taken
+        let cycles = match (instruction.condition, instruction.instruction_family) {
+            (Some(_), GameboyInstructionFamily::JR | GameboyInstructionFamily::JP) => instruction.cycles + 4,
+            (Some(_), GameboyInstructionFamily::CALL | GameboyInstructionFamily::RET) => instruction.cycles + 12,
+            _ => instruction.cycles,
+        };
+
+        match instruction.instruction_family {
+            GameboyInstructionFamily::NOP => {
+                // NOP
+            },
+            GameboyInstructionFamily::JP => {
+                match instruction.operand1 {
+                    Some(GameboyInstructionOperand::Address(address)) => {
+                        // JP n16
+                        self.registers.pc = address;
+                    },
+                    Some(GameboyInstructionOperand::Register16(register)) => {
+                        // JP r16
+                        let address = self.registers.get_reg16(&register);
+                        self.registers.pc = address;
+                    },
+                    _ => return Err(EmuErrorKind::InvalidOperands(GameboyInstructionFamily::JP)),
+                }
+            },
+            GameboyInstructionFamily::JR => {
+                match instruction.operand1 {
+                    Some(GameboyInstructionOperand::ImmediateSigned8(offset)) => {
+                        // JR e8
+                        self.registers.pc = self.registers.pc.wrapping_add(offset as i16 as u16);
+                    },
+                    _ => return Err(EmuErrorKind::InvalidOperands(GameboyInstructionFamily::JR)),
+                }
+            },
+            GameboyInstructionFamily::CALL => {
+                match instruction.operand1 {
+                    Some(GameboyInstructionOperand::Address(address)) => {
+                        // CALL n16
+                        self.push(self.registers.pc);
+                        self.registers.pc = address;
+                    },
+                    _ => return Err(EmuErrorKind::InvalidOperands(GameboyInstructionFamily::CALL)),
+                }
+            },
+            GameboyInstructionFamily::RST => {
+                match instruction.operand1 {
+                    Some(GameboyInstructionOperand::Address(address)) => {
+                        // RST vec
+                        self.push(self.registers.pc);
+                        self.registers.pc = address;
+                    },
+                    _ => return Err(EmuErrorKind::InvalidOperands(GameboyInstructionFamily::RST)),
+                }
+            },
+            GameboyInstructionFamily::RET => {
+                // RET
+                self.registers.pc = self.pop();
+            },
+            GameboyInstructionFamily::RETI => {
+                // RETI
+                // Unlike EI, interrupts are enabled right away
+                self.registers.pc = self.pop();
+                self.ime = true;
+            },
+            GameboyInstructionFamily::DI => {
+                // DI
+                self.ime = false;
+                self.ime_scheduled = false;
+            },
+            GameboyInstructionFamily::EI => {
+                // EI
+                self.ime_scheduled = true;
+            },
+            GameboyInstructionFamily::HALT => {
+                // HALT
+                if !self.ime && self.bus.pending_interrupts() != 0 {
+                    // HALT bug: the CPU doesn't halt, and fails to increment PC after the next opcode
+                    self.halt_bug = true;
+                } else {
+                    self.halted = true;
+                }
+            },
+            GameboyInstructionFamily::STOP => {
+                // STOP
+                // The byte following STOP is skipped, and DIV gets reset
+                self.registers.pc = self.registers.pc.wrapping_add(1);
+                self.bus.write(0xFF04, 0x00);
+
+                if self.bus.speed_switch_armed {
+                    // On the CGB, STOP is how the speed switch armed through KEY1 is performed
+                    self.bus.speed_switch_armed = false;
+                    self.bus.double_speed = !self.bus.double_speed;
+                } else {
+                    self.stopped = true;
+                }
+            },
+            GameboyInstructionFamily::ILLEGAL => {
+                // Illegal opcode: the CPU hangs, leave PC on the faulting instruction
+                self.registers.pc = self.registers.pc.wrapping_sub(1);
+                self.locked = true;
+            },
+            GameboyInstructionFamily::LD => {
+                match (instruction.operand1, instruction.operand2) {
+                    (Some(GameboyInstructionOperand::Register16(register)), Some(GameboyInstructionOperand::Immediate16(value))) => {
+                        // LD r16, n16
+                        self.registers.set_reg16(&register, value);
+                    },
+                    (Some(GameboyInstructionOperand::Register16(register)), Some(GameboyInstructionOperand::Register16(register2))) => {
+                        // LD SP, HL
+                        let value = self.registers.get_reg16(&register2);
+                        self.registers.set_reg16(&register, value);
+                    },
+                    (Some(GameboyInstructionOperand::Register16(register)), Some(GameboyInstructionOperand::StackPointerOffset(offset))) => {
+                        // LD HL, SP+e8
+                        let value = self.add_sp_offset(offset);
+                        self.registers.set_reg16(&register, value);
+                    },
+                    (Some(GameboyInstructionOperand::AddressPointer(address)), Some(GameboyInstructionOperand::Register16(register))) => {
+                        // LD (n16), SP
+                        let value = self.registers.get_reg16(&register);
+                        self.write_word(address, value);
+                    },
+                    (Some(destination), Some(source)) => {
+                        // LD r8, r8 / LD r8, n8 / LD r8, (r16) / LD (r16), r8 / LD (n16), A / LD A, (n16)
+                        let value = self.read_operand8(&source)?;
+                        self.write_operand8(&destination, value)?;
+                    },
+                    _ => return Err(EmuErrorKind::InvalidOperands(GameboyInstructionFamily::LD)),
+                }
+            },
+            GameboyInstructionFamily::LDH => {
+                match (instruction.operand1, instruction.operand2) {
+                    (Some(GameboyInstructionOperand::Immediate8(offset)), Some(GameboyInstructionOperand::Register8(register))) => {
+                        // LDH (n8), A
+                        self.bus.write(0xFF00 | offset as u16, self.registers.get_reg8(&register));
+                    },
+                    (Some(GameboyInstructionOperand::Register8(register)), Some(GameboyInstructionOperand::Immediate8(offset))) => {
+                        // LDH A, (n8)
+                        let value = self.read_byte(0xFF00 | offset as u16);
+                        self.registers.set_reg8(&register, value);
+                    },
+                    (Some(destination @ GameboyInstructionOperand::HighPointer(_)), Some(source @ GameboyInstructionOperand::Register8(_)))
+                    | (Some(destination @ GameboyInstructionOperand::Register8(_)), Some(source @ GameboyInstructionOperand::HighPointer(_))) => {
+                        // LDH (C), A / LDH A, (C)
+                        let value = self.read_operand8(&source)?;
+                        self.write_operand8(&destination, value)?;
+                    },
+                    _ => return Err(EmuErrorKind::InvalidOperands(GameboyInstructionFamily::LDH)),
+                }
+            },
+            GameboyInstructionFamily::INC => {
+                match instruction.operand1 {
+                    Some(GameboyInstructionOperand::Register16(register)) => {
+                        // INC r16
+                        let value = self.registers.get_reg16(&register);
+                        self.registers.set_reg16(&register, value.wrapping_add(1));
+                    },
+                    Some(operand) => {
+                        // INC r8 / INC (HL)
+                        let value = self.read_operand8(&operand)?;
+                        let result = value.wrapping_add(1);
+                        self.write_operand8(&operand, result)?;
+
+                        self.registers.set_flag(GameboyRegisterFlags::Z, result == 0);
+                        self.registers.set_flag(GameboyRegisterFlags::N, false);
+                        self.registers.set_flag(GameboyRegisterFlags::H, (value & 0x0F) == 0x0F);
+                    },
+                    _ => return Err(EmuErrorKind::InvalidOperands(GameboyInstructionFamily::INC)),
+                }
             },
-            GameboyInstructionFamily::LD => {
+            GameboyInstructionFamily::DEC => {
                 match instruction.operand1 {
-                    Some(GameboyInstructionOperand::Register8(register)) => {
-                        match instruction.operand2 {
-                            Some(GameboyInstructionOperand::Immediate8(value)) => {
-                                // LD r8, n8
-                                self.registers.set_reg8(&register, value);
-                            },
-                            Some(GameboyInstructionOperand::Register8(register2)) => {
-                                // LD r8, r8
-                                let value = self.registers.get_reg8(&register2);
-                                self.registers.set_reg8(&register, value);
-                            },
-                            Some(GameboyInstructionOperand::Pointer(register2, pointer_op)) => {
-                                // LD r8, (r16)
-                                let address = self.registers.get_reg16(&register2);
-                                let value = self.memory[address as usize];
-                                self.registers.set_reg8(&register, value);
-
-                                if let Some(operation) = pointer_op {
-                                    match operation {
-                                        GameboyInstructionPointerOp::Increment => {
-                                            self.registers.set_reg16(&register2, address + 1);
-                                        },
-                                        GameboyInstructionPointerOp::Decrement => {
-                                            self.registers.set_reg16(&register2, address - 1);
-                                        },
-                                    }
-                                }
-                            },
-                            _ => panic!("Invalid operand for LD instruction"),
-                        }
+                    Some(GameboyInstructionOperand::Register16(register)) => {
+                        // DEC r16
+                        let value = self.registers.get_reg16(&register);
+                        self.registers.set_reg16(&register, value.wrapping_sub(1));
                     },
-                    Some(GameboyInstructionOperand::Pointer(register, pointer_op)) => {
-                        match instruction.operand2 {
-                            Some(GameboyInstructionOperand::Register8(register2)) => {
-                                // LD (r16), r8
-                                let address = self.registers.get_reg16(&register);
-                                let value = self.registers.get_reg8(&register2);
-                                self.memory[address as usize] = value;
-
-                                if let Some(operation) = pointer_op {
-                                    match operation {
-                                        GameboyInstructionPointerOp::Increment => {
-                                            self.registers.set_reg16(&register, address + 1);
-                                        },
-                                        GameboyInstructionPointerOp::Decrement => {
-                                            self.registers.set_reg16(&register, address - 1);
-                                        },
-                                    }
-                                }
-                            },
-                            Some(GameboyInstructionOperand::Register16(register2)) => {
-                                // LD (r16), r16
-                                let address = self.registers.get_reg16(&register);
-                                let value = self.registers.get_reg16(&register2);
-                                self.memory[address as usize] = (value >> 8) as u8;
-                                self.memory[address as usize + 1] = value as u8;
-
-                                if let Some(operation) = pointer_op {
-                                    match operation {
-                                        GameboyInstructionPointerOp::Increment => {
-                                            self.registers.set_reg16(&register, address + 2);
-                                        },
-                                        GameboyInstructionPointerOp::Decrement => {
-                                            self.registers.set_reg16(&register, address - 2);
-                                        },
-                                    }
-                                }
-                            },
-                            _ => panic!("Invalid operand for LD instruction"),
-                        }
-                    }
+                    Some(operand) => {
+                        // DEC r8 / DEC (HL)
+                        let value = self.read_operand8(&operand)?;
+                        let result = value.wrapping_sub(1);
+                        self.write_operand8(&operand, result)?;
+
+                        self.registers.set_flag(GameboyRegisterFlags::Z, result == 0);
+                        self.registers.set_flag(GameboyRegisterFlags::N, true);
+                        self.registers.set_flag(GameboyRegisterFlags::H, (value & 0x0F) == 0);
+                    },
+                    _ => return Err(EmuErrorKind::InvalidOperands(GameboyInstructionFamily::DEC)),
+                }
+            },
+            GameboyInstructionFamily::ADD => {
+                match (instruction.operand1, instruction.operand2) {
+                    (Some(GameboyInstructionOperand::Register16(GameboyNamedRegister16::SP)), Some(GameboyInstructionOperand::ImmediateSigned8(offset))) => {
+                        // ADD SP, e8
+                        self.registers.sp = self.add_sp_offset(offset);
+                    },
+                    (Some(GameboyInstructionOperand::Register16(register)), Some(GameboyInstructionOperand::Register16(register2))) => {
+                        // ADD HL, r16
+                        let value1 = self.registers.get_reg16(&register);
+                        let value2 = self.registers.get_reg16(&register2);
+                        let result = value1.wrapping_add(value2);
+
+                        self.registers.set_flag(GameboyRegisterFlags::N, false);
+                        self.registers.set_flag(GameboyRegisterFlags::H, (value1 & 0x0FFF) + (value2 & 0x0FFF) > 0x0FFF);
+                        self.registers.set_flag(GameboyRegisterFlags::C, value1 > 0xFFFF - value2);
+
+                        self.registers.set_reg16(&register, result);
+                    },
+                    (Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)), Some(source)) => {
+                        // ADD A, r8 / ADD A, (HL) / ADD A, n8
+                        let value = self.read_operand8(&source)?;
+                        self.alu_add(value, false);
+                    },
+                    _ => return Err(EmuErrorKind::InvalidOperands(GameboyInstructionFamily::ADD)),
+                }
+            },
+            GameboyInstructionFamily::ADC => {
+                match (instruction.operand1, instruction.operand2) {
+                    (Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)), Some(source)) => {
+                        // ADC A, r8 / ADC A, (HL) / ADC A, n8
+                        let value = self.read_operand8(&source)?;
+                        self.alu_add(value, true);
+                    },
+                    _ => return Err(EmuErrorKind::InvalidOperands(GameboyInstructionFamily::ADC)),
+                }
+            },
+            GameboyInstructionFamily::SUB => {
+                match (instruction.operand1, instruction.operand2) {
+                    (Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)), Some(source)) => {
+                        // SUB A, r8 / SUB A, (HL) / SUB A, n8
+                        let value = self.read_operand8(&source)?;
+                        self.alu_sub(value, false, true);
+                    },
+                    _ => return Err(EmuErrorKind::InvalidOperands(GameboyInstructionFamily::SUB)),
+                }
+            },
+            GameboyInstructionFamily::SBC => {
+                match (instruction.operand1, instruction.operand2) {
+                    (Some(GameboyInstructionOperand::Register8(GameboyNamedRegister8::A)), Some(source)) => {
+                        // SBC A, r8 / SBC A, (HL) / SBC A, n8
+                        let value = self.read_operand8(&source)?;
+                        self.alu_sub(value, true, true);
+                    },
+                    _ => return Err(EmuErrorKind::InvalidOperands(GameboyInstructionFamily::SBC)),
+                }
+            },
+            GameboyInstructionFamily::CP => {
+                match instruction.operand1 {
+                    Some(source) => {
+                        // CP r8 / CP (HL) / CP n8
+                        let value = self.read_operand8(&source)?;
+                        self.alu_sub(value, false, false);
+                    },
+                    _ => return Err(EmuErrorKind::InvalidOperands(GameboyInstructionFamily::CP)),
+                }
+            },
+            GameboyInstructionFamily::AND => {
+                match instruction.operand1 {
+                    Some(source) => {
+                        // AND A, r8
+                        let value = self.read_operand8(&source)?;
+                        let result = self.registers.get_reg8(&GameboyNamedRegister8::A) & value;
+                        self.registers.set_reg8(&GameboyNamedRegister8::A, result);
+
+                        self.set_flags(result == 0, false, true, false);
+                    },
+                    _ => return Err(EmuErrorKind::InvalidOperands(GameboyInstructionFamily::AND)),
+                }
+            },
+            GameboyInstructionFamily::XOR => {
+                match instruction.operand1 {
+                    Some(source) => {
+                        // XOR A, r8
+                        let value = self.read_operand8(&source)?;
+                        let result = self.registers.get_reg8(&GameboyNamedRegister8::A) ^ value;
+                        self.registers.set_reg8(&GameboyNamedRegister8::A, result);
+
+                        self.set_flags(result == 0, false, false, false);
+                    },
+                    _ => return Err(EmuErrorKind::InvalidOperands(GameboyInstructionFamily::XOR)),
+                }
+            },
+            GameboyInstructionFamily::OR => {
+                match instruction.operand1 {
+                    Some(source) => {
+                        // OR A, r8
+                        let value = self.read_operand8(&source)?;
+                        let result = self.registers.get_reg8(&GameboyNamedRegister8::A) | value;
+                        self.registers.set_reg8(&GameboyNamedRegister8::A, result);
+
+                        self.set_flags(result == 0, false, false, false);
+                    },
+                    _ => return Err(EmuErrorKind::InvalidOperands(GameboyInstructionFamily::OR)),
+                }
+            },
+            GameboyInstructionFamily::PUSH => {
+                match instruction.operand1 {
+                    Some(GameboyInstructionOperand::Register16(register)) => {
+                        // PUSH r16
+                        let value = self.registers.get_reg16(&register);
+                        self.push(value);
+                    },
+                    _ => return Err(EmuErrorKind::InvalidOperands(GameboyInstructionFamily::PUSH)),
+                }
+            },
+            GameboyInstructionFamily::POP => {
+                match instruction.operand1 {
                     Some(GameboyInstructionOperand::Register16(register)) => {
-                        match instruction.operand2 {
-                            Some(GameboyInstructionOperand::Immediate16(value)) => {
-                                // LD r16, n16
-                                self.registers.set_reg16(&register, value);
-                            },
-                            Some(GameboyInstructionOperand::Pointer(register2, pointer_op)) => {
-                                // LD r16, (r16)
-                                let address = self.registers.get_reg16(&register2);
-                                let value = (self.memory[address as usize + 1] as u16) << 8 | self.memory[address as usize] as u16;
-                                self.registers.set_reg16(&register, value);
-
-                                if let Some(operation) = pointer_op {
-                                    match operation {
-                                        GameboyInstructionPointerOp::Increment => {
-                                            self.registers.set_reg16(&register2, address + 2);
-                                        },
-                                        GameboyInstructionPointerOp::Decrement => {
-                                            self.registers.set_reg16(&register2, address - 2);
-                                        },
-                                    }
-                                }
-                            }
-                            _ => panic!("Invalid operand for LD instruction"),
-                        }
+                        // POP r16
+                        let value = self.pop();
+                        self.registers.set_reg16(&register, value);
                     },
-                    _ => panic!("Invalid operand for LD instruction"),
+                    _ => return Err(EmuErrorKind::InvalidOperands(GameboyInstructionFamily::POP)),
                 }
             },
-            GameboyInstructionFamily::DEC => {
+            GameboyInstructionFamily::SCF => {
+                // SCF
+                self.registers.set_flag(GameboyRegisterFlags::N, false);
+                self.registers.set_flag(GameboyRegisterFlags::H, false);
+                self.registers.set_flag(GameboyRegisterFlags::C, true);
+            },
+            GameboyInstructionFamily::CCF => {
+                // CCF
+                let carry = self.registers.get_flag(GameboyRegisterFlags::C);
+
+                self.registers.set_flag(GameboyRegisterFlags::N, false);
+                self.registers.set_flag(GameboyRegisterFlags::H, false);
+                self.registers.set_flag(GameboyRegisterFlags::C, !carry);
+            },
+            GameboyInstructionFamily::CPL => {
+                // CPL
+                let a = self.registers.get_reg8(&GameboyNamedRegister8::A);
+                self.registers.set_reg8(&GameboyNamedRegister8::A, !a);
+
+                self.registers.set_flag(GameboyRegisterFlags::N, true);
+                self.registers.set_flag(GameboyRegisterFlags::H, true);
+            },
+            GameboyInstructionFamily::DAA => {
+                // DAA
+                let mut a = self.registers.get_reg8(&GameboyNamedRegister8::A);
+                let mut carry = self.registers.get_flag(GameboyRegisterFlags::C);
+                let half_carry = self.registers.get_flag(GameboyRegisterFlags::H);
+
+                if self.registers.get_flag(GameboyRegisterFlags::N) {
+                    // Last operation was a subtraction
+                    if carry {
+                        a = a.wrapping_sub(0x60);
+                    }
+
+                    if half_carry {
+                        a = a.wrapping_sub(0x06);
+                    }
+                } else {
+                    // Last operation was an addition
+                    if carry || a > 0x99 {
+                        a = a.wrapping_add(0x60);
+                        carry = true;
+                    }
+
+                    if half_carry || (a & 0x0F) > 0x09 {
+                        a = a.wrapping_add(0x06);
+                    }
+                }
+
+                self.registers.set_reg8(&GameboyNamedRegister8::A, a);
+
+                self.registers.set_flag(GameboyRegisterFlags::Z, a == 0);
+                self.registers.set_flag(GameboyRegisterFlags::H, false);
+                self.registers.set_flag(GameboyRegisterFlags::C, carry);
+            },
+            GameboyInstructionFamily::RLCA
+            | GameboyInstructionFamily::RRCA
+            | GameboyInstructionFamily::RLA
+            | GameboyInstructionFamily::RRA => {
+                // RLCA / RRCA / RLA / RRA
+                // Same as their CB-prefixed counterparts, except that Z is always cleared
+                let family = match instruction.instruction_family {
+                    GameboyInstructionFamily::RLCA => GameboyInstructionFamily::RLC,
+                    GameboyInstructionFamily::RRCA => GameboyInstructionFamily::RRC,
+                    GameboyInstructionFamily::RLA => GameboyInstructionFamily::RL,
+                    _ => GameboyInstructionFamily::RR,
+                };
+
+                let a = self.registers.get_reg8(&GameboyNamedRegister8::A);
+                let result = self.shift_rotate(family, a)?;
+                self.registers.set_reg8(&GameboyNamedRegister8::A, result);
+
+                self.registers.set_flag(GameboyRegisterFlags::Z, false);
+            },
+            GameboyInstructionFamily::RLC
+            | GameboyInstructionFamily::RRC
+            | GameboyInstructionFamily::RL
+            | GameboyInstructionFamily::RR
+            | GameboyInstructionFamily::SLA
+            | GameboyInstructionFamily::SRA
+            | GameboyInstructionFamily::SRL
+            | GameboyInstructionFamily::SWAP => {
                 match instruction.operand1 {
-                    Some(GameboyInstructionOperand::Register8(register)) => {
-                        // DEC r8
-                        let value = self.registers.get_reg8(&register);
-                        self.registers.set_reg8(&register, value.wrapping_sub(1));
+                    Some(operand) => {
+                        // RLC r8 / RRC r8 / RL r8 / RR r8 / SLA r8 / SRA r8 / SRL r8 / SWAP r8
+                        let value = self.read_operand8(&operand)?;
+                        let result = self.shift_rotate(instruction.instruction_family, value)?;
+                        self.write_operand8(&operand, result)?;
+                    },
+                    _ => return Err(EmuErrorKind::InvalidOperands(instruction.instruction_family)),
+                }
+            },
+            GameboyInstructionFamily::BIT => {
+                match (instruction.operand1, instruction.operand2) {
+                    (Some(GameboyInstructionOperand::Immediate8(bit)), Some(operand)) => {
+                        // BIT n, r8
+                        let value = self.read_operand8(&operand)?;
+                        let result = value & (1 << bit) != 0;
+
+                        self.registers.set_flag(GameboyRegisterFlags::Z, !result);
+                        self.registers.set_flag(GameboyRegisterFlags::N, false);
+                        self.registers.set_flag(GameboyRegisterFlags::H, true);
+                    },
+                    _ => return Err(EmuErrorKind::InvalidOperands(GameboyInstructionFamily::BIT)),
+                }
+            },
+            GameboyInstructionFamily::RES => {
+                match (instruction.operand1, instruction.operand2) {
+                    (Some(GameboyInstructionOperand::Immediate8(bit)), Some(operand)) => {
+                        // RES n, r8
+                        let value = self.read_operand8(&operand)?;
+                        self.write_operand8(&operand, value & !(1 << bit))?;
+                    },
+                    _ => return Err(EmuErrorKind::InvalidOperands(GameboyInstructionFamily::RES)),
+                }
+            },
+            GameboyInstructionFamily::SET => {
+                match (instruction.operand1, instruction.operand2) {
+                    (Some(GameboyInstructionOperand::Immediate8(bit)), Some(operand)) => {
+                        // SET n, r8
+                        let value = self.read_operand8(&operand)?;
+                        self.write_operand8(&operand, value | (1 << bit))?;
+                    },
+                    _ => return Err(EmuErrorKind::InvalidOperands(GameboyInstructionFamily::SET)),
+                }
+            },
+        }
+
+        Ok(cycles)
+    }
+
+    fn check_suspicious(&self, pc: u16, instruction: &GameboyInstruction) -> Option<SuspiciousExecution> {
+        match instruction.instruction_family {
+            GameboyInstructionFamily::NOP => Some(SuspiciousExecution::Nop { pc }),
+            _ => None,
+        }
+    }
+
+    pub fn step(&mut self) -> Result<StepOutcome, EmuError> {
+        if !self.bus.access_log.reads && !self.bus.access_log.writes {
+            return self.step_instruction();
+        }
+
+        // Accesses made from outside an instruction, by a debugger for example, aren't reported
+        self.bus.access_log.take();
+
+        let registers = self.registers;
+        let opcode = self.bus.peek(registers.pc);
+        let cycles = self.cycles;
+        let snapshot = self.history.as_ref().is_some_and(History::needs_snapshot).then(|| self.snapshot());
+        let result = self.step_instruction();
+        let accesses = self.bus.access_log.take();
+
+        if let Some(history) = &mut self.history {
+            if let Some(snapshot) = snapshot {
+                history.push_snapshot(snapshot);
+            }
+
+            // A failed step didn't happen as far as going back is concerned
+            if result.is_ok() {
+                let writes = accesses.iter().filter(|access| access.kind == AccessKind::Write).copied().collect();
+                history.push(registers, opcode, cycles, writes);
+            }
+        }
+
+        for access in accesses {
+            self.hooks.call(&HookContext {
+                pc: registers.pc,
+                opcode,
+                registers,
+                access,
+            });
+        }
+
+        result
+    }
+
+    fn step_instruction(&mut self) -> Result<StepOutcome, EmuError> {
+        let registers = self.registers;
+
+        if self.locked {
+            return Err(self.error(EmuErrorKind::IllegalOpcode, registers));
+        }
+
+        let pending = self.bus.pending_interrupts();
+
+        if pending != 0 {
+            // Any pending interrupt wakes the CPU up, even with IME disabled
+            self.halted = false;
+
+            if self.ime {
+                let interrupt = self.dispatch_interrupt();
+                self.tick(Interrupt::DISPATCH_CYCLES as u32);
+
+                return Ok(StepOutcome::Interrupt(interrupt));
+            }
+        }
+
+        if self.halted {
+            self.tick(4);
+            return Ok(StepOutcome::Halted);
+        }
+
+        if self.stopped && self.bus.io[0x0F] & Interrupt::Joypad.mask() != 0 {
+            // A button press wakes the system up, whether the joypad interrupt is enabled or not
+            self.stopped = false;
+        }
+
+        if self.stopped {
+            // The whole system is stopped, only time passes
+            self.cycles += 4;
+            return Ok(StepOutcome::Stopped);
+        }
+
+        if let Some(mut tracer) = self.tracer.take() {
+            tracer.trace(self);
+            self.tracer = Some(tracer);
+        }
+
+        let enable_ime = self.ime_scheduled;
+
+        let opcode = if self.halt_bug {
+            self.halt_bug = false;
+            self.bus.fetch(self.registers.pc)
+        } else {
+            self.fetch()
+        };
+        let instruction = self.decode(opcode);
+
+        if self.exec_hooks {
+            self.hooks.call(&HookContext {
+                pc: registers.pc,
+                opcode,
+                registers,
+                access: MemoryAccess { address: registers.pc, kind: AccessKind::Execute, old: opcode, new: opcode },
+            });
+        }
+
+        if self.config.suspicious_execution != SuspiciousExecutionPolicy::Execute {
+            if let Some(reason) = self.check_suspicious(registers.pc, &instruction) {
+                match self.config.suspicious_execution {
+                    SuspiciousExecutionPolicy::Log => {
+                        eprintln!("Suspicious execution: {:?} ({})", reason, instruction.mnemonic);
+                    },
+                    _ => {
+                        // Leave the CPU right before the instruction, as if it was never fetched
+                        self.registers = registers;
+                        return Err(self.error(EmuErrorKind::SuspiciousExecution(reason), registers));
+                    },
+                }
+            }
+        }
+
+        let is_breakpoint = self.config.software_breakpoints
+            && instruction.instruction_family == GameboyInstructionFamily::LD
+            && instruction.opcode == 0x40;
+
+        let cycles = match self.execute(instruction) {
+            Ok(cycles) => cycles,
+            Err(kind) => return Err(self.error(kind, registers)),
+        };
+
+        self.tick(cycles as u32);
+
+        // The CPU doesn't run while a VRAM DMA copies blocks
+        let stall = self.bus.take_dma_stall();
+
+        if stall != 0 {
+            self.tick(stall);
+        }
+
+        if enable_ime && self.ime_scheduled {
+            self.ime = true;
+            self.ime_scheduled = false;
+        }
+
+        if self.locked {
+            return Err(self.error(EmuErrorKind::IllegalOpcode, registers));
+        }
+
+        if is_breakpoint {
+            Ok(StepOutcome::Breakpoint)
+        } else if self.halted {
+            Ok(StepOutcome::Halted)
+        } else if self.stopped {
+            Ok(StepOutcome::Stopped)
+        } else {
+            Ok(StepOutcome::Normal)
+        }
+    }
+
+    /// Runs until a breakpoint is hit or the emulator fails.
+    pub fn run(&mut self) -> Result<StepOutcome, EmuError> {
+        loop {
+            if let StepOutcome::Breakpoint = self.step()? {
+                return Ok(StepOutcome::Breakpoint);
+            }
+        }
+    }
+
+    /// Adds a breakpoint for [`Gameboy::run_until`], returns its id.
+    pub fn add_breakpoint(&mut self, breakpoint: Breakpoint) -> usize {
+        self.breakpoints.push(Some(breakpoint));
+        self.breakpoints.len() - 1
+    }
+
+    /// Returns the removed breakpoint, `None` if there was none with this id.
+    pub fn remove_breakpoint(&mut self, id: usize) -> Option<Breakpoint> {
+        self.breakpoints.get_mut(id)?.take()
+    }
+
+    pub fn breakpoints(&self) -> impl Iterator<Item = (usize, &Breakpoint)> {
+        self.breakpoints
+            .iter()
+            .enumerate()
+            .filter_map(|(id, breakpoint)| breakpoint.as_ref().map(|breakpoint| (id, breakpoint)))
+    }
+
+    /// Adds a watchpoint for [`Gameboy::run_until`], returns its id.
+    pub fn add_watchpoint(&mut self, watchpoint: Watchpoint) -> usize {
+        self.bus.watchpoints.add(watchpoint)
+    }
+
+    /// Returns the removed watchpoint, `None` if there was none with this id.
+    pub fn remove_watchpoint(&mut self, id: usize) -> Option<Watchpoint> {
+        self.bus.watchpoints.remove(id)
+    }
+
+    /// Calls `hook` after every instruction that read a byte of data in `range`. Instruction fetches aren't reads.
+    pub fn on_read(&mut self, range: RangeInclusive<u16>, hook: impl FnMut(&HookContext) + Send + 'static) -> usize {
+        self.add_hook(range, AccessKind::Read, Box::new(hook))
+    }
+
+    /// Calls `hook` after every instruction that wrote a byte in `range`, with the byte before and after the write.
+    pub fn on_write(&mut self, range: RangeInclusive<u16>, hook: impl FnMut(&HookContext) + Send + 'static) -> usize {
+        self.add_hook(range, AccessKind::Write, Box::new(hook))
+    }
+
+    /// Calls `hook` before executing any instruction in `range`.
+    pub fn on_exec(&mut self, range: RangeInclusive<u16>, hook: impl FnMut(&HookContext) + Send + 'static) -> usize {
+        self.add_hook(range, AccessKind::Execute, Box::new(hook))
+    }
+
+    fn add_hook(
+        &mut self,
+        range: RangeInclusive<u16>,
+        kind: AccessKind,
+        hook: Box<dyn FnMut(&HookContext) + Send>,
+    ) -> usize {
+        let id = self.hooks.add(range, kind, hook);
+        self.update_hook_flags();
+
+        id
+    }
+
+    /// Returns whether there was a hook with this id.
+    pub fn remove_hook(&mut self, id: usize) -> bool {
+        let removed = self.hooks.remove(id);
+        self.update_hook_flags();
+
+        removed
+    }
+
+    fn update_hook_flags(&mut self) {
+        self.bus.access_log.reads = self.hooks.any(AccessKind::Read);
+        self.bus.access_log.writes = self.hooks.any(AccessKind::Write) || self.history.is_some();
+        self.exec_hooks = self.hooks.any(AccessKind::Execute);
+    }
+
+    /// Traces every instruction from now on, or stops tracing with `None`. Returns the previous tracer, which should
+    /// be finished to flush it.
+    pub fn set_tracer(&mut self, tracer: Option<Tracer>) -> Option<Tracer> {
+        mem::replace(&mut self.tracer, tracer)
+    }
+
+    /// Sets the labels used by [`Gameboy::resolve`] and [`Gameboy::symbolize`], usually from the `.sym` file rgblink
+    /// wrote next to the ROM.
+    pub fn load_symbols(&mut self, symbols: SymbolTable) {
+        self.symbols = symbols;
+    }
+
+    pub fn symbols(&self) -> &SymbolTable {
+        &self.symbols
+    }
+
+    /// Resolves an address given as a number, a label or `label+offset`.
+    pub fn resolve(&self, address: &str) -> Result<u16, Error> {
+        self.symbols.resolve(address)
+    }
+
+    /// Formats an address as `label+offset`, using the label of the bank currently mapped there.
+    pub fn symbolize(&self, address: u16) -> String {
+        self.symbols.symbolize(address, self.bank_at(address))
+    }
+
+    /// Returns the bank mapped at `address`, for the banked areas: ROM, VRAM and WRAM bank 1 - 7.
+    pub fn bank_at(&self, address: u16) -> Option<usize> {
+        match address {
+            0x0000..=0x3FFF => Some(self.bus.cartridge.rom_banks().0),
+            0x4000..=0x7FFF => Some(self.bus.cartridge.rom_banks().1),
+            0x8000..=0x9FFF => Some(self.bus.vram_bank as usize),
+            0xD000..=0xDFFF => Some(self.bus.wram_bank.max(1) as usize),
+            _ => None,
+        }
+    }
+
+    fn breakpoint_hit(&self, breakpoint: &Breakpoint) -> bool {
+        if breakpoint.address != self.registers.pc {
+            return false;
+        }
+
+        if let (Some(bank), Some(mapped)) = (breakpoint.bank, self.bank_at(breakpoint.address)) {
+            if bank != mapped {
+                return false;
+            }
+        }
+
+        match breakpoint.condition {
+            None => true,
+            Some(BreakpointCondition::Register8(register, value)) => self.registers.get_reg8(&register) == value,
+            Some(BreakpointCondition::Register16(register, value)) => self.registers.get_reg16(&register) == value,
+            Some(BreakpointCondition::Memory(address, value)) => self.bus.peek(address) == value,
+        }
+    }
+
+    /// Runs until a breakpoint or a watchpoint is hit, or until `max_cycles` T-cycles have elapsed. The instruction at
+    /// PC is always executed first, so that calling this again resumes after a breakpoint.
+    pub fn run_until(&mut self, max_cycles: u64) -> Result<StopReason, EmuError> {
+        let deadline = self.cycles.saturating_add(max_cycles);
+        self.bus.take_watch_hit();
+
+        loop {
+            if self.cycles >= deadline {
+                return Ok(StopReason::CycleLimit);
+            }
+
+            let pc = self.registers.pc;
+            let outcome = self.step()?;
+
+            if let Some(hit) = self.bus.take_watch_hit() {
+                return Ok(StopReason::Watchpoint(WatchHit { pc, ..hit }));
+            }
+
+            match outcome {
+                StepOutcome::Breakpoint => return Ok(StopReason::SoftwareBreakpoint),
+                // PC didn't move, don't report the same breakpoint over and over
+                StepOutcome::Halted | StepOutcome::Stopped => continue,
+                _ => {},
+            }
+
+            let pc = self.registers.pc;
+
+            if let Some(id) = self.bus.watchpoints.find(pc, AccessKind::Execute) {
+                let value = self.bus.peek(pc);
+                return Ok(StopReason::Watchpoint(WatchHit { id, address: pc, kind: AccessKind::Execute, value, pc }));
+            }
+
+            if let Some((id, _)) = self.breakpoints().find(|(_, breakpoint)| self.breakpoint_hit(breakpoint)) {
+                return Ok(StopReason::Breakpoint(id));
+            }
+        }
+    }
+
+    /// Starts recording every step, to be able to go back to any of the last ones. Clears the history if it was
+    /// already enabled.
+    pub fn enable_history(&mut self, config: HistoryConfig) {
+        self.history = Some(History::new(config));
+        self.update_hook_flags();
+    }
+
+    pub fn disable_history(&mut self) {
+        self.history = None;
+        self.update_hook_flags();
+    }
 
-                        self.registers.set_flag(GameboyRegisterFlags::Z, self.registers.get_reg8(&register) == 0);
-                        self.registers.set_flag(GameboyRegisterFlags::N, true);
-                        self.registers.set_flag(GameboyRegisterFlags::H, (value & 0x0F) == 0);
-                    },
-                    Some(GameboyInstructionOperand::Register16(register)) => {
-                        // DEC r16
-                        let value = self.registers.get_reg16(&register);
-                        self.registers.set_reg16(&register, value.wrapping_sub(1));
-                    },
-                    _ => panic!("Invalid operand for DEC instruction"),
-                }
-            },
-            GameboyInstructionFamily::JR => {
-                match instruction.operand1 {
-                    Some(GameboyInstructionOperand::ImmediateSigned8(offset)) => {
-                        // JR n8
-                        self.registers.pc = (self.registers.pc as i16 + offset as i16) as u16;
-                    },
-                    _ => panic!("Invalid operand for JR instruction"),
-                }
-            },
-            GameboyInstructionFamily::CALL => {
-                match instruction.operand1 {
-                    Some(GameboyInstructionOperand::Address(address)) => {
-                        // CALL n16
-                        let return_address = self.registers.pc;
-                        self.registers.sp -= 2;
-                        self.memory[self.registers.sp as usize] = return_address as u8;
-                        self.memory[self.registers.sp as usize + 1] = (return_address >> 8) as u8;
-                        self.registers.pc = address;
-                    },
-                    _ => panic!("Invalid operand for CALL instruction"),
-                }
-            },
-            GameboyInstructionFamily::OR => {
-                match instruction.operand1 {
-                    Some(GameboyInstructionOperand::Register8(register)) => {
-                        // OR A, r8
-                        let value = self.registers.get_reg8(&register);
-                        let result = self.registers.get_reg8(&GameboyNamedRegister8::A) | value;
-                        self.registers.set_reg8(&GameboyNamedRegister8::A, result);
+    pub fn history(&self) -> Option<&History> {
+        self.history.as_ref()
+    }
 
-                        self.registers.set_flag(GameboyRegisterFlags::Z, result == 0);
-                        self.registers.set_flag(GameboyRegisterFlags::N, false);
-                        self.registers.set_flag(GameboyRegisterFlags::H, false);
-                        self.registers.set_flag(GameboyRegisterFlags::C, false);
-                    },
-                    _ => panic!("Invalid operand for OR instruction"),
-                }
-            },
-            GameboyInstructionFamily::RET => {
-                // RET
-                let low = self.memory[self.registers.sp as usize];
-                let high = self.memory[self.registers.sp as usize + 1];
-                self.registers.sp += 2;
-                self.registers.pc = (high as u16) << 8 | low as u16;
-            },
-            GameboyInstructionFamily::LDH => {
-                match instruction.operand1 {
-                    Some(GameboyInstructionOperand::Immediate8(offset)) => {
-                        match instruction.operand2 {
-                            Some(GameboyInstructionOperand::Register8(register)) => {
-                                // LDH (n8), A
-                                self.memory[0xFF00 + offset as usize] = self.registers.get_reg8(&register);
-                            },
-                            _ => panic!("Invalid operand for LDH instruction"),
-                        }
-                    },
-                    Some(GameboyInstructionOperand::Register8(register)) => {
-                        match instruction.operand2 {
-                            Some(GameboyInstructionOperand::Immediate8(offset)) => {
-                                // LDH A, (n8)
-                                self.registers.set_reg8(&register, self.memory[0xFF00 + offset as usize]);
-                            },
-                            _ => panic!("Invalid operand for LDH instruction"),
-                        }
-                    },
-                    _ => panic!("Invalid operand for LDH instruction"),
-                }
-            },
-            GameboyInstructionFamily::CP => {
-                match instruction.operand1 {
-                    Some(GameboyInstructionOperand::Immediate8(value)) => {
-                        // CP n8
-                        let a = self.registers.get_reg8(&GameboyNamedRegister8::A);
-                        let result = a.wrapping_sub(value);
+    /// Forgets the recorded steps, as going back to them would undo changes made from outside of the emulated
+    /// program. [`Gameboy::write_byte`] and [`Gameboy::write_bytes`] call it, callers editing `registers` or the bus
+    /// directly must call it too.
+    pub fn invalidate_history(&mut self) {
+        if let Some(history) = &mut self.history {
+            history.clear();
+        }
+    }
 
-                        self.registers.set_flag(GameboyRegisterFlags::Z, result == 0);
-                        self.registers.set_flag(GameboyRegisterFlags::N, true);
-                        self.registers.set_flag(GameboyRegisterFlags::H, (a & 0x0F) < (value & 0x0F));
-                        self.registers.set_flag(GameboyRegisterFlags::C, a < value);
-                    },
-                    Some(GameboyInstructionOperand::Register8(register)) => {
-                        // CP r8
-                        let a = self.registers.get_reg8(&GameboyNamedRegister8::A);
-                        let value = self.registers.get_reg8(&register);
-                        let result = a.wrapping_sub(value);
+    /// Goes back to the state right before the step with this index in the history, by restoring the closest
+    /// snapshot before it and replaying the steps in between. Hooks and the tracer aren't called again, and the steps
+    /// gone back over are forgotten. Buttons pressed between steps are only replayed if they come from an input
+    /// movie. The serial port doesn't send the bytes of the replayed steps again, but what the serial output got from
+    /// the steps gone back over stays there, and the MBC3 clock doesn't move with the host clock while replaying.
+    /// Returns `false` if the step isn't in the history anymore.
+    pub fn rewind_to(&mut self, index: u64) -> Result<bool, EmuError> {
+        let Some(mut history) = self.history.take() else {
+            return Ok(false);
+        };
 
-                        self.registers.set_flag(GameboyRegisterFlags::Z, result == 0);
-                        self.registers.set_flag(GameboyRegisterFlags::N, true);
-                        self.registers.set_flag(GameboyRegisterFlags::H, (a & 0x0F) < (value & 0x0F));
-                        self.registers.set_flag(GameboyRegisterFlags::C, a < value);
-                    },
-                    Some(GameboyInstructionOperand::Pointer(register, pointer_op)) => {
-                        // CP (r16)
-                        let address = self.registers.get_reg16(&register);
-                        let value = self.memory[address as usize];
-                        let a = self.registers.get_reg8(&GameboyNamedRegister8::A);
-                        let result = a.wrapping_sub(value);
+        let result = self.replay(&mut history, index);
+        self.history = Some(history);
 
-                        self.registers.set_flag(GameboyRegisterFlags::Z, result == 0);
-                        self.registers.set_flag(GameboyRegisterFlags::N, true);
-                        self.registers.set_flag(GameboyRegisterFlags::H, (a & 0x0F) < (value & 0x0F));
-                        self.registers.set_flag(GameboyRegisterFlags::C, a < value);
-
-                        if let Some(operation) = pointer_op {
-                            match operation {
-                                GameboyInstructionPointerOp::Increment => {
-                                    self.registers.set_reg16(&register, address + 1);
-                                },
-                                GameboyInstructionPointerOp::Decrement => {
-                                    self.registers.set_reg16(&register, address - 1);
-                                },
-                            }
-                        }
-                    }
-                    _ => panic!("Invalid operand for CP instruction"),
-                }
-            },
-            GameboyInstructionFamily::INC => {
-                match instruction.operand1 {
-                    Some(GameboyInstructionOperand::Register8(register)) => {
-                        // INC r8
-                        let value = self.registers.get_reg8(&register);
-                        self.registers.set_reg8(&register, value.wrapping_add(1));
+        result
+    }
 
-                        self.registers.set_flag(GameboyRegisterFlags::Z, self.registers.get_reg8(&register) == 0);
-                        self.registers.set_flag(GameboyRegisterFlags::N, false);
-                        self.registers.set_flag(GameboyRegisterFlags::H, (value & 0x0F) == 0x0F);
-                    },
-                    Some(GameboyInstructionOperand::Register16(register)) => {
-                        // INC r16
-                        let value = self.registers.get_reg16(&register);
-                        self.registers.set_reg16(&register, value.wrapping_add(1));
-                    },
-                    _ => panic!("Invalid operand for INC instruction"),
-                }
-            },
-            GameboyInstructionFamily::AND => {
-                match instruction.operand1 {
-                    Some(GameboyInstructionOperand::Register8(register)) => {
-                        // AND A, r8
-                        let value = self.registers.get_reg8(&register);
-                        let result = self.registers.get_reg8(&GameboyNamedRegister8::A) & value;
-                        self.registers.set_reg8(&GameboyNamedRegister8::A, result);
+    fn replay(&mut self, history: &mut History, index: u64) -> Result<bool, EmuError> {
+        if index < history.oldest() || index > history.position() {
+            return Ok(false);
+        }
 
-                        self.registers.set_flag(GameboyRegisterFlags::Z, result == 0);
-                        self.registers.set_flag(GameboyRegisterFlags::N, false);
-                        self.registers.set_flag(GameboyRegisterFlags::H, true);
-                        self.registers.set_flag(GameboyRegisterFlags::C, false);
-                    },
-                    _ => panic!("Invalid operand for AND instruction"),
-                }
-            },
-            GameboyInstructionFamily::PUSH => {
-                match instruction.operand1 {
-                    Some(GameboyInstructionOperand::Register16(register)) => {
-                        // PUSH r16
-                        let value = self.registers.get_reg16(&register);
-                        self.registers.sp -= 2;
-                        self.memory[self.registers.sp as usize] = value as u8;
-                        self.memory[self.registers.sp as usize + 1] = (value >> 8) as u8;
-                    },
-                    _ => panic!("Invalid operand for PUSH instruction"),
-                }
-            },
-            GameboyInstructionFamily::POP => {
-                match instruction.operand1 {
-                    Some(GameboyInstructionOperand::Register16(register)) => {
-                        // POP r16
-                        let low = self.memory[self.registers.sp as usize];
-                        let high = self.memory[self.registers.sp as usize + 1];
-                        self.registers.sp += 2;
-                        self.registers.set_reg16(&register, (high as u16) << 8 | low as u16);
-                    },
-                    _ => panic!("Invalid operand for POP instruction"),
-                }
-            },
-            GameboyInstructionFamily::SCF => {
-                // SCF
-                self.registers.set_flag(GameboyRegisterFlags::N, false);
-                self.registers.set_flag(GameboyRegisterFlags::H, false);
-                self.registers.set_flag(GameboyRegisterFlags::C, true);
-            },
-            GameboyInstructionFamily::ADD => {
-                match instruction.operand1 {
-                    Some(GameboyInstructionOperand::Register16(register)) => {
-                        match instruction.operand2 {
-                            Some(GameboyInstructionOperand::Register16(register2)) => {
-                                // ADD r16, r16
-                                let value1 = self.registers.get_reg16(&register);
-                                let value2 = self.registers.get_reg16(&register2);
-                                let result = value1.wrapping_add(value2);
-
-                                self.registers.set_flag(GameboyRegisterFlags::N, false);
-                                self.registers.set_flag(GameboyRegisterFlags::H, (value1 & 0x0FFF) + (value2 & 0x0FFF) > 0x0FFF);
-                                self.registers.set_flag(GameboyRegisterFlags::C, value1 > 0xFFFF - value2);
-
-                                self.registers.set_reg16(&register, result);
-                            },
-                            _ => panic!("Invalid operand for ADD instruction"),
-                        }
-                    },
-                    Some(GameboyInstructionOperand::Register8(register)) => {
-                        match instruction.operand2 {
-                            Some(GameboyInstructionOperand::Immediate8(value)) => {
-                                // ADD A, n8
-                                let a = self.registers.get_reg8(&GameboyNamedRegister8::A);
-                                let result = a.wrapping_add(value);
-
-                                self.registers.set_flag(GameboyRegisterFlags::Z, result == 0);
-                                self.registers.set_flag(GameboyRegisterFlags::N, false);
-                                self.registers.set_flag(GameboyRegisterFlags::H, (a & 0x0F) + (value & 0x0F) > 0x0F);
-                                self.registers.set_flag(GameboyRegisterFlags::C, a > 0xFF - value);
-
-                                self.registers.set_reg8(&GameboyNamedRegister8::A, result);
-                            },
-                            _ => panic!("Invalid operand for ADD instruction"),
-                        }
-                    },
-                    _ => panic!("Invalid operand for ADD instruction"),
-                }
-            },
-            GameboyInstructionFamily::BIT => {
-                match instruction.operand1 {
-                    Some(GameboyInstructionOperand::Immediate8(bit)) => {
-                        match instruction.operand2 {
-                            Some(GameboyInstructionOperand::Register8(register)) => {
-                                // BIT n, r8
-                                let value = self.registers.get_reg8(&register);
-                                let result = value & (1 << bit) != 0;
-
-                                self.registers.set_flag(GameboyRegisterFlags::Z, !result);
-                                self.registers.set_flag(GameboyRegisterFlags::N, false);
-                                self.registers.set_flag(GameboyRegisterFlags::H, true);
-                            },
-                            _ => panic!("Invalid operand for BIT instruction"),
-                        }
-                    },
-                    _ => panic!("Invalid operand for BIT instruction"),
-                }
-            },
-            GameboyInstructionFamily::SWAP => {
-                match instruction.operand1 {
-                    Some(GameboyInstructionOperand::Register8(register)) => {
-                        // SWAP r8
-                        let value = self.registers.get_reg8(&register);
-                        let result = (value << 4) | (value >> 4);
+        let Some((start, snapshot)) = history.snapshot_before(index) else {
+            return Ok(false);
+        };
 
-                        self.registers.set_flag(GameboyRegisterFlags::Z, result == 0);
-                        self.registers.set_flag(GameboyRegisterFlags::N, false);
-                        self.registers.set_flag(GameboyRegisterFlags::H, false);
-                        self.registers.set_flag(GameboyRegisterFlags::C, false);
+        if self.load_snapshot(snapshot).is_err() {
+            return Err(self.error(EmuErrorKind::InvalidHistorySnapshot, self.registers));
+        }
 
-                        self.registers.set_reg8(&register, result);
-                    },
-                    _ => panic!("Invalid operand for SWAP instruction"),
-                }
-            },
-            _ => panic!("Unknown instruction family: {:?}", instruction.instruction_family),
+        let tracer = self.tracer.take();
+        let exec_hooks = mem::replace(&mut self.exec_hooks, false);
+        // The replayed steps already sent their serial bytes the first time
+        let output = mem::take(&mut self.bus.serial.output);
+        self.bus.cartridge.freeze_rtc(true);
+        let mut result = Ok(true);
+
+        for _ in start..index {
+            if let Err(error) = self.step_instruction() {
+                result = Err(error);
+                break;
+            }
+        }
+
+        // What the replayed steps did was already reported the first time
+        self.bus.access_log.take();
+        self.bus.take_watch_hit();
+        self.tracer = tracer;
+        self.exec_hooks = exec_hooks;
+        self.bus.serial.output = output;
+        self.bus.cartridge.freeze_rtc(false);
+
+        if result.is_ok() {
+            history.truncate(index);
         }
+
+        result
     }
 
-    pub fn step(&mut self) {
-        let opcode = self.fetch();
-        let instruction = self.decode(opcode);
-        // println!("{:#06X}: {:#04X} {}", self.registers.pc - 1, opcode, instruction.mnemonic);
-        self.execute(instruction);
+    /// Undoes the last step. Returns `false` if there is nothing to undo.
+    pub fn step_back(&mut self) -> Result<bool, EmuError> {
+        Ok(self.rewind(1)? == 1)
+    }
+
+    /// Undoes the last `steps` steps, or as many as the history has. Returns the number of steps undone.
+    pub fn rewind(&mut self, steps: u64) -> Result<u64, EmuError> {
+        let Some(history) = &self.history else {
+            return Ok(0);
+        };
+
+        let position = history.position();
+        let index = position.saturating_sub(steps).max(history.oldest());
+
+        if !self.rewind_to(index)? {
+            return Ok(0);
+        }
+
+        Ok(position - index)
+    }
+
+    /// Goes back to right before the last write to an address in a range, and returns the step that made it.
+    /// Stays where it is and returns `None` if no step in the history wrote there.
+    pub fn reverse_continue_to_write(&mut self, range: RangeInclusive<u16>) -> Result<Option<HistoryEntry>, EmuError> {
+        let Some((entry, _)) = self.history.as_ref().and_then(|history| history.last_write(range)) else {
+            return Ok(None);
+        };
+
+        let entry = entry.clone();
+
+        if !self.rewind_to(entry.index)? {
+            return Ok(None);
+        }
+
+        Ok(Some(entry))
     }
 
-    pub fn run(&mut self) {
+    /// Runs backwards until a breakpoint or a watchpoint would have been hit, which is [`Gameboy::run_until`] in
+    /// reverse. Write watchpoints stop right before the write, read watchpoints are ignored since reads aren't
+    /// recorded. Goes back to the oldest step and returns `None` when the start of the history is reached.
+    pub fn reverse_continue(&mut self) -> Result<Option<StopReason>, EmuError> {
         loop {
-            self.step();
+            let Some(history) = &self.history else {
+                return Ok(None);
+            };
+
+            let candidate = history.iter().rev().find_map(|entry| {
+                let write_hit = entry.writes.iter().rev().find_map(|access| {
+                    let id = self.bus.watchpoints.find(access.address, AccessKind::Write)?;
+                    let pc = entry.registers.pc;
+
+                    Some(WatchHit { id, address: access.address, kind: AccessKind::Write, value: access.new, pc })
+                });
+
+                let pc = entry.registers.pc;
+                let at_breakpoint = self.breakpoints().any(|(_, breakpoint)| breakpoint.address == pc)
+                    || self.bus.watchpoints.find(pc, AccessKind::Execute).is_some();
+
+                (write_hit.is_some() || at_breakpoint).then_some((entry.index, write_hit))
+            });
+
+            let Some((index, write_hit)) = candidate else {
+                let oldest = history.oldest();
+                self.rewind_to(oldest)?;
+
+                return Ok(None);
+            };
+
+            if !self.rewind_to(index)? {
+                return Ok(None);
+            }
+
+            if let Some(hit) = write_hit {
+                return Ok(Some(StopReason::Watchpoint(hit)));
+            }
+
+            // Banks and conditions can only be checked once the whole state is back
+            let pc = self.registers.pc;
+
+            if let Some(id) = self.bus.watchpoints.find(pc, AccessKind::Execute) {
+                let value = self.bus.peek(pc);
+                let hit = WatchHit { id, address: pc, kind: AccessKind::Execute, value, pc };
+
+                return Ok(Some(StopReason::Watchpoint(hit)));
+            }
+
+            if let Some((id, _)) = self.breakpoints().find(|(_, breakpoint)| self.breakpoint_hit(breakpoint)) {
+                return Ok(Some(StopReason::Breakpoint(id)));
+            }
+        }
+    }
+
+    /// Jumps to the handler of the highest priority pending interrupt.
+    fn dispatch_interrupt(&mut self) -> Option<Interrupt> {
+        self.ime = false;
+
+        // The interrupt to service is only picked after the upper byte of PC is pushed. If that push overwrote IE
+        // and cancelled every pending interrupt, the CPU ends up at 0000h.
+        let pc = self.registers.pc;
+        self.registers.sp = self.registers.sp.wrapping_sub(1);
+        self.bus.write(self.registers.sp, (pc >> 8) as u8);
+
+        let interrupt = Interrupt::highest(self.bus.pending_interrupts());
+
+        self.registers.sp = self.registers.sp.wrapping_sub(1);
+        self.bus.write(self.registers.sp, pc as u8);
+
+        match interrupt {
+            Some(interrupt) => {
+                self.bus.acknowledge_interrupt(interrupt);
+                self.registers.pc = interrupt.vector();
+            },
+            None => self.registers.pc = 0x0000,
+        }
+
+        interrupt
+    }
+
+    fn error(&self, kind: EmuErrorKind, registers: GameboyRegisters) -> EmuError {
+        EmuError {
+            kind,
+            pc: registers.pc,
+            opcode: self.read_byte(registers.pc),
+            registers,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+    use crate::debug::WatchAccess;
+    use crate::serial::SerialOutput;
+
+    /// Runs `code` from C000h until PC gets past it, returns the registers then.
+    fn run(code: &[u8]) -> GameboyRegisters {
+        let mut gb = Gameboy::new();
+        gb.write_bytes(0xC000, code);
+        gb.registers.pc = 0xC000;
+
+        while gb.registers.pc < 0xC000 + code.len() as u16 {
+            gb.step().unwrap();
+        }
+
+        gb.registers
+    }
+
+    #[test]
+    fn daa_after_an_addition() {
+        // ld a, $45 / add $38 / daa
+        assert_eq!(run(&[0x3E, 0x45, 0xC6, 0x38, 0x27]).af, 0x8300);
+        // ld a, $99 / add $01 / daa
+        assert_eq!(run(&[0x3E, 0x99, 0xC6, 0x01, 0x27]).af, 0x0090);
+    }
+
+    #[test]
+    fn daa_after_a_subtraction() {
+        // ld a, $42 / sub $15 / daa
+        assert_eq!(run(&[0x3E, 0x42, 0xD6, 0x15, 0x27]).af, 0x2740);
+        // ld a, $10 / sub $20 / daa
+        assert_eq!(run(&[0x3E, 0x10, 0xD6, 0x20, 0x27]).af, 0x9050);
+    }
+
+    #[test]
+    fn adc_and_sbc_use_the_carry() {
+        // scf / ld a, $0F / adc $00
+        assert_eq!(run(&[0x37, 0x3E, 0x0F, 0xCE, 0x00]).af, 0x1020);
+        // scf / ld a, $FF / adc $00
+        assert_eq!(run(&[0x37, 0x3E, 0xFF, 0xCE, 0x00]).af, 0x00B0);
+        // scf / ld a, $10 / sbc $0F
+        assert_eq!(run(&[0x37, 0x3E, 0x10, 0xDE, 0x0F]).af, 0x00E0);
+        // scf / ld a, $00 / sbc $00
+        assert_eq!(run(&[0x37, 0x3E, 0x00, 0xDE, 0x00]).af, 0xFF70);
+    }
+
+    #[test]
+    fn sp_offsets_set_h_and_c_from_the_low_byte() {
+        // ld sp, $00FF / add sp, 1
+        let registers = run(&[0x31, 0xFF, 0x00, 0xE8, 0x01]);
+        assert_eq!((registers.sp, registers.af & 0xFF), (0x0100, 0x30));
+
+        // ld sp, $0000 / add sp, -1
+        let registers = run(&[0x31, 0x00, 0x00, 0xE8, 0xFF]);
+        assert_eq!((registers.sp, registers.af & 0xFF), (0xFFFF, 0x00));
+
+        // ld sp, $FFF8 / ld hl, sp + 8
+        let registers = run(&[0x31, 0xF8, 0xFF, 0xF8, 0x08]);
+        assert_eq!((registers.hl, registers.sp, registers.af & 0xFF), (0x0000, 0xFFF8, 0x30));
+    }
+
+    #[test]
+    fn rotates() {
+        // scf / ld b, $80 / rl b
+        let registers = run(&[0x37, 0x06, 0x80, 0xCB, 0x10]);
+        assert_eq!((registers.bc >> 8, registers.af & 0xFF), (0x01, 0x10));
+
+        // Unlike the CB-prefixed rotates, RLCA always clears Z. xor a / rlca, then xor a / rlc a
+        assert_eq!(run(&[0xAF, 0x07]).af, 0x0000);
+        assert_eq!(run(&[0xAF, 0xCB, 0x07]).af, 0x0080);
+
+        // ld a, $01 / rrca / ld c, $01 / srl c / ld d, $80 / sra d
+        let registers = run(&[0x3E, 0x01, 0x0F, 0x0E, 0x01, 0xCB, 0x39, 0x16, 0x80, 0xCB, 0x2A]);
+        assert_eq!((registers.af >> 8, registers.bc & 0xFF, registers.de >> 8), (0x80, 0x00, 0xC0));
+    }
+
+    #[test]
+    fn frames_go_on_with_the_lcd_off() {
+        let mut rom = vec![0; 0x8000];
+        // xor a / ldh [rLCDC], a / jr @
+        rom[0x100..0x105].copy_from_slice(&[0xAF, 0xE0, 0x40, 0x18, 0xFE]);
+
+        let mut gb = Gameboy::new();
+        gb.load_rom(rom).unwrap();
+        gb.run_until_frame(3).unwrap();
+
+        assert_eq!(gb.bus.peek(0xFF40) & 0x80, 0);
+        assert!(gb.cycles() >= 2 * 70224);
+    }
+
+    /// An MBC1+RAM+BATTERY cartridge with 8 KiB of RAM, running an endless `jr` loop.
+    fn mbc1_ram_rom() -> Vec<u8> {
+        let mut rom = vec![0; 0x8000];
+        rom[0x100..0x102].copy_from_slice(&[0x18, 0xFE]);
+        rom[0x147] = 0x03;
+        rom[0x149] = 0x02;
+        rom
+    }
+
+    #[test]
+    fn snapshot_round_trip() {
+        let mut rom = mbc1_ram_rom();
+        // ld hl, $C000 / .loop: inc [hl] / inc hl / jr .loop
+        rom[0x100..0x107].copy_from_slice(&[0x21, 0x00, 0xC0, 0x34, 0x23, 0x18, 0xFC]);
+
+        let mut gb = Gameboy::new();
+        gb.load_rom(rom.clone()).unwrap();
+
+        for _ in 0..1000 {
+            gb.step().unwrap();
+        }
+
+        let snapshot = Snapshot::from_bytes(gb.snapshot().as_bytes().to_vec()).unwrap();
+
+        for _ in 0..1000 {
+            gb.step().unwrap();
+        }
+
+        let later = gb.snapshot();
+        gb.restore(&snapshot).unwrap();
+
+        assert_eq!(gb.snapshot(), snapshot);
+
+        for _ in 0..1000 {
+            gb.step().unwrap();
+        }
+
+        assert_eq!(gb.snapshot(), later);
+
+        // The snapshot only fits the game it was taken with, told apart by its global checksum
+        rom[0x14F] = 0x01;
+        let mut other = Gameboy::new();
+        other.load_rom(rom).unwrap();
+
+        assert!(other.restore(&snapshot).is_err());
+    }
+
+    #[test]
+    fn restore_checkpoint_undoes_every_write() {
+        let mut rom = mbc1_ram_rom();
+        // ld a, $0A / ld [$0000], a / ld hl, $8000 / .loop: inc [hl] / inc hl / jr .loop
+        rom[0x100..0x10C].copy_from_slice(&[0x3E, 0x0A, 0xEA, 0x00, 0x00, 0x21, 0x00, 0x80, 0x34, 0x23, 0x18, 0xFC]);
+
+        let mut gb = Gameboy::new();
+        gb.load_rom(rom).unwrap();
+
+        let first = gb.checkpoint();
+        let at_first = gb.snapshot();
+
+        // Through VRAM, the external RAM and WRAM
+        for _ in 0..60000 {
+            gb.step().unwrap();
+        }
+
+        let second = gb.checkpoint();
+        let at_second = gb.snapshot();
+
+        for _ in 0..5000 {
+            gb.step().unwrap();
+        }
+
+        // Only the pages written since are copied back the second time
+        for _ in 0..2 {
+            gb.restore_checkpoint(&second);
+            assert_eq!(gb.snapshot(), at_second);
+        }
+
+        gb.restore_checkpoint(&first);
+        assert_eq!(gb.snapshot(), at_first);
+
+        gb.write_bytes(0xC000, &[0x12; 0x100]);
+        gb.restore_checkpoint(&first);
+        assert_eq!(gb.snapshot(), at_first);
+    }
+
+    #[test]
+    fn restore_checkpoint_after_loading_a_save() {
+        let mut gb = Gameboy::new();
+        gb.load_rom(mbc1_ram_rom()).unwrap();
+
+        let checkpoint = gb.checkpoint();
+
+        let path = std::env::temp_dir().join(format!("gbhttpd-test-{}.sav", std::process::id()));
+        fs::write(&path, vec![0xAB; 0x2000]).unwrap();
+        gb.load_save_file(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(gb.sram()[0x1FFF], 0xAB);
+
+        gb.restore_checkpoint(&checkpoint);
+
+        assert!(gb.sram().iter().all(|&byte| byte == 0));
+    }
+
+    #[test]
+    fn read_watchpoints_ignore_instruction_fetches() {
+        let mut rom = vec![0; 0x8000];
+        // ld hl, $0150 / ld a, [hl] / jr @
+        rom[0x100..0x106].copy_from_slice(&[0x21, 0x50, 0x01, 0x7E, 0x18, 0xFE]);
+
+        let mut gb = Gameboy::new();
+        gb.load_rom(rom).unwrap();
+        let id = gb.add_watchpoint(Watchpoint::new(0x0100..=0x0150, WatchAccess::READ));
+
+        let stop = gb.run_until(1000).unwrap();
+
+        assert_eq!(
+            stop,
+            StopReason::Watchpoint(WatchHit { id, address: 0x0150, kind: AccessKind::Read, value: 0x00, pc: 0x0103 })
+        );
+    }
+
+    #[test]
+    fn read_hooks_ignore_instruction_fetches() {
+        let mut rom = vec![0; 0x8000];
+        // ld hl, $0150 / ld a, [hl] / jr @
+        rom[0x100..0x106].copy_from_slice(&[0x21, 0x50, 0x01, 0x7E, 0x18, 0xFE]);
+
+        let mut gb = Gameboy::new();
+        gb.load_rom(rom).unwrap();
+        let reads = Arc::new(Mutex::new(Vec::new()));
+        let log = reads.clone();
+        gb.on_read(0x0000..=0x7FFF, move |context| log.lock().unwrap().push((context.pc, context.access.address)));
+
+        for _ in 0..4 {
+            gb.step().unwrap();
         }
+
+        assert_eq!(*reads.lock().unwrap(), [(0x0103, 0x0150)]);
+    }
+
+    #[test]
+    fn outside_writes_clear_the_history() {
+        let mut rom = vec![0; 0x8000];
+        // ld a, $42 / ld [$C000], a / jr @
+        rom[0x100..0x107].copy_from_slice(&[0x3E, 0x42, 0xEA, 0x00, 0xC0, 0x18, 0xFE]);
+
+        let mut gb = Gameboy::new();
+        gb.load_rom(rom).unwrap();
+        gb.enable_history(HistoryConfig::default());
+
+        for _ in 0..3 {
+            gb.step().unwrap();
+        }
+
+        assert_eq!(gb.history().unwrap().len(), 3);
+
+        gb.write_byte(0xC000, 0x99);
+
+        assert!(gb.history().unwrap().is_empty());
+        assert!(!gb.step_back().unwrap());
+        assert_eq!(gb.read_byte(0xC000), 0x99);
+    }
+
+    #[test]
+    fn rewinding_does_not_send_serial_bytes_again() {
+        let mut rom = vec![0; 0x8000];
+        // ld a, "A" / ldh [rSB], a / ld a, $81 / ldh [rSC], a / jr @
+        rom[0x100..0x10A].copy_from_slice(&[0x3E, 0x41, 0xE0, 0x01, 0x3E, 0x81, 0xE0, 0x02, 0x18, 0xFE]);
+
+        let mut gb = Gameboy::new();
+        gb.load_rom(rom).unwrap();
+        gb.bus.serial.output = SerialOutput::Capture(Vec::new());
+        gb.enable_history(HistoryConfig::default());
+
+        for _ in 0..5 {
+            gb.step().unwrap();
+        }
+
+        assert_eq!(gb.rewind(1).unwrap(), 1);
+        assert_eq!(gb.bus.serial.captured(), b"A");
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,657 @@
+use std::collections::HashMap;
+use std::io::{self, Read, Write};
+use std::net::TcpStream;
+
+use crate::debug::{AccessKind, Breakpoint, StopReason, WatchAccess, Watchpoint};
+use crate::error::{EmuError, Error};
+use crate::gb::{Gameboy, GameboyNamedRegister16};
+
+/// Registers in the order of `g` and `G` packets and of the numbers of `p` and `P` packets, 16 bits each, little
+/// endian.
+const REGISTERS: [GameboyNamedRegister16; 6] = [
+    GameboyNamedRegister16::AF,
+    GameboyNamedRegister16::BC,
+    GameboyNamedRegister16::DE,
+    GameboyNamedRegister16::HL,
+    GameboyNamedRegister16::SP,
+    GameboyNamedRegister16::PC,
+];
+
+/// Target description sent to clients that ask for it, there is no SM83 architecture in GDB itself.
+const TARGET_XML: &str = r#"<?xml version="1.0"?>
+<!DOCTYPE target SYSTEM "gdb-target.dtd">
+<target version="1.0">
+  <feature name="org.gbhttpd.sm83">
+    <reg name="af" bitsize="16" type="uint16" regnum="0"/>
+    <reg name="bc" bitsize="16" type="uint16"/>
+    <reg name="de" bitsize="16" type="uint16"/>
+    <reg name="hl" bitsize="16" type="uint16"/>
+    <reg name="sp" bitsize="16" type="data_ptr"/>
+    <reg name="pc" bitsize="16" type="code_ptr"/>
+  </feature>
+</target>
+"#;
+
+/// Largest packet accepted and sent, advertised to the client.
+const PACKET_SIZE: usize = 0x1000;
+
+/// T-cycles run between two checks for an interrupt request from the client, about a frame.
+const INTERRUPT_CHECK_CYCLES: u64 = 70224;
+
+const SIGINT: u8 = 2;
+const SIGILL: u8 = 4;
+const SIGTRAP: u8 = 5;
+
+/// What the client sent.
+enum Packet {
+    Command(Vec<u8>),
+    /// Ctrl-C, sent outside of a packet.
+    Interrupt,
+}
+
+/// A TCP connection framing packets as `$data#checksum`.
+struct Connection {
+    stream: TcpStream,
+    /// Bytes received but not handled yet.
+    input: Vec<u8>,
+    /// Set once the client asked with `QStartNoAckMode`, after which packets aren't acknowledged anymore.
+    no_ack: bool,
+}
+
+impl Connection {
+    /// Returns `None` once the client disconnected.
+    fn read_byte(&mut self) -> io::Result<Option<u8>> {
+        if self.input.is_empty() {
+            let mut buffer = [0; 1024];
+            let read = self.stream.read(&mut buffer)?;
+
+            if read == 0 {
+                return Ok(None);
+            }
+
+            self.input.extend_from_slice(&buffer[..read]);
+        }
+
+        Ok(Some(self.input.remove(0)))
+    }
+
+    /// Returns `None` once the client disconnected.
+    fn read_packet(&mut self) -> io::Result<Option<Packet>> {
+        loop {
+            match self.read_byte()? {
+                None => return Ok(None),
+                Some(0x03) => return Ok(Some(Packet::Interrupt)),
+                Some(b'$') => {},
+                // Acknowledgements of packets already sent, and noise
+                Some(_) => continue,
+            }
+
+            let mut data = Vec::new();
+            let mut checksum = 0u8;
+
+            loop {
+                match self.read_byte()? {
+                    None => return Ok(None),
+                    Some(b'#') => break,
+                    Some(byte) => {
+                        checksum = checksum.wrapping_add(byte);
+                        data.push(byte);
+                    },
+                }
+            }
+
+            let mut digits = [0; 2];
+
+            for digit in digits.iter_mut() {
+                match self.read_byte()? {
+                    None => return Ok(None),
+                    Some(byte) => *digit = byte,
+                }
+            }
+
+            let expected = std::str::from_utf8(&digits).ok().and_then(|digits| u8::from_str_radix(digits, 16).ok());
+
+            if self.no_ack {
+                return Ok(Some(Packet::Command(unescape(&data))));
+            }
+
+            if expected == Some(checksum) {
+                self.stream.write_all(b"+")?;
+                return Ok(Some(Packet::Command(unescape(&data))));
+            }
+
+            // Ask for the packet again
+            self.stream.write_all(b"-")?;
+        }
+    }
+
+    fn send(&mut self, data: &str) -> io::Result<()> {
+        let mut packet = vec![b'$'];
+
+        for &byte in data.as_bytes() {
+            match byte {
+                b'#' | b'$' | b'}' | b'*' => packet.extend_from_slice(&[b'}', byte ^ 0x20]),
+                _ => packet.push(byte),
+            }
+        }
+
+        let checksum = packet[1..].iter().fold(0u8, |checksum, &byte| checksum.wrapping_add(byte));
+        packet.extend_from_slice(format!("#{:02x}", checksum).as_bytes());
+
+        loop {
+            self.stream.write_all(&packet)?;
+
+            if self.no_ack {
+                return Ok(());
+            }
+
+            loop {
+                match self.read_byte()? {
+                    None | Some(b'+') => return Ok(()),
+                    Some(b'-') => break,
+                    // A Ctrl-C can't mean anything yet, the client is waiting for this packet
+                    Some(_) => continue,
+                }
+            }
+        }
+    }
+
+    /// Returns whether the client sent a Ctrl-C, without waiting for one.
+    fn interrupted(&mut self) -> io::Result<bool> {
+        let mut buffer = [0; 1024];
+
+        self.stream.set_nonblocking(true)?;
+        let result = self.stream.read(&mut buffer);
+        self.stream.set_nonblocking(false)?;
+
+        match result {
+            Ok(read) => self.input.extend_from_slice(&buffer[..read]),
+            Err(error) if error.kind() == io::ErrorKind::WouldBlock => {},
+            Err(error) => return Err(error),
+        }
+
+        match self.input.iter().position(|&byte| byte == 0x03) {
+            Some(index) => {
+                self.input.remove(index);
+                Ok(true)
+            },
+            None => Ok(false),
+        }
+    }
+}
+
+/// Undoes the `}` escapes of binary data.
+fn unescape(data: &[u8]) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(data.len());
+    let mut iter = data.iter();
+
+    while let Some(&byte) = iter.next() {
+        match byte {
+            b'}' => bytes.extend(iter.next().map(|&byte| byte ^ 0x20)),
+            _ => bytes.push(byte),
+        }
+    }
+
+    bytes
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn parse_hex_bytes(text: &str) -> Option<Vec<u8>> {
+    if !text.len().is_multiple_of(2) {
+        return None;
+    }
+
+    (0..text.len())
+        .step_by(2)
+        .map(|index| u8::from_str_radix(text.get(index..index + 2)?, 16).ok())
+        .collect()
+}
+
+fn parse_hex(text: &str) -> Option<usize> {
+    usize::from_str_radix(text, 16).ok()
+}
+
+/// Parses `address,length`, the length is clamped to the end of the address space.
+fn parse_range(text: &str) -> Option<(u16, usize)> {
+    let (address, length) = text.split_once(',')?;
+    let address = u16::try_from(parse_hex(address)?).ok()?;
+    let length = parse_hex(length)?.min(0x10000 - address as usize);
+
+    Some((address, length))
+}
+
+/// Breakpoint and watchpoint types of `Z` and `z` packets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum PointKind {
+    Software,
+    Hardware,
+    Write,
+    Read,
+    Access,
+}
+
+impl PointKind {
+    fn parse(text: &str) -> Option<PointKind> {
+        match text {
+            "0" => Some(PointKind::Software),
+            "1" => Some(PointKind::Hardware),
+            "2" => Some(PointKind::Write),
+            "3" => Some(PointKind::Read),
+            "4" => Some(PointKind::Access),
+            _ => None,
+        }
+    }
+}
+
+/// A client session driving a [`Gameboy`].
+struct Session<'a> {
+    gb: &'a mut Gameboy,
+    connection: Connection,
+    /// Breakpoints and watchpoints added by the client, with their id in the [`Gameboy`].
+    points: HashMap<(PointKind, u16, usize), usize>,
+    /// Set when the client understands the `swbreak` and `hwbreak` stop reasons.
+    break_reasons: bool,
+    last_stop: String,
+}
+
+impl Session<'_> {
+    fn run(&mut self) -> Result<(), Error> {
+        while let Some(packet) = self.connection.read_packet().map_err(Error::IoError)? {
+            let packet = match packet {
+                Packet::Command(packet) => packet,
+                // Nothing is running, the client is already told the target is stopped
+                Packet::Interrupt => continue,
+            };
+
+            match self.handle(&packet)? {
+                Some(reply) => self.connection.send(&reply).map_err(Error::IoError)?,
+                None => break,
+            }
+
+            if packet == b"QStartNoAckMode" {
+                self.connection.no_ack = true;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the reply to a packet, `None` when the session is over.
+    fn handle(&mut self, packet: &[u8]) -> Result<Option<String>, Error> {
+        // Binary data is only found in `X` packets, the rest is ASCII
+        if let Some(data) = packet.strip_prefix(b"X") {
+            return Ok(Some(self.write_binary(data)));
+        }
+
+        let packet = String::from_utf8_lossy(packet);
+        let packet = packet.as_ref();
+
+        let command_length = packet.chars().next().map_or(0, char::len_utf8);
+
+        let reply = match packet.split_at(command_length) {
+            ("?", _) => self.last_stop.clone(),
+            ("g", _) => self.read_registers(),
+            ("G", data) => self.write_registers(data),
+            ("p", number) => self.read_register(number),
+            ("P", assignment) => self.write_register(assignment),
+            ("m", range) => self.read_memory(range),
+            ("M", write) => self.write_memory(write),
+            ("Z", point) => self.insert_point(point),
+            ("z", point) => self.remove_point(point),
+            ("c", address) => self.resume(address, false)?,
+            ("s", address) => self.resume(address, true)?,
+            // Signals don't mean anything to the emulator
+            ("C", arguments) => self.resume(arguments.split_once(';').map_or("", |(_, address)| address), false)?,
+            ("S", arguments) => self.resume(arguments.split_once(';').map_or("", |(_, address)| address), true)?,
+            ("b", "s") => self.reverse(true)?,
+            ("b", "c") => self.reverse(false)?,
+            ("v", _) => self.handle_v(packet)?,
+            ("q", _) | ("Q", _) => self.handle_query(packet),
+            // Only one thread to select
+            ("H", _) | ("T", _) => "OK".to_owned(),
+            ("D", _) => {
+                self.connection.send("OK").map_err(Error::IoError)?;
+                return Ok(None);
+            },
+            ("k", _) => return Ok(None),
+            _ => String::new(),
+        };
+
+        Ok(Some(reply))
+    }
+
+    fn handle_v(&mut self, packet: &str) -> Result<String, Error> {
+        if packet == "vCont?" {
+            return Ok("vCont;c;C;s;S".to_owned());
+        }
+
+        if let Some(actions) = packet.strip_prefix("vCont;") {
+            // There is a single thread, the first action is the one for it
+            let action = actions.split(';').next().unwrap_or("");
+            let action = action.split(':').next().unwrap_or("");
+
+            return match action.chars().next() {
+                Some('c') | Some('C') => self.resume("", false),
+                Some('s') | Some('S') => self.resume("", true),
+                _ => Ok("E01".to_owned()),
+            };
+        }
+
+        Ok(String::new())
+    }
+
+    fn handle_query(&mut self, packet: &str) -> String {
+        if let Some(features) = packet.strip_prefix("qSupported") {
+            self.break_reasons = features.contains("swbreak+");
+
+            let mut reply = format!(
+                "PacketSize={:x};qXfer:features:read+;QStartNoAckMode+;vContSupported+;swbreak+;hwbreak+",
+                PACKET_SIZE
+            );
+
+            if self.gb.history().is_some() {
+                reply.push_str(";ReverseStep+;ReverseContinue+");
+            }
+
+            return reply;
+        }
+
+        if let Some(request) = packet.strip_prefix("qXfer:features:read:target.xml:") {
+            let Some((offset, length)) = request.split_once(',') else {
+                return "E01".to_owned();
+            };
+
+            let (Some(offset), Some(length)) = (parse_hex(offset), parse_hex(length)) else {
+                return "E01".to_owned();
+            };
+
+            let start = offset.min(TARGET_XML.len());
+            let end = start.saturating_add(length).min(TARGET_XML.len());
+            let marker = if end == TARGET_XML.len() { 'l' } else { 'm' };
+
+            return format!("{}{}", marker, &TARGET_XML[start..end]);
+        }
+
+        match packet {
+            "QStartNoAckMode" => "OK".to_owned(),
+            "qAttached" => "1".to_owned(),
+            "qC" => "QC1".to_owned(),
+            "qfThreadInfo" => "m1".to_owned(),
+            "qsThreadInfo" => "l".to_owned(),
+            _ => String::new(),
+        }
+    }
+
+    fn read_registers(&self) -> String {
+        REGISTERS.iter().map(|register| hex(&self.gb.registers.get_reg16(register).to_le_bytes())).collect()
+    }
+
+    fn write_registers(&mut self, data: &str) -> String {
+        let Some(bytes) = parse_hex_bytes(data).filter(|bytes| bytes.len() == REGISTERS.len() * 2) else {
+            return "E01".to_owned();
+        };
+
+        for (register, value) in REGISTERS.iter().zip(bytes.chunks(2)) {
+            self.gb.registers.set_reg16(register, u16::from_le_bytes([value[0], value[1]]));
+        }
+
+        self.gb.invalidate_history();
+        "OK".to_owned()
+    }
+
+    fn read_register(&self, number: &str) -> String {
+        match parse_hex(number).and_then(|number| REGISTERS.get(number)) {
+            Some(register) => hex(&self.gb.registers.get_reg16(register).to_le_bytes()),
+            None => "E01".to_owned(),
+        }
+    }
+
+    fn write_register(&mut self, assignment: &str) -> String {
+        let Some((number, value)) = assignment.split_once('=') else {
+            return "E01".to_owned();
+        };
+
+        let register = parse_hex(number).and_then(|number| REGISTERS.get(number));
+        let value = parse_hex_bytes(value).filter(|bytes| bytes.len() == 2);
+
+        match (register, value) {
+            (Some(register), Some(value)) => {
+                self.gb.registers.set_reg16(register, u16::from_le_bytes([value[0], value[1]]));
+                self.gb.invalidate_history();
+                "OK".to_owned()
+            },
+            _ => "E01".to_owned(),
+        }
+    }
+
+    fn read_memory(&self, range: &str) -> String {
+        let Some((address, length)) = parse_range(range) else {
+            return "E01".to_owned();
+        };
+
+        // Two hex digits per byte
+        let length = length.min(PACKET_SIZE / 2);
+        let bytes: Vec<u8> = (0..length).map(|offset| self.gb.bus.peek(address.wrapping_add(offset as u16))).collect();
+
+        hex(&bytes)
+    }
+
+    fn write_memory(&mut self, write: &str) -> String {
+        let Some((range, data)) = write.split_once(':') else {
+            return "E01".to_owned();
+        };
+
+        match (parse_range(range), parse_hex_bytes(data)) {
+            (Some((address, length)), Some(bytes)) if bytes.len() == length => {
+                self.gb.write_bytes(address, &bytes);
+                "OK".to_owned()
+            },
+            _ => "E01".to_owned(),
+        }
+    }
+
+    fn write_binary(&mut self, data: &[u8]) -> String {
+        let Some(separator) = data.iter().position(|&byte| byte == b':') else {
+            return "E01".to_owned();
+        };
+
+        let range = String::from_utf8_lossy(&data[..separator]);
+        let bytes = &data[separator + 1..];
+
+        match parse_range(&range) {
+            Some((address, length)) if bytes.len() == length => {
+                self.gb.write_bytes(address, bytes);
+                "OK".to_owned()
+            },
+            _ => "E01".to_owned(),
+        }
+    }
+
+    /// Parses `type,address,kind` where kind is the length for watchpoints.
+    fn parse_point(point: &str) -> Option<(PointKind, u16, usize)> {
+        let mut fields = point.split(',');
+        let kind = PointKind::parse(fields.next()?)?;
+        let address = u16::try_from(parse_hex(fields.next()?)?).ok()?;
+        // Conditions and commands evaluated by the target aren't supported
+        let length = parse_hex(fields.next()?.split(';').next()?)?;
+
+        Some((kind, address, length))
+    }
+
+    fn insert_point(&mut self, point: &str) -> String {
+        let Some(point) = Session::parse_point(point) else {
+            return "E01".to_owned();
+        };
+
+        if self.points.contains_key(&point) {
+            return "OK".to_owned();
+        }
+
+        let (kind, address, length) = point;
+        let end = address.saturating_add(length.saturating_sub(1).min(0xFFFF) as u16);
+
+        let id = match kind {
+            PointKind::Software | PointKind::Hardware => self.gb.add_breakpoint(Breakpoint::new(address)),
+            PointKind::Write => self.gb.add_watchpoint(Watchpoint::new(address..=end, WatchAccess::WRITE)),
+            PointKind::Read => self.gb.add_watchpoint(Watchpoint::new(address..=end, WatchAccess::READ)),
+            PointKind::Access => self.gb.add_watchpoint(Watchpoint::new(address..=end, WatchAccess::READ_WRITE)),
+        };
+
+        self.points.insert(point, id);
+
+        "OK".to_owned()
+    }
+
+    fn remove_point(&mut self, point: &str) -> String {
+        let Some(point) = Session::parse_point(point) else {
+            return "E01".to_owned();
+        };
+
+        if let Some(id) = self.points.remove(&point) {
+            self.remove_from_gameboy(point.0, id);
+        }
+
+        "OK".to_owned()
+    }
+
+    fn remove_from_gameboy(&mut self, kind: PointKind, id: usize) {
+        match kind {
+            PointKind::Software | PointKind::Hardware => {
+                self.gb.remove_breakpoint(id);
+            },
+            PointKind::Write | PointKind::Read | PointKind::Access => {
+                self.gb.remove_watchpoint(id);
+            },
+        }
+    }
+
+    /// Continues or steps, from `address` if one is given, and returns the stop reply.
+    fn resume(&mut self, address: &str, step: bool) -> Result<String, Error> {
+        if !address.is_empty() {
+            match parse_hex(address).and_then(|address| u16::try_from(address).ok()) {
+                Some(address) => {
+                    self.gb.registers.pc = address;
+                    self.gb.invalidate_history();
+                },
+                None => return Ok("E01".to_owned()),
+            }
+        }
+
+        let stop = if step { self.step() } else { self.continue_until_stop()? };
+
+        self.last_stop = match stop {
+            Ok(stop) => stop,
+            Err(error) => self.report_error(&error)?,
+        };
+
+        Ok(self.last_stop.clone())
+    }
+
+    /// Prints an emulation error on the client console, and returns the stop reply for it.
+    fn report_error(&mut self, error: &EmuError) -> Result<String, Error> {
+        let message = format!("{}\n", error);
+        self.connection.send(&format!("O{}", hex(message.as_bytes()))).map_err(Error::IoError)?;
+
+        Ok(format!("T{:02x}", SIGILL))
+    }
+
+    fn step(&mut self) -> Result<String, EmuError> {
+        self.gb.bus.take_watch_hit();
+        self.gb.step()?;
+
+        Ok(match self.gb.bus.take_watch_hit() {
+            Some(hit) => self.stop_reply(Some(StopReason::Watchpoint(hit))),
+            None => self.stop_reply(None),
+        })
+    }
+
+    fn continue_until_stop(&mut self) -> Result<Result<String, EmuError>, Error> {
+        loop {
+            match self.gb.run_until(INTERRUPT_CHECK_CYCLES) {
+                Ok(StopReason::CycleLimit) => {},
+                Ok(reason) => return Ok(Ok(self.stop_reply(Some(reason)))),
+                Err(error) => return Ok(Err(error)),
+            }
+
+            if self.connection.interrupted().map_err(Error::IoError)? {
+                return Ok(Ok(format!("T{:02x}", SIGINT)));
+            }
+        }
+    }
+
+    /// Steps or continues backwards through the history of the [`Gameboy`].
+    fn reverse(&mut self, step: bool) -> Result<String, Error> {
+        if self.gb.history().is_none() {
+            return Ok("E01".to_owned());
+        }
+
+        let result = if step {
+            self.gb.step_back().map(|stepped| stepped.then(|| self.stop_reply(None)))
+        } else {
+            self.gb.reverse_continue().map(|reason| reason.map(|reason| self.stop_reply(Some(reason))))
+        };
+
+        self.last_stop = match result {
+            Ok(Some(stop)) => stop,
+            Ok(None) => format!("T{:02x}replaylog:begin;", SIGTRAP),
+            Err(error) => self.report_error(&error)?,
+        };
+
+        Ok(self.last_stop.clone())
+    }
+
+    fn stop_reply(&self, reason: Option<StopReason>) -> String {
+        let kind_of = |id: usize, watchpoint: bool| {
+            self.points.iter().find_map(|(&(kind, _, _), &point_id)| {
+                let is_watchpoint = matches!(kind, PointKind::Write | PointKind::Read | PointKind::Access);
+                (point_id == id && is_watchpoint == watchpoint).then_some(kind)
+            })
+        };
+
+        let detail = match reason {
+            Some(StopReason::Breakpoint(id)) if self.break_reasons => match kind_of(id, false) {
+                Some(PointKind::Software) => "swbreak:;".to_owned(),
+                Some(PointKind::Hardware) => "hwbreak:;".to_owned(),
+                _ => String::new(),
+            },
+            Some(StopReason::Watchpoint(hit)) if hit.kind != AccessKind::Execute => match kind_of(hit.id, true) {
+                Some(PointKind::Write) => format!("watch:{:x};", hit.address),
+                Some(PointKind::Read) => format!("rwatch:{:x};", hit.address),
+                Some(PointKind::Access) => format!("awatch:{:x};", hit.address),
+                _ => String::new(),
+            },
+            _ => String::new(),
+        };
+
+        format!("T{:02x}{}", SIGTRAP, detail)
+    }
+}
+
+/// Serves a GDB remote serial protocol client until it detaches or disconnects. Breakpoints and watchpoints the
+/// client added are removed from the [`Gameboy`] once it's gone.
+pub fn serve(gb: &mut Gameboy, stream: TcpStream) -> Result<(), Error> {
+    stream.set_nodelay(true).map_err(Error::IoError)?;
+
+    let mut session = Session {
+        gb,
+        connection: Connection {
+            stream,
+            input: Vec::new(),
+            no_ack: false,
+        },
+        points: HashMap::new(),
+        break_reasons: false,
+        last_stop: format!("S{:02x}", SIGTRAP),
+    };
+
+    let result = session.run();
+
+    for ((kind, _, _), id) in std::mem::take(&mut session.points) {
+        session.remove_from_gameboy(kind, id);
+    }
+
+    result
+}
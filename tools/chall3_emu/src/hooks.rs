@@ -0,0 +1,97 @@
+use std::cell::RefCell;
+use std::fmt;
+use std::ops::RangeInclusive;
+
+use crate::debug::AccessKind;
+use crate::gb::GameboyRegisters;
+
+/// A memory access made by the CPU.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MemoryAccess {
+    pub address: u16,
+    pub kind: AccessKind,
+    /// The byte before the access. For reads and executions, the byte read.
+    pub old: u8,
+    /// The byte after the access. For reads and executions, the byte read.
+    pub new: u8,
+}
+
+/// What a hook gets called with.
+#[derive(Debug, Clone, Copy)]
+pub struct HookContext {
+    /// Address of the instruction that made the access.
+    pub pc: u16,
+    pub opcode: u8,
+    /// The registers right before the instruction was executed.
+    pub registers: GameboyRegisters,
+    pub access: MemoryAccess,
+}
+
+/// Accesses recorded by the bus for the hooks to be called with once the instruction is done.
+/// Nothing is recorded for the kinds of access no hook is interested in, nor for instruction fetches.
+#[derive(Debug, Clone, Default)]
+pub struct AccessLog {
+    pub reads: bool,
+    pub writes: bool,
+    /// Reads go through `&Bus`, hence the cell.
+    accesses: RefCell<Vec<MemoryAccess>>,
+}
+
+impl AccessLog {
+    pub fn record(&self, access: MemoryAccess) {
+        self.accesses.borrow_mut().push(access);
+    }
+
+    pub fn take(&self) -> Vec<MemoryAccess> {
+        self.accesses.take()
+    }
+}
+
+type HookCallback = Box<dyn FnMut(&HookContext) + Send>;
+
+struct Hook {
+    range: RangeInclusive<u16>,
+    kind: AccessKind,
+    callback: HookCallback,
+}
+
+/// Callbacks registered with [`crate::gb::Gameboy::on_read`], [`crate::gb::Gameboy::on_write`] and
+/// [`crate::gb::Gameboy::on_exec`].
+#[derive(Default)]
+pub struct Hooks {
+    /// Slots indexed by hook id, `None` once removed.
+    hooks: Vec<Option<Hook>>,
+}
+
+impl fmt::Debug for Hooks {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(self.hooks.iter().flatten().map(|hook| (hook.kind, &hook.range)))
+            .finish()
+    }
+}
+
+impl Hooks {
+    pub fn add(&mut self, range: RangeInclusive<u16>, kind: AccessKind, callback: HookCallback) -> usize {
+        self.hooks.push(Some(Hook { range, kind, callback }));
+        self.hooks.len() - 1
+    }
+
+    /// Returns whether there was a hook with this id.
+    pub fn remove(&mut self, id: usize) -> bool {
+        self.hooks.get_mut(id).and_then(Option::take).is_some()
+    }
+
+    pub fn any(&self, kind: AccessKind) -> bool {
+        self.hooks.iter().flatten().any(|hook| hook.kind == kind)
+    }
+
+    /// Calls every hook interested in the access of `context`.
+    pub fn call(&mut self, context: &HookContext) {
+        for hook in self.hooks.iter_mut().flatten() {
+            if hook.kind == context.access.kind && hook.range.contains(&context.access.address) {
+                (hook.callback)(context);
+            }
+        }
+    }
+}
@@ -0,0 +1,197 @@
+use crate::error::Error;
+use crate::savestate::{SaveState, StateReader, StateWriter};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Button {
+    Right = 0,
+    Left = 1,
+    Up = 2,
+    Down = 3,
+    A = 4,
+    B = 5,
+    Select = 6,
+    Start = 7,
+}
+
+impl Button {
+    pub const ALL: [Button; 8] = [
+        Button::Right,
+        Button::Left,
+        Button::Up,
+        Button::Down,
+        Button::A,
+        Button::B,
+        Button::Select,
+        Button::Start,
+    ];
+
+    pub fn mask(self) -> u8 {
+        1 << self as u8
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Button::Right => "RIGHT",
+            Button::Left => "LEFT",
+            Button::Up => "UP",
+            Button::Down => "DOWN",
+            Button::A => "A",
+            Button::B => "B",
+            Button::Select => "SELECT",
+            Button::Start => "START",
+        }
+    }
+
+    /// Parses a button name, case insensitive.
+    pub fn parse(name: &str) -> Option<Button> {
+        Button::ALL.into_iter().find(|button| button.name().eq_ignore_ascii_case(name))
+    }
+}
+
+/// The joypad register P1 (FF00h).
+#[derive(Debug, Clone)]
+pub struct Joypad {
+    /// Bits 4 and 5 of P1, a line is selected when its bit is 0.
+    select: u8,
+    /// Pressed buttons, one bit per [`Button`].
+    pressed: u8,
+}
+
+impl Default for Joypad {
+    fn default() -> Self {
+        Joypad {
+            select: 0x30,
+            pressed: 0,
+        }
+    }
+}
+
+impl Joypad {
+    pub fn pressed(&self) -> u8 {
+        self.pressed
+    }
+
+    /// Returns the lower nibble of P1, where a pressed button of a selected line reads as 0.
+    fn input_lines(&self) -> u8 {
+        let mut lines = 0;
+
+        if self.select & 0x10 == 0 {
+            lines |= self.pressed & 0x0F;
+        }
+
+        if self.select & 0x20 == 0 {
+            lines |= self.pressed >> 4;
+        }
+
+        !lines & 0x0F
+    }
+
+    /// Sets the pressed buttons, returns whether the joypad interrupt should be requested.
+    pub fn set_pressed(&mut self, pressed: u8) -> bool {
+        let old_lines = self.input_lines();
+        self.pressed = pressed;
+
+        // The interrupt is requested when an input line goes from high to low
+        old_lines & !self.input_lines() != 0
+    }
+
+    pub fn read(&self) -> u8 {
+        0xC0 | self.select | self.input_lines()
+    }
+
+    /// Changes the selected lines. Selecting a line with a button held also pulls an input line low, but no
+    /// interrupt is requested then, matching the DMG.
+    pub fn write(&mut self, value: u8) {
+        self.select = value & 0x30;
+    }
+}
+
+impl SaveState for Joypad {
+    fn save_state(&self, writer: &mut StateWriter) {
+        writer.u8(self.select);
+        writer.u8(self.pressed);
+    }
+
+    fn load_state(&mut self, reader: &mut StateReader) -> Result<(), Error> {
+        self.select = reader.u8()?;
+        self.pressed = reader.u8()?;
+
+        Ok(())
+    }
+}
+
+/// A frame-indexed input script. Each entry holds its buttons from its frame until the frame of the next one.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct InputMovie {
+    /// Frames and the buttons pressed from them on, sorted by frame.
+    entries: Vec<(u64, u8)>,
+}
+
+impl InputMovie {
+    /// Parses a script made of `frame buttons` lines, where `buttons` is a `+` separated list of button names, or
+    /// `-` to release everything. Blank lines and anything after a `#` are ignored.
+    ///
+    /// ```text
+    /// # Open the start menu
+    /// 120 START
+    /// 125 -
+    /// 200 DOWN+A
+    /// ```
+    pub fn parse(script: &str) -> Result<InputMovie, Error> {
+        let mut entries: Vec<(u64, u8)> = Vec::new();
+
+        for (index, line) in script.lines().enumerate() {
+            let line_number = index + 1;
+            let line = line.split('#').next().unwrap_or("").trim();
+
+            if line.is_empty() {
+                continue;
+            }
+
+            let invalid = |reason: &str| Error::InvalidInputMovie(line_number, reason.to_owned());
+
+            let mut fields = line.split_whitespace();
+            let frame = fields
+                .next()
+                .and_then(|frame| frame.parse::<u64>().ok())
+                .ok_or_else(|| invalid("expected a frame number"))?;
+            let buttons = fields.next().ok_or_else(|| invalid("expected buttons"))?;
+
+            if fields.next().is_some() {
+                return Err(invalid("unexpected text after the buttons"));
+            }
+
+            let mut pressed = 0;
+
+            if buttons != "-" {
+                for name in buttons.split('+') {
+                    let button = Button::parse(name).ok_or_else(|| invalid(&format!("unknown button {:?}", name)))?;
+                    pressed |= button.mask();
+                }
+            }
+
+            if let Some(&(last_frame, _)) = entries.last() {
+                if frame <= last_frame {
+                    return Err(invalid("frames must be in increasing order"));
+                }
+            }
+
+            entries.push((frame, pressed));
+        }
+
+        Ok(InputMovie { entries })
+    }
+
+    /// Returns the buttons held during `frame`.
+    pub fn buttons_at(&self, frame: u64) -> u8 {
+        match self.entries.partition_point(|&(entry_frame, _)| entry_frame <= frame) {
+            0 => 0,
+            index => self.entries[index - 1].1,
+        }
+    }
+
+    /// Returns the frame of the last entry, after which the input doesn't change anymore.
+    pub fn last_frame(&self) -> u64 {
+        self.entries.last().map_or(0, |&(frame, _)| frame)
+    }
+}
@@ -0,0 +1,20 @@
+pub mod asm;
+pub mod bus;
+pub mod cartridge;
+pub mod debug;
+pub mod disasm;
+pub mod dirty;
+pub mod dma;
+pub mod error;
+pub mod gb;
+pub mod gdb;
+pub mod hooks;
+pub mod joypad;
+pub mod link;
+pub mod ppu;
+pub mod rewind;
+pub mod savestate;
+pub mod serial;
+pub mod symbols;
+pub mod timer;
+pub mod trace;
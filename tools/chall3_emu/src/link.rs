@@ -0,0 +1,84 @@
+use crate::bus::Interrupt;
+use crate::error::EmuError;
+use crate::gb::{Gameboy, StepOutcome};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinkSide {
+    Left,
+    Right,
+}
+
+/// Two Game Boys connected by a link cable, run in lockstep in the same process.
+#[derive(Debug)]
+pub struct LinkCable {
+    pub left: Gameboy,
+    pub right: Gameboy,
+}
+
+impl LinkCable {
+    pub fn new(mut left: Gameboy, mut right: Gameboy) -> Self {
+        left.bus.serial.linked = true;
+        right.bus.serial.linked = true;
+
+        LinkCable { left, right }
+    }
+
+    /// Unplugs the cable, giving both Game Boys back.
+    pub fn unplug(mut self) -> (Gameboy, Gameboy) {
+        self.left.bus.serial.linked = false;
+        self.right.bus.serial.linked = false;
+
+        (self.left, self.right)
+    }
+
+    /// Steps the Game Boy that is behind the other one, then exchanges the bytes of any completed transfer.
+    pub fn step(&mut self) -> Result<(LinkSide, StepOutcome), EmuError> {
+        let side = if self.left.cycles() <= self.right.cycles() {
+            LinkSide::Left
+        } else {
+            LinkSide::Right
+        };
+
+        let outcome = match side {
+            LinkSide::Left => self.left.step()?,
+            LinkSide::Right => self.right.step()?,
+        };
+
+        LinkCable::exchange(&mut self.left, &mut self.right);
+        LinkCable::exchange(&mut self.right, &mut self.left);
+
+        Ok((side, outcome))
+    }
+
+    /// Runs both Game Boys until they have both been running for at least `cycles` T-cycles.
+    pub fn run_until_cycles(&mut self, cycles: u64) -> Result<(), EmuError> {
+        while self.left.cycles() < cycles || self.right.cycles() < cycles {
+            self.step()?;
+        }
+
+        Ok(())
+    }
+
+    /// Completes a transfer clocked by `master`, with `slave` on the other end of the cable, which clears the history
+    /// of both. If `slave` isn't ready for a transfer, `master` receives FFh like with nothing plugged in.
+    fn exchange(master: &mut Gameboy, slave: &mut Gameboy) {
+        let Some(byte) = master.bus.serial.take_outgoing() else {
+            return;
+        };
+
+        let received = match slave.bus.serial.receive(byte) {
+            Some(received) => {
+                slave.bus.request_interrupt(Interrupt::Serial);
+                received
+            },
+            None => 0xFF,
+        };
+
+        master.bus.serial.complete(received);
+        master.bus.request_interrupt(Interrupt::Serial);
+
+        // The byte came from outside of the steps of each side, going back over it would send it again
+        master.invalidate_history();
+        slave.invalidate_history();
+    }
+}
@@ -1,25 +1,42 @@
-mod gb;
-
+#[cfg(fuzzing)]
 #[macro_use]
 extern crate afl;
 
 use std::{fs, io, thread};
 use std::path::PathBuf;
 use clap::Parser;
+#[cfg(fuzzing)]
 use md5::Digest;
 
+use gbhttpd::cartridge::Cartridge;
+use gbhttpd::debug::{Breakpoint, StopReason};
+use gbhttpd::error::EmuError;
+use gbhttpd::gb;
+use gbhttpd::symbols::SymbolTable;
+
 #[derive(Parser, Debug)]
 #[command(version, about, long_about = None)]
 struct Args {
     #[arg(short, long)]
     rom_file_path: PathBuf,
+
+    /// Symbol file written by rgblink, the ROM path with a `.sym` extension by default. Required, the harness finds
+    /// the labels it needs in it.
+    #[arg(short, long)]
+    symbol_file_path: Option<PathBuf>,
 }
 
 #[derive(Debug)]
+#[allow(dead_code)] // Only read through the Debug impl when returned from main
 enum Error {
     FileRead(io::Error),
+    InvalidRom(gbhttpd::error::Error),
+    /// The symbol file isn't there, the harness can't find the labels it needs without it.
+    MissingSymbolFile(PathBuf),
+    InvalidSymbols(gbhttpd::error::Error),
 }
 
+#[cfg(fuzzing)]
 const KNOWN_DIGESTS: [Digest; 4] = [
     // 77152813e184f4af1fee56d86a6dc9a8
     Digest([
@@ -43,41 +60,122 @@ const KNOWN_DIGESTS: [Digest; 4] = [
     ]),
 ];
 
-fn step(gb: &mut gb::Gameboy, request: &[u8]) -> Option<String> {
-    gb.step();
+/// Value of `hDriverStatus` telling the ROM a request was written.
+const STATUS_RECEIVED_REQUEST: u8 = 0x02;
+
+/// Where the harness and the ROM meet, taken from the symbol file.
+#[derive(Debug, Clone, Copy)]
+struct HarnessSymbols {
+    /// Loop polling `status` until a request is written.
+    waiting_for_request: u16,
+    /// Loop the ROM ends up in once the response is written.
+    done: u16,
+    request: u16,
+    response: u16,
+    status: u16,
+}
 
-    if gb.registers.pc == 0x0168 {
-        // ROM is waiting for us, write the request
-        // println!("ROM is waiting for us, writing request...");
-        gb.write_bytes(0xC000, request);
-        gb.write_byte(0xFF80, 0x02);
-    } else if gb.registers.pc == 0x07D0 {
-        // ROM is done, read the response
-        // println!("ROM is done, reading response...");
+impl HarnessSymbols {
+    /// Labels of the ROM the harness looks up, in the order of the fields.
+    const LABELS: [&'static str; 5] =
+        ["EntryPoint.waitForHarness_loop", "FinalizeResponse.forever", "wRequestData", "wResponseData", "hDriverStatus"];
+
+    fn resolve(symbols: &SymbolTable) -> Result<HarnessSymbols, gbhttpd::error::Error> {
+        let [waiting_for_request, done, request, response, status] =
+            HarnessSymbols::LABELS.map(|label| symbols.resolve(label));
+
+        Ok(HarnessSymbols {
+            waiting_for_request: waiting_for_request?,
+            done: done?,
+            request: request?,
+            response: response?,
+            status: status?,
+        })
+    }
+}
+
+/// Time the ROM gets to handle a request before giving up on it, in T-cycles.
+const REQUEST_CYCLE_LIMIT: u64 = 100_000_000;
+
+/// Writes a request for the ROM waiting for it, and runs until the response is ready.
+/// Returns `None` if the ROM took too long to respond.
+fn run_request(gb: &mut gb::Gameboy, harness: &HarnessSymbols, request: &[u8]) -> Result<Option<String>, EmuError> {
+    gb.write_bytes(harness.request, request);
+    gb.write_byte(harness.status, STATUS_RECEIVED_REQUEST);
 
-        let response = gb.read_bytes(0xC800, 0x800);
+    // The breakpoint on the final loop is the only one left once booted
+    if let StopReason::Breakpoint(_) = gb.run_until(REQUEST_CYCLE_LIMIT)? {
+        // ROM is done, read the response
+        let response = gb.read_bytes(harness.response, 0x800);
         let response_cleaned = response.iter().take_while(|&&b| b != 0).cloned().collect::<Vec<u8>>();
 
-        return Some(String::from_utf8_lossy(&response_cleaned).to_string());
+        return Ok(Some(String::from_utf8_lossy(&response_cleaned).to_string()));
     }
 
-    None
+    Ok(None)
 }
 
-fn task(rom_contents: Vec<u8>) {
+/// Boots the ROM until it waits for a request, and checkpoints the system there.
+fn boot_to_harness(gb: &mut gb::Gameboy, harness: &HarnessSymbols) -> Result<gb::Checkpoint, EmuError> {
+    let waiting = gb.add_breakpoint(Breakpoint::new(harness.waiting_for_request));
+    gb.run_until(u64::MAX)?;
+    gb.remove_breakpoint(waiting);
+
+    gb.add_breakpoint(Breakpoint::new(harness.done));
+
+    Ok(gb.checkpoint())
+}
+
+fn task(cartridge: Cartridge, symbols: SymbolTable, harness: HarnessSymbols) {
     println!("Task executes on thread: {:?}", thread::current().id());
 
-    // Create a new Gameboy instance
-    let mut gb = gb::Gameboy::new();
-    gb.load_rom(rom_contents);
+    // Create a new Gameboy instance, gbhttp never executes a NOP so stop if it does
+    let mut gb = gb::Gameboy::with_config(gb::GameboyConfig {
+        suspicious_execution: gb::SuspiciousExecutionPolicy::Stop,
+        ..Default::default()
+    });
+    gb.load_cartridge(cartridge);
+    gb.load_symbols(symbols);
+
+    // Boot once, every request then starts from a clean copy of the system waiting for it
+    let checkpoint = match boot_to_harness(&mut gb, &harness) {
+        Ok(checkpoint) => checkpoint,
+        Err(error) => {
+            println!("The ROM crashed the emulator in {} while booting: {}", gb.symbolize(error.pc), error);
+            return;
+        },
+    };
+
+    #[cfg(fuzzing)]
+    {
+        // A panic ends the fuzzing run, whatever state it leaves the system in
+        let mut gb = std::panic::AssertUnwindSafe(&mut gb);
+        let checkpoint = std::panic::AssertUnwindSafe(&checkpoint);
+
+        fuzz!(|data: &[u8]| {
+            gb.restore_checkpoint(&checkpoint);
 
+            if let Ok(Some(response)) = run_request(&mut gb, &harness, data) {
+                let digest = md5::compute(response.as_bytes());
+                // println!("Digest: {:x}", digest);
+                // fs::write("fuzzing_output", &data).unwrap();
+
+                if !KNOWN_DIGESTS.contains(&digest) {
+                    panic!("Unexpected digest: {:x}", digest);
+                }
+            }
+        });
+    }
+
+    #[cfg(not(fuzzing))]
     let mut request = " /secret xxxx".as_bytes().to_vec();
+    #[cfg(not(fuzzing))]
     let request_len = request.len();
 
     #[cfg(not(fuzzing))]
     loop {
         let current = fastrand::u32(..);
-        gb.reset();
+        gb.restore_checkpoint(&checkpoint);
 
         // Set the last four bytes of the request to the current value of the loop counter
         request[request_len - 4] = (current & 0xFF) as u8;
@@ -85,21 +183,30 @@ fn task(rom_contents: Vec<u8>) {
         request[request_len - 2] = ((current >> 16) & 0xFF) as u8;
         request[request_len - 1] = ((current >> 24) & 0xFF) as u8;
 
-        loop {
-            if let Some(response) = step(&mut gb, &request) {
-                // println!("Response: {}", response);
-                // println!("Digest: {:x}", md5::compute(response.as_bytes()));
-
-                let oops = &response[131..=135];
-                if oops != "Oops!" {
-                    println!("OOPS! Found it!");
-                    println!("Request: {:?}", request);
-                    println!("Response: {}", response);
-                    println!("Current: {}", current);
-                }
-
-                break;
-            }
+        let start_cycles = gb.cycles();
+
+        let response = match run_request(&mut gb, &harness, &request) {
+            Ok(Some(response)) => response,
+            Ok(None) => {
+                println!("Request {:?} timed out", request);
+                continue;
+            },
+            Err(error) => {
+                println!("Request {:?} crashed the emulator in {}: {}", request, gb.symbolize(error.pc), error);
+                continue;
+            },
+        };
+
+        // println!("Response: {}", response);
+        // println!("Digest: {:x}", md5::compute(response.as_bytes()));
+
+        let oops = &response[131..=135];
+        if oops != "Oops!" {
+            println!("OOPS! Found it!");
+            println!("Request: {:?}", request);
+            println!("Response: {}", response);
+            println!("Current: {}", current);
+            println!("Cycles: {}", gb.cycles() - start_cycles);
         }
     }
 }
@@ -110,36 +217,39 @@ fn main() -> Result<(), Error> {
     println!("Reading ROM file from {:?}", args.rom_file_path);
 
     // Read the save file into a byte vector
-    let rom_contents = fs::read(args.rom_file_path).map_err(Error::FileRead)?;
-
-    let pool = rayon::ThreadPoolBuilder::new().num_threads(24).build().unwrap();
-
-    for i in 0..23 {
-        let rom_contents = rom_contents.clone();
-
-        pool.spawn(move || {
-            task(rom_contents);
-        });
+    let rom_contents = fs::read(&args.rom_file_path).map_err(Error::FileRead)?;
+    let cartridge = Cartridge::new(rom_contents).map_err(Error::InvalidRom)?;
+
+    let symbol_file_path = args.symbol_file_path.unwrap_or_else(|| args.rom_file_path.with_extension("sym"));
+    println!("Reading symbols from {:?}", symbol_file_path);
+
+    if !symbol_file_path.exists() {
+        println!(
+            "The harness needs a symbol file defining {}, pass it with --symbol-file-path",
+            HarnessSymbols::LABELS.join(", ")
+        );
+        return Err(Error::MissingSymbolFile(symbol_file_path));
     }
 
-    task(rom_contents);
+    let symbols = SymbolTable::load(&symbol_file_path).map_err(Error::InvalidSymbols)?;
+    let harness = HarnessSymbols::resolve(&symbols).map_err(Error::InvalidSymbols)?;
 
-    #[cfg(fuzzing)]
-    fuzz!(|data: &[u8]| {
-        loop {
-            if let Some(response) = step(&mut gb, request) {
-                let digest = md5::compute(response.as_bytes());
-                // println!("Digest: {:x}", digest);
-                // fs::write("fuzzing_output", &data).unwrap();
+    // AFL runs a single fuzzing loop per process
+    #[cfg(not(fuzzing))]
+    {
+        let pool = rayon::ThreadPoolBuilder::new().num_threads(24).build().unwrap();
 
-                if !KNOWN_DIGESTS.iter().any(|&d| d == digest) {
-                    panic!("Unexpected digest: {:x}", digest);
-                }
+        for _ in 0..23 {
+            let cartridge = cartridge.clone();
+            let symbols = symbols.clone();
 
-                break;
-            }
+            pool.spawn(move || {
+                task(cartridge, symbols, harness);
+            });
         }
-    });
+    }
+
+    task(cartridge, symbols, harness);
 
     Ok(())
 }
\ No newline at end of file
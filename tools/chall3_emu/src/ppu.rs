@@ -0,0 +1,500 @@
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+
+use crate::error::Error;
+use crate::savestate::{SaveState, StateReader, StateWriter};
+
+pub const SCREEN_WIDTH: usize = 160;
+pub const SCREEN_HEIGHT: usize = 144;
+
+const DOTS_PER_LINE: u32 = 456;
+const LINES_PER_FRAME: u8 = 154;
+const DOTS_PER_FRAME: u32 = DOTS_PER_LINE * LINES_PER_FRAME as u32;
+const OAM_SCAN_DOTS: u32 = 80;
+const DRAWING_DOTS: u32 = 172;
+
+/// Size of one VRAM bank, the CGB has two of them.
+pub const VRAM_BANK_SIZE: usize = 0x2000;
+
+/// The shades of the DMG palettes, from white to black.
+const DMG_SHADES: [[u8; 3]; 4] = [
+    [0xFF, 0xFF, 0xFF],
+    [0xAA, 0xAA, 0xAA],
+    [0x55, 0x55, 0x55],
+    [0x00, 0x00, 0x00],
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PpuMode {
+    HBlank = 0,
+    VBlank = 1,
+    OamScan = 2,
+    Drawing = 3,
+}
+
+/// Interrupts raised by the PPU during a tick.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PpuInterrupts {
+    pub vblank: bool,
+    pub stat: bool,
+    /// Not an interrupt: set when a visible line entered HBlank, which is when an HBlank DMA copies a block.
+    pub hblank: bool,
+}
+
+/// A scanline-based picture processing unit.
+#[derive(Debug, Clone)]
+pub struct Ppu {
+    lcdc: u8,
+    /// Only the interrupt selection bits (3 - 6) are stored, the rest is computed.
+    stat: u8,
+    scy: u8,
+    scx: u8,
+    ly: u8,
+    lyc: u8,
+    bgp: u8,
+    obp0: u8,
+    obp1: u8,
+    wy: u8,
+    wx: u8,
+    /// Set in CGB mode, which renders with VRAM bank 1 attributes and the color palettes.
+    cgb: bool,
+    /// BG palette index (FF68h), bit 7 enables auto-increment on writes to BCPD.
+    bcps: u8,
+    /// 8 BG palettes of 4 RGB555 colors.
+    bg_palettes: [u8; 64],
+    /// OBJ palette index (FF6Ah), bit 7 enables auto-increment on writes to OCPD.
+    ocps: u8,
+    /// 8 OBJ palettes of 4 RGB555 colors.
+    obj_palettes: [u8; 64],
+    mode: PpuMode,
+    /// Dots elapsed in the current line, or in the current frame while the LCD is off.
+    dot: u32,
+    /// Line of the window to draw next, only incremented on lines where the window is visible.
+    window_line: u8,
+    /// State of the STAT interrupt line, the interrupt is only requested on a rising edge.
+    stat_line: bool,
+    /// Frame being drawn, as RGB triplets.
+    back_buffer: Vec<u8>,
+    /// Last complete frame, as RGB triplets.
+    front_buffer: Vec<u8>,
+    frame: u64,
+}
+
+impl Default for Ppu {
+    fn default() -> Self {
+        Ppu {
+            lcdc: 0x91,
+            stat: 0x00,
+            scy: 0,
+            scx: 0,
+            ly: 0,
+            lyc: 0,
+            bgp: 0xFC,
+            obp0: 0xFF,
+            obp1: 0xFF,
+            wy: 0,
+            wx: 0,
+            cgb: false,
+            bcps: 0,
+            // The CGB boot ROM leaves every color white for CGB games
+            bg_palettes: [0xFF; 64],
+            ocps: 0,
+            obj_palettes: [0xFF; 64],
+            mode: PpuMode::OamScan,
+            dot: 0,
+            window_line: 0,
+            stat_line: false,
+            back_buffer: vec![0xFF; SCREEN_WIDTH * SCREEN_HEIGHT * 3],
+            front_buffer: vec![0xFF; SCREEN_WIDTH * SCREEN_HEIGHT * 3],
+            frame: 0,
+        }
+    }
+}
+
+impl Ppu {
+    fn lcd_enabled(&self) -> bool {
+        self.lcdc & 0x80 != 0
+    }
+
+    pub fn set_cgb_mode(&mut self, cgb: bool) {
+        self.cgb = cgb;
+    }
+
+    pub fn mode(&self) -> PpuMode {
+        self.mode
+    }
+
+    pub fn ly(&self) -> u8 {
+        self.ly
+    }
+
+    /// Number of frames completed since power on.
+    pub fn frame(&self) -> u64 {
+        self.frame
+    }
+
+    /// Returns the last complete frame, as 160x144 RGB triplets.
+    pub fn framebuffer(&self) -> &[u8] {
+        &self.front_buffer
+    }
+
+    pub fn read(&self, address: u16) -> u8 {
+        match address {
+            0xFF40 => self.lcdc,
+            0xFF41 => {
+                let coincidence = ((self.ly == self.lyc) as u8) << 2;
+                let mode = if self.lcd_enabled() { self.mode as u8 } else { 0 };
+
+                0x80 | self.stat | coincidence | mode
+            },
+            0xFF42 => self.scy,
+            0xFF43 => self.scx,
+            0xFF44 => self.ly,
+            0xFF45 => self.lyc,
+            0xFF47 => self.bgp,
+            0xFF48 => self.obp0,
+            0xFF49 => self.obp1,
+            0xFF4A => self.wy,
+            0xFF4B => self.wx,
+            // The palette registers don't exist on the DMG
+            _ if !self.cgb => 0xFF,
+            0xFF68 => self.bcps | 0x40,
+            0xFF69 => self.bg_palettes[(self.bcps & 0x3F) as usize],
+            0xFF6A => self.ocps | 0x40,
+            _ => self.obj_palettes[(self.ocps & 0x3F) as usize],
+        }
+    }
+
+    pub fn write(&mut self, address: u16, value: u8) {
+        match address {
+            0xFF40 => {
+                let was_enabled = self.lcd_enabled();
+                self.lcdc = value;
+
+                if was_enabled && !self.lcd_enabled() {
+                    // Turning the LCD off resets the PPU to the start of the frame
+                    self.ly = 0;
+                    self.dot = 0;
+                    self.window_line = 0;
+                    self.mode = PpuMode::HBlank;
+                } else if !was_enabled && self.lcd_enabled() {
+                    self.dot = 0;
+                    self.mode = PpuMode::OamScan;
+                }
+            },
+            0xFF41 => self.stat = value & 0x78,
+            0xFF42 => self.scy = value,
+            0xFF43 => self.scx = value,
+            // LY is read-only
+            0xFF44 => {},
+            0xFF45 => self.lyc = value,
+            0xFF47 => self.bgp = value,
+            0xFF48 => self.obp0 = value,
+            0xFF49 => self.obp1 = value,
+            0xFF4A => self.wy = value,
+            0xFF4B => self.wx = value,
+            _ if !self.cgb => {},
+            0xFF68 => self.bcps = value & 0xBF,
+            0xFF69 => Self::write_palette(&mut self.bg_palettes, &mut self.bcps, value),
+            0xFF6A => self.ocps = value & 0xBF,
+            _ => Self::write_palette(&mut self.obj_palettes, &mut self.ocps, value),
+        }
+    }
+
+    /// Writes to BCPD or OCPD, at the index in BCPS or OCPS which gets incremented if auto-increment is set.
+    fn write_palette(palette_ram: &mut [u8; 64], index: &mut u8, value: u8) {
+        palette_ram[(*index & 0x3F) as usize] = value;
+
+        if *index & 0x80 != 0 {
+            *index = 0x80 | (*index + 1) & 0x3F;
+        }
+    }
+
+    /// Advances the PPU by `cycles` dots.
+    pub fn tick(&mut self, cycles: u32, vram: &[u8], oam: &[u8]) -> PpuInterrupts {
+        let mut interrupts = PpuInterrupts::default();
+
+        if !self.lcd_enabled() {
+            // Frames keep their pace with the LCD off, the screen is blank and there is no VBlank
+            self.dot += cycles;
+
+            while self.dot >= DOTS_PER_FRAME {
+                self.dot -= DOTS_PER_FRAME;
+                self.frame += 1;
+                self.front_buffer.fill(0xFF);
+            }
+
+            return interrupts;
+        }
+
+        for _ in 0..cycles {
+            self.dot += 1;
+
+            if self.ly < SCREEN_HEIGHT as u8 {
+                if self.dot == OAM_SCAN_DOTS {
+                    self.mode = PpuMode::Drawing;
+                } else if self.dot == OAM_SCAN_DOTS + DRAWING_DOTS {
+                    self.render_line(vram, oam);
+                    self.mode = PpuMode::HBlank;
+                    interrupts.hblank = true;
+                }
+            }
+
+            if self.dot == DOTS_PER_LINE {
+                self.dot = 0;
+                self.ly += 1;
+
+                if self.ly == LINES_PER_FRAME {
+                    self.ly = 0;
+                    self.window_line = 0;
+                }
+
+                if self.ly == SCREEN_HEIGHT as u8 {
+                    self.mode = PpuMode::VBlank;
+                    self.frame += 1;
+                    self.front_buffer.copy_from_slice(&self.back_buffer);
+                    interrupts.vblank = true;
+                } else if self.ly < SCREEN_HEIGHT as u8 {
+                    self.mode = PpuMode::OamScan;
+                }
+            }
+
+            // The STAT interrupt is requested when any of the selected conditions becomes true
+            let stat_line = (self.stat & 0x40 != 0 && self.ly == self.lyc)
+                || (self.stat & 0x20 != 0 && self.mode == PpuMode::OamScan)
+                || (self.stat & 0x10 != 0 && self.mode == PpuMode::VBlank)
+                || (self.stat & 0x08 != 0 && self.mode == PpuMode::HBlank);
+
+            if stat_line && !self.stat_line {
+                interrupts.stat = true;
+            }
+
+            self.stat_line = stat_line;
+        }
+
+        interrupts
+    }
+
+    /// Returns the 2-bit color of a pixel in a tile, `tile_address` being relative to 8000h.
+    fn tile_pixel(vram: &[u8], tile_address: usize, x: u8, y: u8) -> u8 {
+        let low = vram[tile_address + y as usize * 2];
+        let high = vram[tile_address + y as usize * 2 + 1];
+        let bit = 7 - x;
+
+        ((high >> bit) & 0x01) << 1 | ((low >> bit) & 0x01)
+    }
+
+    /// Returns the address of a BG/window tile relative to 8000h, following the addressing mode in LCDC.
+    fn bg_tile_address(&self, tile: u8) -> usize {
+        if self.lcdc & 0x10 != 0 {
+            tile as usize * 16
+        } else {
+            (0x1000 + (tile as i8 as i32) * 16) as usize
+        }
+    }
+
+    /// Converts a color of the CGB palette RAM, stored as little endian RGB555, to RGB.
+    fn cgb_color(palette_ram: &[u8; 64], palette: u8, color: u8) -> [u8; 3] {
+        let index = palette as usize * 8 + color as usize * 2;
+        let value = u16::from_le_bytes([palette_ram[index], palette_ram[index + 1]]);
+
+        [value, value >> 5, value >> 10].map(|component| {
+            let component = (component & 0x1F) as u8;
+            component << 3 | component >> 2
+        })
+    }
+
+    fn render_line(&mut self, vram: &[u8], oam: &[u8]) {
+        let ly = self.ly;
+        // 2-bit color of the BG/window, and whether its CGB attributes give it priority over sprites
+        let mut bg_colors = [0u8; SCREEN_WIDTH];
+        let mut bg_priorities = [false; SCREEN_WIDTH];
+        let mut line = [DMG_SHADES[0]; SCREEN_WIDTH];
+
+        // Background and window. On the CGB, LCDC bit 0 doesn't hide them, it takes their priority away instead.
+        let window_visible = self.lcdc & 0x20 != 0 && self.wy <= ly && self.wx <= 166;
+        let bg_enabled = self.cgb || self.lcdc & 0x01 != 0;
+
+        if bg_enabled {
+            for x in 0..SCREEN_WIDTH {
+                let in_window = window_visible && x as i16 >= self.wx as i16 - 7;
+
+                let (map_base, map_x, map_y) = if in_window {
+                    let map_base = if self.lcdc & 0x40 != 0 { 0x1C00 } else { 0x1800 };
+                    (map_base, (x as i16 - (self.wx as i16 - 7)) as u8, self.window_line)
+                } else {
+                    let map_base = if self.lcdc & 0x08 != 0 { 0x1C00 } else { 0x1800 };
+                    (map_base, self.scx.wrapping_add(x as u8), self.scy.wrapping_add(ly))
+                };
+
+                let map_address = map_base + (map_y as usize / 8) * 32 + map_x as usize / 8;
+                let tile = vram[map_address];
+
+                if self.cgb {
+                    // The attributes of a tile are at the same address in VRAM bank 1
+                    let attributes = vram[VRAM_BANK_SIZE + map_address];
+                    let bank = if attributes & 0x08 != 0 { VRAM_BANK_SIZE } else { 0 };
+                    let pixel_x = if attributes & 0x20 != 0 { 7 - map_x % 8 } else { map_x % 8 };
+                    let pixel_y = if attributes & 0x40 != 0 { 7 - map_y % 8 } else { map_y % 8 };
+
+                    bg_colors[x] = Self::tile_pixel(vram, bank + self.bg_tile_address(tile), pixel_x, pixel_y);
+                    bg_priorities[x] = attributes & 0x80 != 0;
+                    line[x] = Self::cgb_color(&self.bg_palettes, attributes & 0x07, bg_colors[x]);
+                } else {
+                    bg_colors[x] = Self::tile_pixel(vram, self.bg_tile_address(tile), map_x % 8, map_y % 8);
+                    line[x] = DMG_SHADES[((self.bgp >> (bg_colors[x] * 2)) & 0x03) as usize];
+                }
+            }
+
+            if window_visible {
+                self.window_line += 1;
+            }
+        }
+
+        // Sprites
+        if self.lcdc & 0x02 != 0 {
+            let height = if self.lcdc & 0x04 != 0 { 16 } else { 8 };
+
+            // Only the first 10 sprites on the line in OAM order are drawn
+            let mut sprites = oam
+                .chunks_exact(4)
+                .enumerate()
+                .filter(|(_, sprite)| {
+                    let top = sprite[0] as i16 - 16;
+                    (top..top + height).contains(&(ly as i16))
+                })
+                .take(10)
+                .collect::<Vec<_>>();
+
+            // On the DMG, sprites with a smaller X are drawn on top, then the ones first in OAM. The CGB only looks at
+            // the OAM order. Draw them in reverse.
+            if !self.cgb {
+                sprites.sort_by_key(|(i, sprite)| (sprite[1], *i));
+            }
+
+            let bg_master_priority = !self.cgb || self.lcdc & 0x01 != 0;
+
+            for (_, sprite) in sprites.iter().rev() {
+                let (y, x, mut tile, attributes) = (sprite[0], sprite[1], sprite[2], sprite[3]);
+                let mut row = (ly as i16 - (y as i16 - 16)) as u8;
+
+                if attributes & 0x40 != 0 {
+                    row = height as u8 - 1 - row;
+                }
+
+                if height == 16 {
+                    tile &= 0xFE;
+                }
+
+                let bank = if self.cgb && attributes & 0x08 != 0 { VRAM_BANK_SIZE } else { 0 };
+
+                for column in 0..8u8 {
+                    let screen_x = x as i16 - 8 + column as i16;
+
+                    if !(0..SCREEN_WIDTH as i16).contains(&screen_x) {
+                        continue;
+                    }
+
+                    let screen_x = screen_x as usize;
+                    let pixel_x = if attributes & 0x20 != 0 { 7 - column } else { column };
+                    let color = Self::tile_pixel(vram, bank + tile as usize * 16, pixel_x, row);
+
+                    // Color 0 is transparent, and the BG can be given priority over the sprite
+                    let behind_bg = (attributes & 0x80 != 0 || bg_priorities[screen_x]) && bg_colors[screen_x] != 0;
+
+                    if color == 0 || (bg_master_priority && behind_bg) {
+                        continue;
+                    }
+
+                    line[screen_x] = if self.cgb {
+                        Self::cgb_color(&self.obj_palettes, attributes & 0x07, color)
+                    } else {
+                        let palette = if attributes & 0x10 != 0 { self.obp1 } else { self.obp0 };
+                        DMG_SHADES[((palette >> (color * 2)) & 0x03) as usize]
+                    };
+                }
+            }
+        }
+
+        let offset = ly as usize * SCREEN_WIDTH * 3;
+
+        for (i, pixel) in line.iter().enumerate() {
+            self.back_buffer[offset + i * 3..offset + i * 3 + 3].copy_from_slice(pixel);
+        }
+    }
+}
+
+impl SaveState for Ppu {
+    fn save_state(&self, writer: &mut StateWriter) {
+        for register in [
+            self.lcdc, self.stat, self.scy, self.scx, self.ly, self.lyc, self.bgp, self.obp0, self.obp1, self.wy, self.wx,
+        ] {
+            writer.u8(register);
+        }
+
+        writer.bool(self.cgb);
+        writer.u8(self.bcps);
+        writer.bytes(&self.bg_palettes);
+        writer.u8(self.ocps);
+        writer.bytes(&self.obj_palettes);
+        writer.u8(self.mode as u8);
+        writer.u32(self.dot);
+        writer.u8(self.window_line);
+        writer.bool(self.stat_line);
+        writer.bytes(&self.back_buffer);
+        writer.bytes(&self.front_buffer);
+        writer.u64(self.frame);
+    }
+
+    fn load_state(&mut self, reader: &mut StateReader) -> Result<(), Error> {
+        for register in [
+            &mut self.lcdc,
+            &mut self.stat,
+            &mut self.scy,
+            &mut self.scx,
+            &mut self.ly,
+            &mut self.lyc,
+            &mut self.bgp,
+            &mut self.obp0,
+            &mut self.obp1,
+            &mut self.wy,
+            &mut self.wx,
+        ] {
+            *register = reader.u8()?;
+        }
+
+        self.cgb = reader.bool()?;
+        self.bcps = reader.u8()?;
+        reader.bytes_into(&mut self.bg_palettes)?;
+        self.ocps = reader.u8()?;
+        reader.bytes_into(&mut self.obj_palettes)?;
+        self.mode = match reader.u8()? {
+            0 => PpuMode::HBlank,
+            1 => PpuMode::VBlank,
+            2 => PpuMode::OamScan,
+            3 => PpuMode::Drawing,
+            _ => return Err(Error::InvalidSnapshot("invalid PPU mode")),
+        };
+        self.dot = reader.u32()?;
+        self.window_line = reader.u8()?;
+        self.stat_line = reader.bool()?;
+        reader.bytes_into(&mut self.back_buffer)?;
+        reader.bytes_into(&mut self.front_buffer)?;
+        self.frame = reader.u64()?;
+
+        Ok(())
+    }
+}
+
+/// Writes a framebuffer returned by [`Ppu::framebuffer`] to a PNG file.
+pub fn write_png(path: &Path, framebuffer: &[u8]) -> Result<(), Error> {
+    let file = File::create(path).map_err(Error::IoError)?;
+
+    let mut encoder = png::Encoder::new(BufWriter::new(file), SCREEN_WIDTH as u32, SCREEN_HEIGHT as u32);
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+
+    let mut writer = encoder.write_header().map_err(Error::PngError)?;
+    writer.write_image_data(framebuffer).map_err(Error::PngError)
+}
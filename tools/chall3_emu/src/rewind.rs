@@ -0,0 +1,189 @@
+use std::collections::VecDeque;
+use std::ops::RangeInclusive;
+
+use crate::debug::AccessKind;
+use crate::gb::GameboyRegisters;
+use crate::hooks::MemoryAccess;
+use crate::savestate::Snapshot;
+
+/// How much execution history [`crate::gb::Gameboy::enable_history`] keeps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HistoryConfig {
+    /// Number of steps kept, the oldest ones are forgotten first.
+    pub capacity: usize,
+    /// Steps between two snapshots. Going back replays at most this many steps from the closest snapshot.
+    pub snapshot_interval: u64,
+}
+
+impl Default for HistoryConfig {
+    fn default() -> Self {
+        HistoryConfig {
+            capacity: 1_000_000,
+            snapshot_interval: 10_000,
+        }
+    }
+}
+
+/// A step of the CPU: an instruction, an interrupt dispatch, or a cycle spent halted or stopped.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HistoryEntry {
+    /// Number of steps recorded before this one since the history was enabled or cleared.
+    pub index: u64,
+    /// The registers right before the step.
+    pub registers: GameboyRegisters,
+    /// The byte at PC right before the step.
+    pub opcode: u8,
+    /// T-cycles elapsed since power on, right before the step.
+    pub cycles: u64,
+    /// Bytes written by the CPU during the step, in order.
+    pub writes: Vec<MemoryAccess>,
+}
+
+impl HistoryEntry {
+    /// Returns the last write of the step to an address in a range.
+    pub fn last_write(&self, range: &RangeInclusive<u16>) -> Option<&MemoryAccess> {
+        self.writes.iter().rev().find(|access| range.contains(&access.address))
+    }
+}
+
+/// Ring buffer of the last steps executed, with periodic snapshots to go back to any of them by replaying from the
+/// closest snapshot before it.
+#[derive(Debug)]
+pub struct History {
+    config: HistoryConfig,
+    entries: VecDeque<HistoryEntry>,
+    /// Snapshots taken right before the step with the given index, oldest first.
+    snapshots: VecDeque<(u64, Snapshot)>,
+    /// Index of the next step to be recorded.
+    position: u64,
+}
+
+impl History {
+    pub fn new(config: HistoryConfig) -> Self {
+        History {
+            config: HistoryConfig {
+                capacity: config.capacity.max(1),
+                snapshot_interval: config.snapshot_interval.max(1),
+            },
+            entries: VecDeque::new(),
+            snapshots: VecDeque::new(),
+            position: 0,
+        }
+    }
+
+    pub fn config(&self) -> HistoryConfig {
+        self.config
+    }
+
+    /// Forgets everything, for when the state is replaced from the outside.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.snapshots.clear();
+        self.position = 0;
+    }
+
+    /// Returns the index of the next step to be recorded, which is the number of steps recorded so far.
+    pub fn position(&self) -> u64 {
+        self.position
+    }
+
+    /// Returns the index of the oldest step that can be gone back to.
+    pub fn oldest(&self) -> u64 {
+        self.entries.front().map_or(self.position, |entry| entry.index)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the step with this index, if it's still in the history.
+    pub fn get(&self, index: u64) -> Option<&HistoryEntry> {
+        let offset = index.checked_sub(self.oldest())?;
+        self.entries.get(usize::try_from(offset).ok()?)
+    }
+
+    /// Returns the step `steps` steps ago: 1 is the last step executed.
+    pub fn ago(&self, steps: u64) -> Option<&HistoryEntry> {
+        self.get(self.position.checked_sub(steps)?)
+    }
+
+    /// Returns the steps from the oldest to the last one.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &HistoryEntry> {
+        self.entries.iter()
+    }
+
+    /// Returns the last step that wrote to an address in a range, with the write.
+    pub fn last_write(&self, range: RangeInclusive<u16>) -> Option<(&HistoryEntry, &MemoryAccess)> {
+        self.entries
+            .iter()
+            .rev()
+            .find_map(|entry| entry.last_write(&range).map(|access| (entry, access)))
+    }
+
+    /// Returns whether a snapshot should be taken before the next step is recorded.
+    pub fn needs_snapshot(&self) -> bool {
+        match self.snapshots.back() {
+            None => true,
+            // Going back to a snapshot keeps it, it doesn't need to be taken again
+            Some((position, _)) => {
+                *position != self.position && self.position.is_multiple_of(self.config.snapshot_interval)
+            },
+        }
+    }
+
+    /// Saves the state right before the next step.
+    pub fn push_snapshot(&mut self, snapshot: Snapshot) {
+        self.snapshots.push_back((self.position, snapshot));
+    }
+
+    /// Records a step, forgetting the oldest one when the history is full.
+    pub fn push(&mut self, registers: GameboyRegisters, opcode: u8, cycles: u64, writes: Vec<MemoryAccess>) {
+        debug_assert!(writes.iter().all(|access| access.kind == AccessKind::Write));
+
+        if self.entries.len() >= self.config.capacity {
+            self.entries.pop_front();
+        }
+
+        self.entries.push_back(HistoryEntry {
+            index: self.position,
+            registers,
+            opcode,
+            cycles,
+            writes,
+        });
+        self.position += 1;
+
+        // A snapshot is only needed while it's the closest one before the oldest step
+        let oldest = self.oldest();
+
+        while self.snapshots.len() > 1 && self.snapshots[1].0 <= oldest {
+            self.snapshots.pop_front();
+        }
+    }
+
+    /// Returns the closest snapshot before a step, and the index of the step it was taken before.
+    pub(crate) fn snapshot_before(&self, index: u64) -> Option<(u64, &Snapshot)> {
+        self.snapshots
+            .iter()
+            .rev()
+            .find(|(position, _)| *position <= index)
+            .map(|(position, snapshot)| (*position, snapshot))
+    }
+
+    /// Forgets the steps from `index` on, once the state went back to right before it.
+    pub(crate) fn truncate(&mut self, index: u64) {
+        while self.entries.back().is_some_and(|entry| entry.index >= index) {
+            self.entries.pop_back();
+        }
+
+        while self.snapshots.back().is_some_and(|(position, _)| *position > index) {
+            self.snapshots.pop_back();
+        }
+
+        self.position = index;
+    }
+}
@@ -0,0 +1,168 @@
+use std::fs;
+use std::path::Path;
+
+use crate::error::Error;
+
+/// Identifies a save state file.
+const MAGIC: &[u8; 4] = b"GBSS";
+
+/// Version of the save state format, to be bumped whenever the layout of any component changes.
+pub const VERSION: u32 = 1;
+
+/// A component whose state can be saved and restored.
+pub trait SaveState {
+    fn save_state(&self, writer: &mut StateWriter);
+
+    /// Restores the state written by [`SaveState::save_state`]. The component is left in an unspecified state if
+    /// this fails.
+    fn load_state(&mut self, reader: &mut StateReader) -> Result<(), Error>;
+}
+
+/// Serializes state as little endian values.
+#[derive(Debug, Default)]
+pub struct StateWriter {
+    data: Vec<u8>,
+}
+
+impl StateWriter {
+    pub fn u8(&mut self, value: u8) {
+        self.data.push(value);
+    }
+
+    pub fn bool(&mut self, value: bool) {
+        self.u8(value as u8);
+    }
+
+    pub fn u16(&mut self, value: u16) {
+        self.data.extend_from_slice(&value.to_le_bytes());
+    }
+
+    pub fn u32(&mut self, value: u32) {
+        self.data.extend_from_slice(&value.to_le_bytes());
+    }
+
+    pub fn u64(&mut self, value: u64) {
+        self.data.extend_from_slice(&value.to_le_bytes());
+    }
+
+    /// Writes a length-prefixed byte buffer.
+    pub fn bytes(&mut self, bytes: &[u8]) {
+        self.u32(bytes.len() as u32);
+        self.data.extend_from_slice(bytes);
+    }
+}
+
+/// Deserializes what a [`StateWriter`] wrote.
+#[derive(Debug)]
+pub struct StateReader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> StateReader<'a> {
+    fn take(&mut self, length: usize) -> Result<&'a [u8], Error> {
+        if self.data.len() < length {
+            return Err(Error::InvalidSnapshot("truncated data"));
+        }
+
+        let (taken, rest) = self.data.split_at(length);
+        self.data = rest;
+
+        Ok(taken)
+    }
+
+    pub fn u8(&mut self) -> Result<u8, Error> {
+        Ok(self.take(1)?[0])
+    }
+
+    pub fn bool(&mut self) -> Result<bool, Error> {
+        Ok(self.u8()? != 0)
+    }
+
+    pub fn u16(&mut self) -> Result<u16, Error> {
+        Ok(u16::from_le_bytes(self.take(2)?.try_into().unwrap()))
+    }
+
+    pub fn u32(&mut self) -> Result<u32, Error> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    pub fn u64(&mut self) -> Result<u64, Error> {
+        Ok(u64::from_le_bytes(self.take(8)?.try_into().unwrap()))
+    }
+
+    /// Reads a length-prefixed byte buffer.
+    pub fn bytes(&mut self) -> Result<&'a [u8], Error> {
+        let length = self.u32()? as usize;
+        self.take(length)
+    }
+
+    /// Reads a length-prefixed byte buffer into `buffer`, whose size must match.
+    pub fn bytes_into(&mut self, buffer: &mut [u8]) -> Result<(), Error> {
+        let bytes = self.bytes()?;
+
+        if bytes.len() != buffer.len() {
+            return Err(Error::InvalidSnapshot("buffer size mismatch"));
+        }
+
+        buffer.copy_from_slice(bytes);
+
+        Ok(())
+    }
+}
+
+/// The serialized state of a whole [`crate::gb::Gameboy`], see [`crate::gb::Gameboy::snapshot`].
+/// The ROM isn't part of it, only a fingerprint of it to make sure the state is restored with the same game.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Snapshot {
+    data: Vec<u8>,
+}
+
+impl Snapshot {
+    /// Wraps the state of a Game Boy behind the header of the format.
+    pub(crate) fn new(save: impl FnOnce(&mut StateWriter)) -> Self {
+        let mut writer = StateWriter::default();
+        writer.data.extend_from_slice(MAGIC);
+        writer.u32(VERSION);
+
+        save(&mut writer);
+
+        Snapshot { data: writer.data }
+    }
+
+    /// Checks the header of a serialized snapshot.
+    pub fn from_bytes(data: Vec<u8>) -> Result<Self, Error> {
+        let snapshot = Snapshot { data };
+        snapshot.reader()?;
+
+        Ok(snapshot)
+    }
+
+    /// Returns a reader past the header.
+    pub(crate) fn reader(&self) -> Result<StateReader<'_>, Error> {
+        let mut reader = StateReader { data: &self.data };
+
+        if reader.take(MAGIC.len())? != MAGIC {
+            return Err(Error::InvalidSnapshot("not a save state"));
+        }
+
+        let version = reader.u32()?;
+
+        if version != VERSION {
+            return Err(Error::UnsupportedSnapshotVersion(version));
+        }
+
+        Ok(reader)
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.data
+    }
+
+    pub fn load(path: &Path) -> Result<Self, Error> {
+        Snapshot::from_bytes(fs::read(path).map_err(Error::IoError)?)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), Error> {
+        fs::write(path, &self.data).map_err(Error::IoError)
+    }
+}
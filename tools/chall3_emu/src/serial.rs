@@ -0,0 +1,161 @@
+use std::io::{self, Write};
+
+use crate::error::Error;
+use crate::savestate::{SaveState, StateReader, StateWriter};
+
+/// Where the bytes sent over the serial port end up.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum SerialOutput {
+    /// Bytes are dropped.
+    #[default]
+    Discard,
+    /// Bytes are collected, test ROMs report their results this way.
+    Capture(Vec<u8>),
+    /// Bytes are written to stdout as they are sent.
+    Stdout,
+}
+
+/// The serial port (FF01h - FF02h).
+#[derive(Debug, Clone, Default)]
+pub struct Serial {
+    sb: u8,
+    sc: u8,
+    /// T-cycles left before the transfer in progress completes, 0 if there is none.
+    remaining: u32,
+    pub output: SerialOutput,
+    /// Set when a [`crate::link::LinkCable`] is plugged in, transfers then wait for the other side.
+    pub linked: bool,
+    /// Set when a transfer clocked by this side completed, but the byte hasn't been exchanged yet.
+    awaiting_peer: bool,
+}
+
+impl Serial {
+    /// Length of a transfer with the internal clock: 8 bits at 8192 Hz.
+    pub const TRANSFER_CYCLES: u32 = 4096;
+
+    /// Returns the bytes captured so far, empty if the output isn't [`SerialOutput::Capture`].
+    pub fn captured(&self) -> &[u8] {
+        match &self.output {
+            SerialOutput::Capture(bytes) => bytes,
+            _ => &[],
+        }
+    }
+
+    fn uses_internal_clock(&self) -> bool {
+        self.sc & 0x01 != 0
+    }
+
+    fn transferring(&self) -> bool {
+        self.sc & 0x80 != 0
+    }
+
+    fn send(&mut self, byte: u8) {
+        match &mut self.output {
+            SerialOutput::Discard => {},
+            SerialOutput::Capture(bytes) => bytes.push(byte),
+            SerialOutput::Stdout => {
+                let mut stdout = io::stdout();
+                let _ = stdout.write_all(&[byte]);
+                let _ = stdout.flush();
+            },
+        }
+    }
+
+    /// Ends the transfer in progress, `received` being the byte shifted in from the other side.
+    pub fn complete(&mut self, received: u8) {
+        self.sb = received;
+        self.sc &= 0x7F;
+        self.remaining = 0;
+        self.awaiting_peer = false;
+    }
+
+    /// Advances the serial port by `cycles` T-cycles, returns whether the serial interrupt should be requested.
+    pub fn tick(&mut self, cycles: u32) -> bool {
+        if self.remaining == 0 {
+            return false;
+        }
+
+        self.remaining = self.remaining.saturating_sub(cycles);
+
+        if self.remaining != 0 {
+            return false;
+        }
+
+        if self.linked {
+            self.awaiting_peer = true;
+            false
+        } else {
+            // Nothing is plugged in, the data line stays high
+            self.complete(0xFF);
+            true
+        }
+    }
+
+    /// Returns the byte to send to the other side if a transfer clocked by this side is waiting for it.
+    pub fn take_outgoing(&mut self) -> Option<u8> {
+        if self.awaiting_peer {
+            self.awaiting_peer = false;
+            Some(self.sb)
+        } else {
+            None
+        }
+    }
+
+    /// Handles a byte clocked in by the other side. If this side is ready for a transfer with the external clock,
+    /// the transfer completes and the byte that was in SB is returned.
+    pub fn receive(&mut self, byte: u8) -> Option<u8> {
+        if !self.transferring() || self.uses_internal_clock() {
+            return None;
+        }
+
+        let sent = self.sb;
+        self.send(sent);
+        self.complete(byte);
+
+        Some(sent)
+    }
+
+    pub fn read(&self, address: u16) -> u8 {
+        match address {
+            0xFF01 => self.sb,
+            _ => self.sc | 0x7E,
+        }
+    }
+
+    pub fn write(&mut self, address: u16, value: u8) {
+        match address {
+            0xFF01 => self.sb = value,
+            _ => {
+                self.sc = value & 0x81;
+
+                if self.transferring() && self.uses_internal_clock() {
+                    let byte = self.sb;
+                    self.send(byte);
+                    self.remaining = Serial::TRANSFER_CYCLES;
+                    self.awaiting_peer = false;
+                } else {
+                    self.remaining = 0;
+                }
+            },
+        }
+    }
+}
+
+/// The output sink and the link cable are left alone, they aren't part of the emulated state.
+impl SaveState for Serial {
+    fn save_state(&self, writer: &mut StateWriter) {
+        writer.u8(self.sb);
+        writer.u8(self.sc);
+        writer.u32(self.remaining);
+        writer.bool(self.awaiting_peer);
+    }
+
+    fn load_state(&mut self, reader: &mut StateReader) -> Result<(), Error> {
+        self.sb = reader.u8()?;
+        self.sc = reader.u8()?;
+        self.remaining = reader.u32()?;
+        self.awaiting_peer = reader.bool()?;
+
+        Ok(())
+    }
+}
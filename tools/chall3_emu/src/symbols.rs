@@ -0,0 +1,231 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use crate::error::Error;
+
+/// A label from a symbol file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Symbol {
+    /// ROM, VRAM, SRAM or WRAM bank the label is in, 0 for unbanked memory.
+    pub bank: usize,
+    pub address: u16,
+    /// Full name of the label, `Parent.local` for local labels.
+    pub name: String,
+}
+
+/// Labels loaded from an rgblink `.sym` file, used to resolve names to addresses and back.
+#[derive(Debug, Clone, Default)]
+pub struct SymbolTable {
+    /// Sorted by address, labels at the same address keep the order of the file.
+    symbols: Vec<Symbol>,
+    /// Index in `symbols` of every name.
+    names: HashMap<String, usize>,
+}
+
+/// Returns the start of the memory area an address is in. Labels aren't used for addresses of another area, where
+/// the offset would be meaningless.
+fn area_start(address: u16) -> u16 {
+    match address {
+        0x0000..=0x3FFF => 0x0000,
+        0x4000..=0x7FFF => 0x4000,
+        0x8000..=0x9FFF => 0x8000,
+        0xA000..=0xBFFF => 0xA000,
+        0xC000..=0xCFFF => 0xC000,
+        0xD000..=0xDFFF => 0xD000,
+        0xE000..=0xFDFF => 0xE000,
+        0xFE00..=0xFE9F => 0xFE00,
+        0xFEA0..=0xFEFF => 0xFEA0,
+        0xFF00..=0xFF7F => 0xFF00,
+        0xFF80..=0xFFFE => 0xFF80,
+        0xFFFF => 0xFFFF,
+    }
+}
+
+/// Parses a number the way RGBDS does: decimal unless prefixed with `$` or `0x` for hexadecimal, or `%` for binary.
+/// `07D0h` is accepted too, the only form with a suffix.
+pub fn parse_number(text: &str) -> Option<u16> {
+    let (digits, radix) = if let Some(digits) = text.strip_prefix('$') {
+        (digits, 16)
+    } else if let Some(digits) = text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+        (digits, 16)
+    } else if let Some(digits) = text.strip_suffix('h').or_else(|| text.strip_suffix('H')) {
+        (digits, 16)
+    } else if let Some(digits) = text.strip_prefix('%') {
+        (digits, 2)
+    } else {
+        (text, 10)
+    };
+
+    if digits.is_empty() {
+        return None;
+    }
+
+    u16::from_str_radix(digits, radix).ok()
+}
+
+impl SymbolTable {
+    /// Parses the lines of a `.sym` file, `bank:address label` where both numbers are hexadecimal. Comments start
+    /// with `;`.
+    pub fn parse(text: &str) -> Result<SymbolTable, Error> {
+        let mut symbols = Vec::new();
+
+        for (index, line) in text.lines().enumerate() {
+            let line_number = index + 1;
+            let line = line.split(';').next().unwrap_or("").trim();
+
+            if line.is_empty() {
+                continue;
+            }
+
+            let invalid = |reason: &str| Error::InvalidSymbolFile(line_number, reason.to_owned());
+
+            let mut fields = line.split_whitespace();
+            let location = fields.next().ok_or_else(|| invalid("expected a bank and an address"))?;
+            let name = fields.next().ok_or_else(|| invalid("expected a label"))?;
+
+            if fields.next().is_some() {
+                return Err(invalid("unexpected text after the label"));
+            }
+
+            let (bank, address) = location.split_once(':').ok_or_else(|| invalid("expected bank:address"))?;
+            let bank = usize::from_str_radix(bank, 16).map_err(|_| invalid("invalid bank"))?;
+            let address = u16::from_str_radix(address, 16).map_err(|_| invalid("invalid address"))?;
+
+            symbols.push(Symbol {
+                bank,
+                address,
+                name: name.to_owned(),
+            });
+        }
+
+        Ok(SymbolTable::new(symbols))
+    }
+
+    pub fn load(path: &Path) -> Result<SymbolTable, Error> {
+        SymbolTable::parse(&fs::read_to_string(path).map_err(Error::IoError)?)
+    }
+
+    pub fn new(mut symbols: Vec<Symbol>) -> SymbolTable {
+        symbols.sort_by_key(|symbol| symbol.address);
+
+        let mut names = HashMap::new();
+
+        for (index, symbol) in symbols.iter().enumerate() {
+            // The first definition wins if a name is somehow repeated
+            names.entry(symbol.name.clone()).or_insert(index);
+        }
+
+        SymbolTable { symbols, names }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.symbols.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Symbol> {
+        self.symbols.iter()
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Symbol> {
+        self.names.get(name).map(|&index| &self.symbols[index])
+    }
+
+    /// Resolves an address written as a number (see [`parse_number`]), a label, or a label with an offset such as
+    /// `EntryPoint.waitForHarness_loop+4`. Labels take precedence over numbers that look the same.
+    pub fn resolve(&self, text: &str) -> Result<u16, Error> {
+        let text = text.trim();
+
+        if let Some(symbol) = self.get(text) {
+            return Ok(symbol.address);
+        }
+
+        if let Some(address) = parse_number(text) {
+            return Ok(address);
+        }
+
+        if let Some(index) = text.rfind(['+', '-']) {
+            let (name, offset) = text.split_at(index);
+            let (sign, offset) = offset.split_at(1);
+
+            if let (Some(symbol), Some(offset)) = (self.get(name.trim()), parse_number(offset.trim())) {
+                return Ok(match sign {
+                    "-" => symbol.address.wrapping_sub(offset),
+                    _ => symbol.address.wrapping_add(offset),
+                });
+            }
+        }
+
+        Err(Error::UnknownSymbol(text.to_owned()))
+    }
+
+    /// Returns the closest label at or before an address, in the same memory area, with the offset from it. `bank`
+    /// is the bank mapped at the address if it's in banked memory, labels of other banks are skipped.
+    pub fn lookup(&self, address: u16, bank: Option<usize>) -> Option<(&Symbol, u16)> {
+        let area = area_start(address);
+        let end = self.symbols.partition_point(|symbol| symbol.address <= address);
+
+        let matches = |symbol: &Symbol| symbol.address >= area && bank.is_none_or(|bank| symbol.bank == bank);
+
+        let mut found = self.symbols[..end].iter().rposition(&matches)?;
+
+        // Prefer the first label of the file among those at the same address, usually the parent of the others
+        while found > 0
+            && self.symbols[found - 1].address == self.symbols[found].address
+            && matches(&self.symbols[found - 1])
+        {
+            found -= 1;
+        }
+
+        let symbol = &self.symbols[found];
+
+        Some((symbol, address - symbol.address))
+    }
+
+    /// Returns the labels at exactly an address, in the order of the file. `bank` is as for [`SymbolTable::lookup`].
+    pub fn labels_at(&self, address: u16, bank: Option<usize>) -> impl Iterator<Item = &Symbol> {
+        let start = self.symbols.partition_point(|symbol| symbol.address < address);
+
+        self.symbols[start..]
+            .iter()
+            .take_while(move |symbol| symbol.address == address)
+            .filter(move |symbol| bank.is_none_or(|bank| symbol.bank == bank))
+    }
+
+    /// Formats an address as `label+offset`, or as `$XXXX` when there is no label for it.
+    pub fn symbolize(&self, address: u16, bank: Option<usize>) -> String {
+        match self.lookup(address, bank) {
+            Some((symbol, 0)) => symbol.name.clone(),
+            Some((symbol, offset)) => format!("{}+${:X}", symbol.name, offset),
+            None => format!("${:04X}", address),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn numbers_are_decimal_without_a_prefix() {
+        assert_eq!(parse_number("2000"), Some(2000));
+        assert_eq!(parse_number("$2000"), Some(0x2000));
+        assert_eq!(parse_number("0x2000"), Some(0x2000));
+        assert_eq!(parse_number("2000h"), Some(0x2000));
+        assert_eq!(parse_number("%1010"), Some(0b1010));
+        assert_eq!(parse_number("07D0"), None);
+        assert_eq!(parse_number("70000"), None);
+        assert_eq!(parse_number("$"), None);
+    }
+
+    #[test]
+    fn resolve_labels_and_offsets() {
+        let symbols = SymbolTable::parse("00:0150 Start\n00:0150 Start.loop ; local\n").unwrap();
+
+        assert_eq!(symbols.resolve("Start").unwrap(), 0x0150);
+        assert_eq!(symbols.resolve("Start.loop+16").unwrap(), 0x0160);
+        assert_eq!(symbols.resolve("Start - $10").unwrap(), 0x0140);
+        assert_eq!(symbols.resolve("336").unwrap(), 0x0150);
+        assert!(symbols.resolve("Stop").is_err());
+    }
+}
@@ -0,0 +1,164 @@
+use crate::error::Error;
+use crate::savestate::{SaveState, StateReader, StateWriter};
+
+/// The DIV/TIMA/TMA/TAC timer (FF04h - FF07h).
+#[derive(Debug, Clone, Default)]
+pub struct Timer {
+    /// Internal 16-bit counter incremented every T-cycle, DIV is its upper byte.
+    counter: u16,
+    tima: u8,
+    tma: u8,
+    tac: u8,
+    /// Set for the M-cycle following a TIMA overflow, during which TIMA reads 00h before being reloaded.
+    reload_pending: bool,
+}
+
+impl Timer {
+    /// Sets the internal counter, DIV being its upper byte.
+    pub fn set_counter(&mut self, counter: u16) {
+        self.counter = counter;
+    }
+
+    /// Returns the bit of the internal counter whose falling edge increments TIMA, if the timer is enabled.
+    fn selected_bit(&self) -> bool {
+        let bit = match self.tac & 0x03 {
+            0x00 => 9,
+            0x01 => 3,
+            0x02 => 5,
+            _ => 7,
+        };
+
+        self.tac & 0x04 != 0 && self.counter & (1 << bit) != 0
+    }
+
+    fn increment_tima(&mut self) {
+        let (tima, overflow) = self.tima.overflowing_add(1);
+        self.tima = tima;
+        self.reload_pending = overflow;
+    }
+
+    /// Advances the timer by `cycles` T-cycles, returns whether the timer interrupt should be requested.
+    pub fn tick(&mut self, cycles: u32) -> bool {
+        let mut interrupt = false;
+
+        for _ in 0..cycles / 4 {
+            if self.reload_pending {
+                self.reload_pending = false;
+                self.tima = self.tma;
+                interrupt = true;
+            }
+
+            let old_bit = self.selected_bit();
+            self.counter = self.counter.wrapping_add(4);
+
+            if old_bit && !self.selected_bit() {
+                self.increment_tima();
+            }
+        }
+
+        interrupt
+    }
+
+    pub fn read(&self, address: u16) -> u8 {
+        match address {
+            0xFF04 => (self.counter >> 8) as u8,
+            0xFF05 => self.tima,
+            0xFF06 => self.tma,
+            _ => self.tac | 0xF8,
+        }
+    }
+
+    pub fn write(&mut self, address: u16, value: u8) {
+        let old_bit = self.selected_bit();
+
+        match address {
+            0xFF04 => self.counter = 0,
+            0xFF05 => {
+                // Writing TIMA while it's about to be reloaded cancels the reload
+                self.tima = value;
+                self.reload_pending = false;
+            },
+            0xFF06 => self.tma = value,
+            _ => self.tac = value & 0x07,
+        }
+
+        // Resetting DIV or changing TAC can produce a falling edge on the selected bit
+        if old_bit && !self.selected_bit() {
+            self.increment_tima();
+        }
+    }
+}
+
+impl SaveState for Timer {
+    fn save_state(&self, writer: &mut StateWriter) {
+        writer.u16(self.counter);
+        writer.u8(self.tima);
+        writer.u8(self.tma);
+        writer.u8(self.tac);
+        writer.bool(self.reload_pending);
+    }
+
+    fn load_state(&mut self, reader: &mut StateReader) -> Result<(), Error> {
+        self.counter = reader.u16()?;
+        self.tima = reader.u8()?;
+        self.tma = reader.u8()?;
+        self.tac = reader.u8()?;
+        self.reload_pending = reader.bool()?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A timer counting every 16 T-cycles, on the falling edge of bit 3.
+    fn fast_timer() -> Timer {
+        let mut timer = Timer::default();
+        timer.write(0xFF07, 0x05);
+        timer
+    }
+
+    #[test]
+    fn tima_increments_on_the_falling_edge() {
+        let mut timer = fast_timer();
+
+        timer.tick(12);
+        assert_eq!(timer.read(0xFF05), 0);
+        timer.tick(4);
+        assert_eq!(timer.read(0xFF05), 1);
+        timer.tick(160);
+        assert_eq!(timer.read(0xFF05), 11);
+    }
+
+    #[test]
+    fn resetting_div_or_disabling_the_timer_can_increment_tima() {
+        let mut timer = fast_timer();
+
+        // Bit 3 is set from 8 to 15
+        timer.tick(8);
+        timer.write(0xFF04, 0x00);
+        assert_eq!(timer.read(0xFF05), 1);
+
+        timer.tick(4);
+        timer.write(0xFF04, 0x00);
+        assert_eq!(timer.read(0xFF05), 1);
+
+        timer.tick(8);
+        timer.write(0xFF07, 0x01);
+        assert_eq!(timer.read(0xFF05), 2);
+    }
+
+    #[test]
+    fn tima_reloads_one_m_cycle_after_overflowing() {
+        let mut timer = fast_timer();
+        timer.write(0xFF06, 0xAB);
+        timer.write(0xFF05, 0xFF);
+
+        assert!(!timer.tick(16));
+        assert_eq!(timer.read(0xFF05), 0x00);
+        assert!(timer.tick(4));
+        assert_eq!(timer.read(0xFF05), 0xAB);
+    }
+}
@@ -0,0 +1,248 @@
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufWriter, Read, Write};
+use std::path::Path;
+
+use crate::disasm::Disassembler;
+use crate::error::Error;
+use crate::gb::{Gameboy, GameboyRegisters};
+
+/// Identifies a binary trace file.
+const MAGIC: &[u8; 4] = b"GBTR";
+
+/// Version of the binary trace format, to be bumped whenever the layout of a record changes.
+pub const VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TraceFormat {
+    /// Gameboy Doctor lines, `A:01 F:B0 B:00 C:13 D:00 E:D8 H:01 L:4D SP:FFFE PC:0100 PCMEM:00,C3,13,02`, to be
+    /// compared with reference logs line by line.
+    Doctor,
+    /// Gameboy Doctor lines followed by the symbolized PC and the disassembled instruction.
+    Annotated,
+    /// Records of [`TraceRecord::SIZE`] bytes after a header, for long runs.
+    Binary,
+}
+
+/// The CPU state right before an instruction is executed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TraceRecord {
+    pub registers: GameboyRegisters,
+    /// The 4 bytes at PC.
+    pub pcmem: [u8; 4],
+}
+
+impl TraceRecord {
+    /// Size of a binary record: AF, BC, DE, HL, SP and PC as little-endian words, so F comes before A, then the 4
+    /// bytes of PCMEM.
+    pub const SIZE: usize = 16;
+
+    pub fn capture(gb: &Gameboy) -> Self {
+        let pc = gb.registers.pc;
+        let mut pcmem = [0; 4];
+
+        for (offset, byte) in pcmem.iter_mut().enumerate() {
+            *byte = gb.bus.peek(pc.wrapping_add(offset as u16));
+        }
+
+        TraceRecord {
+            registers: gb.registers,
+            pcmem,
+        }
+    }
+
+    pub fn to_bytes(&self) -> [u8; TraceRecord::SIZE] {
+        let registers = &self.registers;
+        let mut bytes = [0; TraceRecord::SIZE];
+
+        bytes[0..2].copy_from_slice(&registers.af.to_le_bytes());
+        bytes[2..4].copy_from_slice(&registers.bc.to_le_bytes());
+        bytes[4..6].copy_from_slice(&registers.de.to_le_bytes());
+        bytes[6..8].copy_from_slice(&registers.hl.to_le_bytes());
+        bytes[8..10].copy_from_slice(&registers.sp.to_le_bytes());
+        bytes[10..12].copy_from_slice(&registers.pc.to_le_bytes());
+        bytes[12..16].copy_from_slice(&self.pcmem);
+
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8; TraceRecord::SIZE]) -> Self {
+        TraceRecord {
+            registers: GameboyRegisters {
+                af: u16::from_le_bytes([bytes[0], bytes[1]]),
+                bc: u16::from_le_bytes([bytes[2], bytes[3]]),
+                de: u16::from_le_bytes([bytes[4], bytes[5]]),
+                hl: u16::from_le_bytes([bytes[6], bytes[7]]),
+                sp: u16::from_le_bytes([bytes[8], bytes[9]]),
+                pc: u16::from_le_bytes([bytes[10], bytes[11]]),
+            },
+            pcmem: [bytes[12], bytes[13], bytes[14], bytes[15]],
+        }
+    }
+}
+
+impl fmt::Display for TraceRecord {
+    /// Formats the record as a Gameboy Doctor line.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let registers = &self.registers;
+
+        write!(
+            f,
+            "A:{:02X} F:{:02X} B:{:02X} C:{:02X} D:{:02X} E:{:02X} H:{:02X} L:{:02X} SP:{:04X} PC:{:04X} \
+             PCMEM:{:02X},{:02X},{:02X},{:02X}",
+            registers.af >> 8,
+            registers.af & 0xFF,
+            registers.bc >> 8,
+            registers.bc & 0xFF,
+            registers.de >> 8,
+            registers.de & 0xFF,
+            registers.hl >> 8,
+            registers.hl & 0xFF,
+            registers.sp,
+            registers.pc,
+            self.pcmem[0],
+            self.pcmem[1],
+            self.pcmem[2],
+            self.pcmem[3],
+        )
+    }
+}
+
+/// Writes a line or a record per executed instruction, see [`Gameboy::set_tracer`].
+pub struct Tracer {
+    output: Box<dyn Write + Send>,
+    format: TraceFormat,
+    /// The first write error, after which nothing more is written.
+    error: Option<io::Error>,
+}
+
+impl fmt::Debug for Tracer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Tracer").field("format", &self.format).field("error", &self.error).finish()
+    }
+}
+
+impl Tracer {
+    pub fn new(output: impl Write + Send + 'static, format: TraceFormat) -> Self {
+        let mut tracer = Tracer {
+            output: Box::new(output),
+            format,
+            error: None,
+        };
+
+        if format == TraceFormat::Binary {
+            tracer.write(|output| {
+                output.write_all(MAGIC)?;
+                output.write_all(&VERSION.to_le_bytes())
+            });
+        }
+
+        tracer
+    }
+
+    /// Traces to a buffered file.
+    pub fn create(path: &Path, format: TraceFormat) -> Result<Self, Error> {
+        let file = File::create(path).map_err(Error::IoError)?;
+
+        Ok(Tracer::new(BufWriter::new(file), format))
+    }
+
+    fn write(&mut self, write: impl FnOnce(&mut dyn Write) -> io::Result<()>) {
+        if self.error.is_none() {
+            if let Err(error) = write(&mut self.output) {
+                self.error = Some(error);
+            }
+        }
+    }
+
+    /// Traces the instruction the CPU is about to execute.
+    pub fn trace(&mut self, gb: &Gameboy) {
+        let record = TraceRecord::capture(gb);
+
+        match self.format {
+            TraceFormat::Doctor => self.write(|output| writeln!(output, "{}", record)),
+            TraceFormat::Annotated => {
+                let pc = record.registers.pc;
+                let read = |address: u16| gb.bus.peek(address);
+                let instruction = Disassembler::for_gameboy(gb).instruction(&read, pc, 3);
+                let location = gb.symbolize(pc);
+
+                self.write(|output| writeln!(output, "{} ; {}: {}", record, location, instruction.text))
+            },
+            TraceFormat::Binary => self.write(|output| output.write_all(&record.to_bytes())),
+        }
+    }
+
+    /// Flushes the output, returns the first error the trace ran into.
+    pub fn finish(mut self) -> Result<(), Error> {
+        self.write(|output| output.flush());
+
+        match self.error {
+            Some(error) => Err(Error::IoError(error)),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Reads the records of a binary trace.
+pub struct TraceReader<R: Read> {
+    input: R,
+}
+
+impl<R: Read> TraceReader<R> {
+    /// Checks the header of the trace.
+    pub fn new(mut input: R) -> Result<Self, Error> {
+        let mut header = [0; 8];
+        input.read_exact(&mut header).map_err(|_| Error::InvalidTrace("not a binary trace"))?;
+
+        if &header[..4] != MAGIC {
+            return Err(Error::InvalidTrace("not a binary trace"));
+        }
+
+        let version = u32::from_le_bytes([header[4], header[5], header[6], header[7]]);
+
+        if version != VERSION {
+            return Err(Error::InvalidTrace("unsupported binary trace version"));
+        }
+
+        Ok(TraceReader { input })
+    }
+}
+
+impl<R: Read> Iterator for TraceReader<R> {
+    type Item = Result<TraceRecord, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut bytes = [0; TraceRecord::SIZE];
+        let mut filled = 0;
+
+        while filled < bytes.len() {
+            match self.input.read(&mut bytes[filled..]) {
+                Ok(0) if filled == 0 => return None,
+                Ok(0) => return Some(Err(Error::InvalidTrace("truncated record"))),
+                Ok(read) => filled += read,
+                Err(error) if error.kind() == io::ErrorKind::Interrupted => {},
+                Err(error) => return Some(Err(Error::IoError(error))),
+            }
+        }
+
+        Some(Ok(TraceRecord::from_bytes(&bytes)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_are_little_endian() {
+        let record = TraceRecord {
+            registers: GameboyRegisters { af: 0x01B0, bc: 0x0013, de: 0x00D8, hl: 0x014D, sp: 0xFFFE, pc: 0x0100 },
+            pcmem: [0x00, 0xC3, 0x50, 0x01],
+        };
+        let bytes = record.to_bytes();
+
+        assert_eq!(bytes, [0xB0, 0x01, 0x13, 0x00, 0xD8, 0x00, 0x4D, 0x01, 0xFE, 0xFF, 0x00, 0x01, 0x00, 0xC3, 0x50, 0x01]);
+        assert_eq!(TraceRecord::from_bytes(&bytes), record);
+    }
+}